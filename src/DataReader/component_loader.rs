@@ -1,16 +1,21 @@
+use log::{debug, info};
 use lru::LruCache;
+use prost::Message;
 
-use crate::component::Component;
+use crate::component::{Component, Declarations};
 use crate::xml_parser;
 use crate::DataReader::json_reader;
 use crate::DataReader::json_writer::component_to_json_file;
-use crate::DataReader::xml_parser::parse_xml_from_file;
+use crate::DataReader::xml_parser::{parse_query_file, parse_xml_from_file, query_file_path};
+use crate::DataTypes::SearchOrder;
 use crate::ModelObjects::queries::Query;
 use crate::ModelObjects::system_declarations::SystemDeclarations;
 use crate::ProtobufServer::services;
 use crate::ProtobufServer::services::query_request::Settings;
+use crate::ProtobufServer::services::QueryResponse;
 use crate::System::input_enabler;
-use std::collections::HashMap;
+use crate::System::limits::QueryLimits;
+use std::collections::{HashMap, HashSet};
 use std::num::NonZeroUsize;
 use std::sync::{Arc, Mutex};
 
@@ -18,25 +23,115 @@ use super::proto_reader::components_info_to_components;
 
 type ComponentsMap = HashMap<String, Component>;
 
+/// The options of the engine which are not in the protobuf [`Settings`], so they can only be set from the CLI
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EngineOptions {
+    /// The number of threads refinement is checked with
+    pub refinement_threads: usize,
+    /// The order of the search for refinement and reachability, where `None` is the default order of each search
+    pub search_order: Option<SearchOrder>,
+    /// The limits each query is stopped at
+    pub limits: QueryLimits,
+}
+
+impl Default for EngineOptions {
+    fn default() -> Self {
+        crate::DEFAULT_OPTIONS
+    }
+}
+
+/// The number of query responses cached for each model the cache can hold
+const RESPONSES_PER_MODEL: usize = 100;
+
 struct ComponentTuple {
     components_hash: u32,
     components_map: Arc<ComponentsMap>,
 }
 
-/// A struct used for caching the models.
+/// Identifies a query on a model with some settings, so the response to an identical query can be reused
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct QueryKey {
+    user_id: i32,
+    components_hash: u32,
+    /// The parsed query, so queries which are only written differently have the same key
+    query: String,
+    ignored_inputs: Vec<String>,
+    ignored_outputs: Vec<String>,
+    /// The protobuf encoding of the settings
+    settings: Vec<u8>,
+}
+
+impl QueryKey {
+    pub fn new(user_id: i32, components_hash: u32, query: &Query, settings: &Settings) -> Self {
+        fn sorted(actions: HashSet<String>) -> Vec<String> {
+            let mut actions: Vec<String> = actions.into_iter().collect();
+            actions.sort();
+            actions
+        }
+
+        QueryKey {
+            user_id,
+            components_hash,
+            query: format!("{:?}", query.query),
+            ignored_inputs: sorted(query.get_ignored_inputs()),
+            ignored_outputs: sorted(query.get_ignored_outputs()),
+            settings: settings.encode_to_vec(),
+        }
+    }
+}
+
+/// The query responses cached by a [ModelCache]
+struct ResponseCache {
+    responses: LruCache<QueryKey, QueryResponse>,
+    /// The hash of the model each user last queried
+    model_hashes: HashMap<i32, u32>,
+    hits: u64,
+    misses: u64,
+}
+
+impl ResponseCache {
+    fn new(capacity: usize) -> Self {
+        ResponseCache {
+            responses: LruCache::new(NonZeroUsize::new(capacity).unwrap()),
+            model_hashes: HashMap::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Removes the responses of the user to queries on other models than the one with `components_hash`
+    fn invalidate(&mut self, user_id: i32, components_hash: u32) {
+        let outdated: Vec<QueryKey> = self
+            .responses
+            .iter()
+            .map(|(key, _)| key)
+            .filter(|key| key.user_id == user_id && key.components_hash != components_hash)
+            .cloned()
+            .collect();
+        if !outdated.is_empty() {
+            debug!(
+                "Invalidating {} cached responses of user {}",
+                outdated.len(),
+                user_id
+            );
+        }
+        for key in outdated {
+            self.responses.pop(&key);
+        }
+    }
+}
+
+/// A struct used for caching the models, and the responses to the queries on them.
 #[derive(Debug, Clone)]
 pub struct ModelCache {
     // TODO: A concurrent lru may be faster to use and cause less prone to lock contention.
     cache: Arc<Mutex<LruCache<i32, ComponentTuple>>>,
+    responses: Arc<Mutex<ResponseCache>>,
 }
 
 impl Default for ModelCache {
     fn default() -> Self {
-        Self {
-            cache: Arc::new(Mutex::new(LruCache::<i32, ComponentTuple>::new(
-                NonZeroUsize::new(100).unwrap(),
-            ))),
-        }
+        Self::new(100)
     }
 }
 
@@ -51,9 +146,56 @@ impl ModelCache {
             cache: Arc::new(Mutex::new(LruCache::<i32, ComponentTuple>::new(
                 NonZeroUsize::new(cache_size).unwrap(),
             ))),
+            responses: Arc::new(Mutex::new(ResponseCache::new(
+                cache_size * RESPONSES_PER_MODEL,
+            ))),
         }
     }
 
+    /// A method that returns the cached response to an identical query on the same model, if any.
+    /// The responses of a user are invalidated when the hash of the model they query changes.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the query
+    pub fn get_response(&self, key: &QueryKey) -> Option<QueryResponse> {
+        let mut cache = self.responses.lock().unwrap();
+        if cache.model_hashes.insert(key.user_id, key.components_hash) != Some(key.components_hash)
+        {
+            cache.invalidate(key.user_id, key.components_hash);
+        }
+
+        let response = cache.responses.get(key).cloned();
+        if response.is_some() {
+            cache.hits += 1;
+        } else {
+            cache.misses += 1;
+        }
+        info!(
+            "Query response cache {}: {} hits, {} misses",
+            if response.is_some() { "hit" } else { "miss" },
+            cache.hits,
+            cache.misses
+        );
+        response
+    }
+
+    /// A method that inserts the response to a query into the cache.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key of the query
+    /// * `response` - The response to the query
+    pub fn insert_response(&self, key: QueryKey, response: QueryResponse) {
+        self.responses.lock().unwrap().responses.put(key, response);
+    }
+
+    /// Returns the number of hits and misses of the cached query responses
+    pub fn response_stats(&self) -> (u64, u64) {
+        let cache = self.responses.lock().unwrap();
+        (cache.hits, cache.misses)
+    }
+
     /// A Method that returns the model from the cache.
     ///
     /// # Arguments
@@ -103,12 +245,15 @@ pub trait ComponentLoader {
     fn get_component(&mut self, component_name: &str) -> &Component;
     fn save_component(&mut self, component: Component);
     fn get_settings(&self) -> &Settings;
+    fn get_options(&self) -> &EngineOptions;
+    fn set_options(&mut self, options: EngineOptions);
 }
 
 #[derive(Debug, Default, Clone)]
 pub struct ComponentContainer {
     pub loaded_components: Arc<ComponentsMap>,
     settings: Option<Settings>,
+    options: EngineOptions,
 }
 
 impl ComponentLoader for ComponentContainer {
@@ -126,6 +271,14 @@ impl ComponentLoader for ComponentContainer {
     fn get_settings(&self) -> &Settings {
         self.settings.as_ref().unwrap()
     }
+
+    fn get_options(&self) -> &EngineOptions {
+        &self.options
+    }
+
+    fn set_options(&mut self, options: EngineOptions) {
+        self.options = options;
+    }
 }
 
 impl ComponentContainer {
@@ -133,6 +286,7 @@ impl ComponentContainer {
         ComponentContainer {
             loaded_components: map,
             settings: None,
+            options: EngineOptions::default(),
         }
     }
 
@@ -175,7 +329,7 @@ pub fn parse_components_if_some(
     if let Some(rep) = &proto_component.rep {
         match rep {
             services::component::Rep::Json(json) => parse_json_component(json),
-            services::component::Rep::Xml(xml) => Ok(parse_xml_components(xml)),
+            services::component::Rep::Xml(xml) => parse_xml_components(xml),
         }
     } else {
         Ok(vec![])
@@ -185,15 +339,21 @@ pub fn parse_components_if_some(
 fn parse_json_component(json: &str) -> Result<Vec<Component>, tonic::Status> {
     match json_reader::json_to_component(json) {
         Ok(comp) => Ok(vec![comp]),
-        Err(_) => Err(tonic::Status::invalid_argument(
-            "Failed to parse json component",
-        )),
+        Err(err) => Err(tonic::Status::invalid_argument(format!(
+            "Failed to parse json component: {}",
+            err
+        ))),
     }
 }
 
-fn parse_xml_components(xml: &str) -> Vec<Component> {
-    let (comps, _, _) = xml_parser::parse_xml_from_str(xml);
-    comps
+fn parse_xml_components(xml: &str) -> Result<Vec<Component>, tonic::Status> {
+    match xml_parser::parse_xml_from_str(xml) {
+        Ok((comps, _, _)) => Ok(comps),
+        Err(err) => Err(tonic::Status::invalid_argument(format!(
+            "Failed to parse xml components: {}",
+            err
+        ))),
+    }
 }
 
 pub trait ProjectLoader: ComponentLoader {
@@ -207,8 +367,10 @@ pub struct JsonProjectLoader {
     project_path: String,
     loaded_components: ComponentsMap,
     system_declarations: SystemDeclarations,
+    global_declarations: Declarations,
     queries: Vec<Query>,
     settings: Settings,
+    options: EngineOptions,
 }
 
 impl ComponentLoader for JsonProjectLoader {
@@ -233,6 +395,14 @@ impl ComponentLoader for JsonProjectLoader {
     fn get_settings(&self) -> &Settings {
         &self.settings
     }
+
+    fn get_options(&self) -> &EngineOptions {
+        &self.options
+    }
+
+    fn set_options(&mut self, options: EngineOptions) {
+        self.options = options;
+    }
 }
 
 impl ProjectLoader for JsonProjectLoader {
@@ -254,22 +424,52 @@ impl ProjectLoader for JsonProjectLoader {
 }
 
 impl JsonProjectLoader {
+    /// Loads the project with the channels in its global declarations synchronising as usual,
+    /// even the ones declared `broadcast chan`
     #[allow(clippy::new_ret_no_self)]
     pub fn new(project_path: String, settings: Settings) -> Box<dyn ProjectLoader> {
+        Self::load(project_path, settings, false)
+    }
+
+    /// Loads the project with broadcast semantics for the channels declared `broadcast chan` in its global declarations,
+    /// so an output on them does not wait for the other components of a composition to receive it
+    pub fn with_broadcast_channels(
+        project_path: String,
+        settings: Settings,
+    ) -> Box<dyn ProjectLoader> {
+        Self::load(project_path, settings, true)
+    }
+
+    fn load(
+        project_path: String,
+        settings: Settings,
+        broadcast_channels: bool,
+    ) -> Box<dyn ProjectLoader> {
         let system_declarations = json_reader::read_system_declarations(&project_path).unwrap();
         let queries = json_reader::read_queries(&project_path).unwrap();
+        let mut global_declarations = json_reader::read_global_declarations(&project_path)
+            .map(|global| global.declarations)
+            .unwrap_or_else(Declarations::empty);
+        if !broadcast_channels {
+            global_declarations.broadcast_channels.clear();
+        }
 
         Box::new(JsonProjectLoader {
             project_path,
             loaded_components: HashMap::new(),
             system_declarations,
+            global_declarations,
             queries,
             settings,
+            options: EngineOptions::default(),
         })
     }
 
     fn load_component(&mut self, component_name: &str) {
         let mut component = json_reader::read_json_component(&self.project_path, component_name);
+        component
+            .declarations
+            .add_global_declarations(&self.global_declarations);
 
         component.create_edge_io_split();
 
@@ -295,6 +495,7 @@ pub struct XmlProjectLoader {
     system_declarations: SystemDeclarations,
     queries: Vec<Query>,
     settings: Settings,
+    options: EngineOptions,
 }
 
 impl ComponentLoader for XmlProjectLoader {
@@ -313,6 +514,14 @@ impl ComponentLoader for XmlProjectLoader {
     fn get_settings(&self) -> &Settings {
         &self.settings
     }
+
+    fn get_options(&self) -> &EngineOptions {
+        &self.options
+    }
+
+    fn set_options(&mut self, options: EngineOptions) {
+        self.options = options;
+    }
 }
 
 impl ProjectLoader for XmlProjectLoader {
@@ -336,7 +545,9 @@ impl ProjectLoader for XmlProjectLoader {
 impl XmlProjectLoader {
     #[allow(clippy::new_ret_no_self)]
     pub fn new(project_path: String, settings: Settings) -> Box<dyn ProjectLoader> {
-        let (comps, system_declarations, queries) = parse_xml_from_file(&project_path);
+        let (comps, system_declarations, mut queries) = parse_xml_from_file(&project_path)
+            .unwrap_or_else(|err| panic!("Could not parse {}: {}", project_path, err));
+        queries.extend(parse_query_file(&query_file_path(&project_path)));
 
         let mut map = HashMap::<String, Component>::new();
         for mut component in comps {
@@ -357,6 +568,7 @@ impl XmlProjectLoader {
             system_declarations,
             queries,
             settings,
+            options: EngineOptions::default(),
         })
     }
 }
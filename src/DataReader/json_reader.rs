@@ -1,6 +1,6 @@
 use crate::ModelObjects::component;
 use crate::ModelObjects::queries;
-use crate::ModelObjects::system_declarations::SystemDeclarations;
+use crate::ModelObjects::system_declarations::{GlobalDeclarations, SystemDeclarations};
 use serde::de::DeserializeOwned;
 use std::fs::File;
 use std::io::Read;
@@ -26,6 +26,26 @@ pub fn read_system_declarations(project_path: &str) -> Option<SystemDeclarations
     }
 }
 
+pub fn read_global_declarations(project_path: &str) -> Option<GlobalDeclarations> {
+    let global_path = format!(
+        "{}{}GlobalDeclarations.json",
+        project_path,
+        std::path::MAIN_SEPARATOR
+    );
+
+    if !Path::new(&global_path).exists() {
+        return None;
+    }
+
+    match read_json::<GlobalDeclarations>(&global_path) {
+        Ok(global_decls) => Some(global_decls),
+        Err(error) => panic!(
+            "We got error {}, and could not parse json file {} to global declarations",
+            error, &global_path
+        ),
+    }
+}
+
 pub fn read_json_component(project_path: &str, component_name: &str) -> component::Component {
     let component_path = format!(
         "{0}{1}Components{1}{2}.json",
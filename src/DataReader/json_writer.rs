@@ -1,4 +1,7 @@
 use crate::ModelObjects::component::Component;
+use crate::System::refine::RefinementRelation;
+use crate::System::strategy::{ConsistencyStrategy, StrategyMove};
+use serde::Serialize;
 use std::fs::File;
 
 pub fn component_to_json_file(project_path: &str, component: &Component) {
@@ -16,3 +19,70 @@ pub fn component_to_json_file(project_path: &str, component: &Component) {
 pub fn component_to_json(component: &Component) -> String {
     serde_json::to_string(component).unwrap()
 }
+
+/// A pair of locations in a refinement relation, as written to json
+#[derive(Serialize)]
+struct RelationPairJson {
+    locations1: String,
+    values1: Vec<i32>,
+    locations2: String,
+    values2: Vec<i32>,
+    federation: String,
+}
+
+pub fn relation_to_json_file(path: &str, relation: &RefinementRelation) {
+    let pairs: Vec<RelationPairJson> = relation
+        .pairs
+        .iter()
+        .map(|pair| RelationPairJson {
+            locations1: pair.locations1.0.to_string(),
+            values1: pair.locations1.1.clone(),
+            locations2: pair.locations2.0.to_string(),
+            values2: pair.locations2.1.clone(),
+            federation: relation.format_federation(&pair.federation),
+        })
+        .collect();
+    let file = File::create(path).expect("Couldnt open file");
+
+    serde_json::to_writer_pretty(&file, &pairs).expect("Failed to serialize relation");
+}
+
+/// A zone of a location in a consistency strategy, as written to json
+#[derive(Serialize)]
+struct StrategyEntryJson {
+    location: String,
+    values: Vec<i32>,
+    federation: String,
+    /// Either "delay" or the output action to take
+    action: String,
+    /// The edge of the output, if any
+    transition: Option<String>,
+}
+
+pub fn strategy_to_json(strategy: &ConsistencyStrategy) -> String {
+    let entries: Vec<StrategyEntryJson> = strategy
+        .sorted_entries()
+        .into_iter()
+        .map(|((location, values), entry)| {
+            let (action, transition) = match &entry.strategy_move {
+                StrategyMove::Delay => ("delay".to_string(), None),
+                StrategyMove::Output { action, transition } => {
+                    (action.clone(), Some(transition.to_string()))
+                }
+            };
+            StrategyEntryJson {
+                location: location.to_string(),
+                values: values.clone(),
+                federation: strategy.format_federation(&entry.federation),
+                action,
+                transition,
+            }
+        })
+        .collect();
+
+    serde_json::to_string_pretty(&entries).expect("Failed to serialize strategy")
+}
+
+pub fn strategy_to_json_file(path: &str, strategy: &ConsistencyStrategy) {
+    std::fs::write(path, strategy_to_json(strategy)).expect("Couldnt write file");
+}
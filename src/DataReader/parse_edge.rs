@@ -136,8 +136,8 @@ fn build_assignments_from_pair(pair: pest::iterators::Pair<Rule>) -> Vec<Update>
             let assignment_pair = pairs.next().unwrap();
             match assignment_pair.as_rule() {
                 Rule::assignment => {
-                    updates = build_assignments_from_pair(pairs.next().unwrap());
                     updates.push(build_assignment_from_pair(assignment_pair));
+                    updates.extend(build_assignments_from_pair(pairs.next().unwrap()));
                 }
                 Rule::finalAssignment => {
                     updates.push(build_assignment_from_pair(assignment_pair));
@@ -259,6 +259,9 @@ fn build_compareExpr_from_pair(pair: pest::iterators::Pair<Rule>) -> BoolExpress
                 BoolExpression::Bool(left_side_pair.as_str().trim().parse::<bool>().unwrap())
             }
             Rule::terms => build_expression_from_pair(left_side_pair),
+            Rule::sub_add => {
+                BoolExpression::Arithmetic(Box::new(build_sub_add_from_pair(left_side_pair)))
+            }
             err => panic!("Unable to match: {:?} as rule atom or variable", err),
         },
         Some(operator) => {
@@ -17,13 +17,7 @@ pub struct QueryParser;
 
 pub fn parse_to_query(query: &str) -> Vec<Query> {
     let queries = parse_to_expression_tree(query).unwrap();
-    queries
-        .into_iter()
-        .map(|q| Query {
-            query: Option::from(q),
-            comment: "".to_string(),
-        })
-        .collect()
+    queries.into_iter().map(|q| Query::new(q, "")).collect()
 }
 
 pub fn parse_to_expression_tree(edge_attribute_str: &str) -> Result<Vec<QueryExpression>, String> {
@@ -72,7 +66,7 @@ pub fn build_query_from_pair(pair: pest::iterators::Pair<Rule>) -> QueryExpressi
 
     match pair.as_rule() {
         Rule::refinement => build_refinement_from_pair(pair),
-        Rule::reachability => build_reachability_from_pair(pair),
+        Rule::reachability | Rule::fastestReachability => build_reachability_from_pair(pair),
         Rule::getComponent => {
             let inner_pair = pair.into_inner().next().unwrap();
             QueryExpression::GetComponent(Box::new(build_expression_from_pair(inner_pair)))
@@ -97,10 +91,27 @@ pub fn build_query_from_pair(pair: pest::iterators::Pair<Rule>) -> QueryExpressi
             let inner_pair = pair.into_inner().next().unwrap();
             QueryExpression::Determinism(Box::new(build_expression_from_pair(inner_pair)))
         }
+        Rule::deadlock => {
+            let inner_pair = pair.into_inner().next().unwrap();
+            QueryExpression::Deadlock(Box::new(build_expression_from_pair(inner_pair)))
+        }
+        Rule::timelock => {
+            let inner_pair = pair.into_inner().next().unwrap();
+            QueryExpression::Timelock(Box::new(build_expression_from_pair(inner_pair)))
+        }
+        Rule::nonZeno => {
+            let inner_pair = pair.into_inner().next().unwrap();
+            QueryExpression::NonZeno(Box::new(build_expression_from_pair(inner_pair)))
+        }
+        Rule::strategy => {
+            let inner_pair = pair.into_inner().next().unwrap();
+            QueryExpression::Strategy(Box::new(build_expression_from_pair(inner_pair)))
+        }
         Rule::specification => {
             let inner_pair = pair.into_inner().next().unwrap();
             QueryExpression::Specification(Box::new(build_expression_from_pair(inner_pair)))
         }
+        Rule::systemFormula => build_system_formula_from_pair(pair),
         Rule::logicFormulas => {
             let inner_pair = pair.into_inner().next().unwrap();
             build_expression_from_pair(inner_pair)
@@ -205,22 +216,53 @@ fn build_state_from_pair(pair: pest::iterators::Pair<Rule>) -> QueryExpression {
     }
 }
 
+/// Builds the end states of a reachability query. Several states become a disjunction with [QueryExpression::OrOp].
+fn build_targets_from_pair(pair: pest::iterators::Pair<Rule>) -> QueryExpression {
+    pair.into_inner()
+        .map(build_state_from_pair)
+        .reduce(|left, right| QueryExpression::OrOp(Box::new(left), Box::new(right)))
+        .unwrap()
+}
+
 fn build_reachability_from_pair(pair: pest::iterators::Pair<Rule>) -> QueryExpression {
+    let rule = pair.as_rule();
     let mut inner_pair = pair.into_inner();
     let automata = build_expression_from_pair(inner_pair.next().unwrap());
-    let state = build_state_from_pair(inner_pair.next().unwrap());
+    let pair = inner_pair.next().unwrap();
 
-    if let Some(pair) = inner_pair.next() {
-        QueryExpression::Reachability(
-            Box::new(automata),
-            Box::new(Some(state)),
-            Box::new(build_state_from_pair(pair)),
+    let (start, end) = if let Some(targets) = inner_pair.next() {
+        (
+            Some(build_state_from_pair(pair)),
+            build_targets_from_pair(targets),
         )
     } else {
-        QueryExpression::Reachability(Box::new(automata), Box::new(None), Box::new(state))
+        (None, build_targets_from_pair(pair))
+    };
+
+    match rule {
+        Rule::fastestReachability => {
+            QueryExpression::FastestReachability(Box::new(automata), Box::new(start), Box::new(end))
+        }
+        _ => QueryExpression::Reachability(Box::new(automata), Box::new(start), Box::new(end)),
     }
 }
 
+fn build_system_formula_from_pair(pair: pest::iterators::Pair<Rule>) -> QueryExpression {
+    let mut inner_pair = pair.into_inner();
+    let system_pair = inner_pair.next().unwrap();
+    let formula_pair = inner_pair.next().unwrap().into_inner().next().unwrap();
+
+    let system = match system_pair.as_rule() {
+        Rule::variable => QueryExpression::VarName(system_pair.as_str().trim().to_string()),
+        _ => build_expression_from_pair(system_pair),
+    };
+
+    QueryExpression::ComponentExpression(
+        Box::new(system),
+        Box::new(build_expression_from_pair(formula_pair)),
+    )
+}
+
 fn build_refinement_from_pair(pair: pest::iterators::Pair<Rule>) -> QueryExpression {
     let mut inner_pair = pair.into_inner();
     let left_side_pair = inner_pair.next().unwrap();
@@ -1,13 +1,12 @@
 use crate::DataReader::parse_edge;
 use crate::DataReader::parse_invariant;
 use crate::ModelObjects::component::{
-    Component, Declarations, Edge, Location, LocationType, SyncType,
+    Component, Declarations, Edge, Location, LocationType, SyncType, DEFAULT_INT_RANGE,
 };
 use crate::ModelObjects::representations;
 use crate::Simulation::graph_layout::layout_dummy_component;
 use edbm::util::constraints::ClockIndex;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use std::collections::HashMap;
 use std::ops::Add;
 
 #[derive(Serialize)]
@@ -184,8 +183,7 @@ where
     let s = String::deserialize(deserializer)?;
     //Split string into vector of strings
     let decls: Vec<String> = s.split('\n').map(|s| s.into()).collect();
-    let mut ints: HashMap<String, i32> = HashMap::new();
-    let mut clocks: HashMap<String, ClockIndex> = HashMap::new();
+    let mut declarations = Declarations::empty();
     let mut counter: ClockIndex = 1;
     for string in decls {
         //skip comments
@@ -205,27 +203,120 @@ where
                             split_str.split(',').map(|s| s.into()).collect();
                         for var in comma_split {
                             if !var.is_empty() {
-                                clocks.insert(var, counter);
+                                declarations.clocks.insert(var, counter);
                                 counter += 1;
                             }
                         }
                     }
-                } else if variable_type == "int" {
-                    for split_str in split_string.iter().skip(1) {
-                        let comma_split: Vec<String> =
-                            split_str.split(',').map(|s| s.into()).collect();
-                        for var in comma_split {
-                            ints.insert(var, 0);
+                } else if is_data_declaration(variable_type) {
+                    decode_data_declaration(&sub_decl, &mut declarations)
+                        .map_err(serde::de::Error::custom)?;
+                } else if variable_type == "broadcast" {
+                    for split_str in split_string.iter().skip(2) {
+                        for channel in split_str.split(',') {
+                            if !channel.is_empty() {
+                                declarations.broadcast_channels.insert(channel.to_string());
+                            }
                         }
                     }
+                } else if variable_type == "chan" || variable_type == "typedef" {
+                    // Binary channels synchronise like any other action, and type names are not used
+                    continue;
                 } else {
-                    panic!("Not implemented read for type: \"{}\"", variable_type);
+                    return Err(serde::de::Error::custom(format!(
+                        "Not implemented read for type: \"{}\"",
+                        variable_type
+                    )));
                 }
             }
         }
     }
 
-    Ok(Declarations { ints, clocks })
+    Ok(declarations)
+}
+
+/// Whether a declaration starting with `variable_type` declares ints, bools or constants
+pub fn is_data_declaration(variable_type: &str) -> bool {
+    matches!(variable_type, "int" | "bool" | "const") || variable_type.starts_with("int[")
+}
+
+/// Reads a declaration of ints, bools or constants, such as `const int N = 5`, `int[0,N] n = 3` or `bool b`.
+/// Variables without a value start at 0, and bools are ints within the range [0,1]
+pub fn decode_data_declaration(declaration: &str, decls: &mut Declarations) -> Result<(), String> {
+    let declaration = declaration.trim();
+    let (is_const, declaration) = match declaration.strip_prefix("const ") {
+        Some(rest) => (true, rest.trim_start()),
+        None => (false, declaration),
+    };
+
+    let (range, variables) = if let Some(rest) = declaration.strip_prefix("bool") {
+        (Some((0, 1)), rest)
+    } else if let Some(rest) = declaration.strip_prefix("int") {
+        match rest.trim_start().strip_prefix('[') {
+            Some(rest) => {
+                let (bounds, variables) = rest
+                    .split_once(']')
+                    .ok_or_else(|| format!("Missing \"]\" in declaration \"{}\"", declaration))?;
+                let (min, max) = bounds
+                    .split_once(',')
+                    .ok_or_else(|| format!("Could not read the range of \"{}\"", declaration))?;
+                (
+                    Some((
+                        decode_data_value(min, decls)?,
+                        decode_data_value(max, decls)?,
+                    )),
+                    variables,
+                )
+            }
+            None => (None, rest),
+        }
+    } else {
+        return Err(format!(
+            "Not implemented read for type: \"{}\"",
+            declaration
+        ));
+    };
+
+    for var in variables.split(',') {
+        let (name, value) = match var.split_once('=') {
+            Some((name, value)) => (name.trim(), decode_data_value(value, decls)?),
+            None => (var.trim(), 0),
+        };
+        if name.is_empty() {
+            continue;
+        }
+
+        let (min, max) = range.unwrap_or(DEFAULT_INT_RANGE);
+        if value < min || value > max {
+            return Err(format!(
+                "The value {} of \"{}\" is outside its range [{},{}]",
+                value, name, min, max
+            ));
+        }
+
+        if is_const {
+            decls.consts.insert(name.to_string(), value);
+        } else {
+            decls.ints.insert(name.to_string(), value);
+            if let Some(range) = range {
+                decls.ranges.insert(name.to_string(), range);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Reads an int literal, `true`, `false` or a constant declared earlier
+fn decode_data_value(value: &str, decls: &Declarations) -> Result<i32, String> {
+    match value.trim() {
+        "true" => Ok(1),
+        "false" => Ok(0),
+        value => value
+            .parse()
+            .ok()
+            .or_else(|| decls.get_consts().get(value).copied())
+            .ok_or_else(|| format!("Could not read the value \"{}\"", value)),
+    }
 }
 
 /// Function used for deserializing guards
@@ -362,19 +453,21 @@ pub fn encode_declarations<S>(decls: &Declarations, serializer: S) -> Result<S::
 where
     S: Serializer,
 {
-    let mut output = String::from("clock ");
-    let mut it = decls.clocks.iter();
-    if let Some((first_clock, _)) = it.next() {
-        output = output.add(first_clock);
-
-        for (clock, _) in it {
-            output = output.add(&format!(", {}", clock));
+    let mut output = vec![];
+    if !decls.clocks.is_empty() {
+        let clocks: Vec<&str> = decls.clocks.keys().map(|clock| clock.as_str()).collect();
+        output.push(format!("clock {};", clocks.join(", ")));
+    }
+    for (name, value) in &decls.consts {
+        output.push(format!("const int {} = {};", name, value));
+    }
+    for (name, value) in &decls.ints {
+        match decls.ranges.get(name) {
+            Some((min, max)) => output.push(format!("int[{},{}] {} = {};", min, max, name, value)),
+            None => output.push(format!("int {} = {};", name, value)),
         }
-        output = output.add(";");
-
-        return serializer.serialize_str(&output);
     }
-    serializer.serialize_str("")
+    serializer.serialize_str(&output.join("\n"))
 }
 
 pub fn encode_opt_boolexpr<S>(
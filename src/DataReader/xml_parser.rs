@@ -1,5 +1,6 @@
 use crate::DataReader::parse_edge::Update;
-use crate::DataReader::{parse_edge, parse_invariant};
+use crate::DataReader::serialization::{decode_data_declaration, is_data_declaration};
+use crate::DataReader::{parse_edge, parse_invariant, parse_queries};
 use crate::ModelObjects::component::{Declarations, Edge, LocationType, SyncType};
 use crate::ModelObjects::system_declarations::{SystemDeclarations, SystemSpecification};
 use crate::ModelObjects::{component, queries, representations, system_declarations};
@@ -9,19 +10,91 @@ use std::collections::HashMap;
 use std::fs::File;
 use std::io::BufReader;
 use std::io::Read;
+use std::path::Path;
 
 pub fn is_xml_project(project_path: &str) -> bool {
     project_path.ends_with(".xml")
 }
 
-///Used to parse systems described in xml
-pub(crate) fn parse_xml_from_file(
-    fileName: &str,
-) -> (
+/// Returns the path of the UPPAAL query file belonging to an xml project, e.g. `ConsTests.q` for `ConsTests.xml`
+pub fn query_file_path(project_path: &str) -> String {
+    match project_path.strip_suffix(".xml") {
+        Some(stem) => format!("{}.q", stem),
+        None => format!("{}.q", project_path),
+    }
+}
+
+/// Reads the queries of a UPPAAL query file, if it exists. The comment before a query is kept as its comment
+pub(crate) fn parse_query_file(file_name: &str) -> Vec<queries::Query> {
+    if !Path::new(file_name).exists() {
+        return vec![];
+    }
+
+    let mut text = String::new();
+    File::open(file_name)
+        .and_then(|mut file| file.read_to_string(&mut text))
+        .unwrap_or_else(|e| panic!("Could not read query file {}: {}", file_name, e));
+
+    parse_query_text(&text)
+}
+
+pub(crate) fn parse_query_text(text: &str) -> Vec<queries::Query> {
+    let mut queries = vec![];
+    let mut comment = String::new();
+    let mut lines = text.lines();
+
+    while let Some(line) = lines.next() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("//") {
+            continue;
+        }
+
+        if let Some(mut rest) = line.strip_prefix("/*") {
+            let mut comment_lines = vec![];
+            loop {
+                if let Some((inside, _)) = rest.split_once("*/") {
+                    comment_lines.push(inside.trim());
+                    break;
+                }
+                comment_lines.push(rest.trim());
+                match lines.next() {
+                    Some(next) => rest = next,
+                    None => break,
+                }
+            }
+            comment = comment_lines
+                .into_iter()
+                .filter(|line| !line.is_empty())
+                .collect::<Vec<_>>()
+                .join("\n");
+            continue;
+        }
+
+        queries.extend(to_queries(line, &comment));
+        comment.clear();
+    }
+
+    queries
+}
+
+fn to_queries(formula: &str, comment: &str) -> Vec<queries::Query> {
+    match parse_queries::parse_to_expression_tree(formula) {
+        Ok(expressions) => expressions
+            .into_iter()
+            .map(|query| queries::Query::new(query, comment))
+            .collect(),
+        Err(e) => panic!("Could not parse query {} got error: {}", formula, e),
+    }
+}
+
+type XmlSystem = (
     Vec<component::Component>,
     system_declarations::SystemDeclarations,
     Vec<queries::Query>,
-) {
+);
+
+///Used to parse systems described in xml, failing if a declaration can not be read
+pub(crate) fn parse_xml_from_file(fileName: &str) -> Result<XmlSystem, String> {
     //Open file and read xml
     let file = File::open(fileName).unwrap();
     let reader = BufReader::new(file);
@@ -29,25 +102,13 @@ pub(crate) fn parse_xml_from_file(
     parse_xml(reader)
 }
 
-pub(crate) fn parse_xml_from_str(
-    xml: &str,
-) -> (
-    Vec<component::Component>,
-    system_declarations::SystemDeclarations,
-    Vec<queries::Query>,
-) {
+pub(crate) fn parse_xml_from_str(xml: &str) -> Result<XmlSystem, String> {
     let reader = BufReader::new(xml.as_bytes());
 
     parse_xml(reader)
 }
 
-fn parse_xml<R: Read>(
-    xml_data: R,
-) -> (
-    Vec<component::Component>,
-    system_declarations::SystemDeclarations,
-    Vec<queries::Query>,
-) {
+fn parse_xml<R: Read>(xml_data: R) -> Result<XmlSystem, String> {
     let root = Element::from_reader(xml_data).unwrap();
 
     //storage of components
@@ -55,8 +116,8 @@ fn parse_xml<R: Read>(
 
     for xml_comp in root.find_all("template") {
         let declarations = match xml_comp.find("declaration") {
-            Some(e) => parse_declarations(e.text()),
-            None => parse_declarations(""),
+            Some(e) => parse_declarations(e.text())?,
+            None => parse_declarations("")?,
         };
         let edges = collect_edges(xml_comp.find_all("transition"));
         let comp = component::Component {
@@ -82,7 +143,26 @@ fn parse_xml<R: Read>(
         declarations: decode_sync_type(root.find("system").unwrap().text()),
     };
 
-    (xml_components, system_declarations, vec![])
+    let queries = match root.find("queries") {
+        Some(xml_queries) => collect_queries(xml_queries.find_all("query")),
+        None => vec![],
+    };
+
+    Ok((xml_components, system_declarations, queries))
+}
+
+fn collect_queries(xml_queries: FindChildren) -> Vec<queries::Query> {
+    let mut queries = vec![];
+    for query in xml_queries {
+        let formula = query.find("formula").map_or("", |f| f.text()).trim();
+        if formula.is_empty() {
+            continue;
+        }
+        let comment = query.find("comment").map_or("", |c| c.text()).trim();
+        queries.extend(to_queries(formula, comment));
+    }
+
+    queries
 }
 
 fn collect_locations(xml_locations: FindChildren, initial_id: &str) -> Vec<component::Location> {
@@ -167,11 +247,10 @@ fn collect_edges(xml_edges: FindChildren) -> Vec<Edge> {
     edges
 }
 
-fn parse_declarations(variables: &str) -> Declarations {
+fn parse_declarations(variables: &str) -> Result<Declarations, String> {
     //Split string into vector of strings
     let decls: Vec<String> = variables.split('\n').map(|s| s.into()).collect();
-    let mut ints: HashMap<String, i32> = HashMap::new();
-    let mut clocks: HashMap<String, ClockIndex> = HashMap::new();
+    let mut declarations = Declarations::empty();
     let mut counter: ClockIndex = 1;
     for string in decls {
         //skip comments
@@ -193,27 +272,21 @@ fn parse_declarations(variables: &str) -> Declarations {
                             split_str.split(',').map(|s| s.into()).collect();
                         for var in comma_split {
                             if !var.is_empty() {
-                                clocks.insert(var, counter);
+                                declarations.clocks.insert(var, counter);
                                 counter += 1;
                             }
                         }
                     }
-                } else if variable_type == "int" {
-                    for split_str in split_string.iter().skip(1) {
-                        let comma_split: Vec<String> =
-                            split_str.split(',').map(|s| s.into()).collect();
-                        for var in comma_split {
-                            ints.insert(var, 0);
-                        }
-                    }
+                } else if is_data_declaration(variable_type) {
+                    decode_data_declaration(&sub_decl, &mut declarations)?;
                 } else {
-                    panic!("not implemented read for type: {}", variable_type);
+                    return Err(format!("not implemented read for type: {}", variable_type));
                 }
             }
         }
     }
 
-    Declarations { ints, clocks }
+    Ok(declarations)
 }
 
 fn decode_sync_type(global_decl: &str) -> SystemSpecification {
@@ -1,3 +1,5 @@
+pub mod search_queue;
 pub mod statepair_list;
 
+pub use search_queue::{SearchOrder, SearchQueue};
 pub use statepair_list::{PassedStateList, PassedStateListExt, WaitingStateList};
@@ -0,0 +1,143 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, VecDeque};
+use std::fmt;
+use std::str::FromStr;
+
+use rand::Rng;
+
+/// The order in which a search explores the states waiting in its [SearchQueue]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SearchOrder {
+    BreadthFirst,
+    DepthFirst,
+    /// Depth first, but the successors of a state are explored in a random order
+    RandomDepthFirst,
+    /// The state estimated to be closest to a failure (or target) first, depth first among equally close states
+    ClosestToFailure,
+}
+
+impl SearchOrder {
+    /// Whether the order depends on the distances the states are pushed with
+    pub fn uses_distance(self) -> bool {
+        self == SearchOrder::ClosestToFailure
+    }
+}
+
+impl FromStr for SearchOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bfs" => Ok(SearchOrder::BreadthFirst),
+            "dfs" => Ok(SearchOrder::DepthFirst),
+            "random-dfs" => Ok(SearchOrder::RandomDepthFirst),
+            "closest" => Ok(SearchOrder::ClosestToFailure),
+            _ => Err(format!(
+                "Unknown search order {}, expected one of bfs, dfs, random-dfs and closest",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for SearchOrder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SearchOrder::BreadthFirst => write!(f, "bfs"),
+            SearchOrder::DepthFirst => write!(f, "dfs"),
+            SearchOrder::RandomDepthFirst => write!(f, "random-dfs"),
+            SearchOrder::ClosestToFailure => write!(f, "closest"),
+        }
+    }
+}
+
+/// The states waiting to be explored by a search, which are popped in the [SearchOrder] of the queue.
+/// Each state is pushed with its estimated distance to a failure, which only [SearchOrder::ClosestToFailure] uses.
+pub struct SearchQueue<T> {
+    order: SearchOrder,
+    queue: VecDeque<(T, usize)>,
+    closest: BinaryHeap<ClosestEntry<T>>,
+    pushed: usize,
+    /// The number of states pushed since the last pop, which are the ones shuffled by a random depth first search
+    pushed_since_pop: usize,
+}
+
+/// An entry of the heap of [SearchOrder::ClosestToFailure], which pops the least distance,
+/// and the last pushed entry among equal distances
+struct ClosestEntry<T> {
+    key: (Reverse<usize>, usize),
+    item: T,
+}
+
+impl<T> PartialEq for ClosestEntry<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl<T> Eq for ClosestEntry<T> {}
+
+impl<T> PartialOrd for ClosestEntry<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for ClosestEntry<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+impl<T> SearchQueue<T> {
+    pub fn new(order: SearchOrder) -> Self {
+        SearchQueue {
+            order,
+            queue: VecDeque::new(),
+            closest: BinaryHeap::new(),
+            pushed: 0,
+            pushed_since_pop: 0,
+        }
+    }
+
+    pub fn order(&self) -> SearchOrder {
+        self.order
+    }
+
+    pub fn push(&mut self, item: T, distance: usize) {
+        match self.order {
+            SearchOrder::BreadthFirst => self.queue.push_back((item, distance)),
+            SearchOrder::DepthFirst => self.queue.push_front((item, distance)),
+            SearchOrder::RandomDepthFirst => {
+                let index = rand::thread_rng().gen_range(0..=self.pushed_since_pop);
+                self.queue.insert(index, (item, distance));
+            }
+            SearchOrder::ClosestToFailure => self.closest.push(ClosestEntry {
+                key: (Reverse(distance), self.pushed),
+                item,
+            }),
+        }
+        self.pushed += 1;
+        self.pushed_since_pop += 1;
+    }
+
+    /// Pops the next state to explore along with the distance it was pushed with
+    pub fn pop(&mut self) -> Option<(T, usize)> {
+        self.pushed_since_pop = 0;
+        match self.order {
+            SearchOrder::ClosestToFailure => self.closest.pop().map(|entry| {
+                let Reverse(distance) = entry.key.0;
+                (entry.item, distance)
+            }),
+            _ => self.queue.pop_front(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.len() + self.closest.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
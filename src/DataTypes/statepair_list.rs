@@ -1,29 +1,34 @@
-use std::collections::{HashMap, VecDeque};
+use std::collections::HashMap;
 
 use edbm::zones::OwnedFederation;
 
-use crate::{ModelObjects::statepair::StatePair, TransitionSystems::LocationID};
+use crate::DataTypes::search_queue::{SearchOrder, SearchQueue};
+use crate::{ModelObjects::statepair::StatePair, TransitionSystems::LocationKey};
 
 pub type PassedStateList = PassedStateListFed;
-type PassedStateListFed = HashMap<(LocationID, LocationID), OwnedFederation>;
-type PassedStateListVec = HashMap<(LocationID, LocationID), Vec<OwnedFederation>>;
-
-pub type WaitingStateList = DepthFirstWaitingStateList;
-pub struct DepthFirstWaitingStateList {
-    queue: VecDeque<StatePair>,
-    map: HashMap<(LocationID, LocationID), VecDeque<OwnedFederation>>,
+pub type StatePairKey = (LocationKey, LocationKey);
+type PassedStateListFed = HashMap<StatePairKey, OwnedFederation>;
+type PassedStateListVec = HashMap<StatePairKey, Vec<OwnedFederation>>;
+
+pub type WaitingStateList = OrderedWaitingStateList;
+/// The waiting list of a refinement check, which pops the state pairs in its [SearchOrder]
+pub struct OrderedWaitingStateList {
+    queue: SearchQueue<(usize, StatePair)>,
+    /// The zones waiting for each key, along with the number the pair was put with
+    map: HashMap<StatePairKey, Vec<(usize, OwnedFederation)>>,
+    put_count: usize,
 }
 
 pub trait PassedStateListExt {
     fn put(&mut self, pair: StatePair);
     fn has(&self, pair: &StatePair) -> bool;
-    fn zones(&self, key: &(LocationID, LocationID)) -> Vec<&OwnedFederation>;
+    fn zones(&self, key: &StatePairKey) -> Vec<&OwnedFederation>;
 }
 
 impl PassedStateListExt for PassedStateListVec {
     fn put(&mut self, mut pair: StatePair) {
         let fed = pair.take_zone();
-        let (loc1, loc2) = (pair.locations1.id, pair.locations2.id);
+        let (loc1, loc2) = (pair.locations1.get_key(), pair.locations2.get_key());
         let key = (loc1, loc2);
         if let Some(vec) = self.get_mut(&key) {
             vec.push(fed);
@@ -34,8 +39,8 @@ impl PassedStateListExt for PassedStateListVec {
 
     fn has(&self, pair: &StatePair) -> bool {
         let (loc1, loc2, fed) = (
-            pair.locations1.id.clone(),
-            pair.locations2.id.clone(),
+            pair.locations1.get_key(),
+            pair.locations2.get_key(),
             pair.ref_zone(),
         );
         let key = (loc1, loc2);
@@ -45,7 +50,7 @@ impl PassedStateListExt for PassedStateListVec {
         }
     }
 
-    fn zones(&self, key: &(LocationID, LocationID)) -> Vec<&OwnedFederation> {
+    fn zones(&self, key: &StatePairKey) -> Vec<&OwnedFederation> {
         match self.get(key) {
             Some(vec) => vec.iter().collect(),
             None => panic!("No zones for key: {:?}", key),
@@ -53,56 +58,67 @@ impl PassedStateListExt for PassedStateListVec {
     }
 }
 
-impl PassedStateListExt for DepthFirstWaitingStateList {
-    fn put(&mut self, mut pair: StatePair) {
-        self.queue.push_front(pair.clone());
-        let fed = pair.take_zone();
-        let (loc1, loc2) = (pair.locations1.id, pair.locations2.id);
-        let key = (loc1, loc2);
-        if let Some(vec) = self.map.get_mut(&key) {
-            vec.push_front(fed);
-        } else {
-            self.map.insert(key, vec![fed].into());
-        };
+impl PassedStateListExt for OrderedWaitingStateList {
+    fn put(&mut self, pair: StatePair) {
+        self.put_with_distance(pair, 0);
     }
     fn has(&self, pair: &StatePair) -> bool {
         let (loc1, loc2, fed) = (
-            pair.locations1.id.clone(),
-            pair.locations2.id.clone(),
+            pair.locations1.get_key(),
+            pair.locations2.get_key(),
             pair.ref_zone(),
         );
         let key = (loc1, loc2);
         match self.map.get(&key) {
-            Some(vec) => vec.iter().any(|f| fed.subset_eq(f)),
+            Some(vec) => vec.iter().any(|(_, f)| fed.subset_eq(f)),
             None => false,
         }
     }
-    fn zones(&self, key: &(LocationID, LocationID)) -> Vec<&OwnedFederation> {
+    fn zones(&self, key: &StatePairKey) -> Vec<&OwnedFederation> {
         match self.map.get(key) {
-            Some(vec) => vec.iter().collect(),
+            Some(vec) => vec.iter().map(|(_, f)| f).collect(),
             None => panic!("No zones for key: {:?}", key),
         }
     }
 }
 
-#[allow(clippy::new_without_default)]
-impl DepthFirstWaitingStateList {
-    pub fn new() -> Self {
-        DepthFirstWaitingStateList {
-            queue: VecDeque::new(),
+impl OrderedWaitingStateList {
+    pub fn new(order: SearchOrder) -> Self {
+        OrderedWaitingStateList {
+            queue: SearchQueue::new(order),
             map: HashMap::new(),
+            put_count: 0,
         }
     }
 
+    pub fn order(&self) -> SearchOrder {
+        self.queue.order()
+    }
+
+    /// Puts the pair in the list with its estimated distance to a failure, see [SearchQueue]
+    pub fn put_with_distance(&mut self, mut pair: StatePair, distance: usize) {
+        let number = self.put_count;
+        self.put_count += 1;
+        self.queue.push((number, pair.clone()), distance);
+        let fed = pair.take_zone();
+        let key = (pair.locations1.get_key(), pair.locations2.get_key());
+        self.map.entry(key).or_default().push((number, fed));
+    }
+
     pub fn pop(&mut self) -> Option<StatePair> {
-        let pair = self.queue.pop_front()?;
-        let key = (pair.locations1.id.clone(), pair.locations2.id.clone());
+        self.pop_with_distance().map(|(pair, _)| pair)
+    }
+
+    /// Pops the next pair along with the distance it was put with
+    pub fn pop_with_distance(&mut self) -> Option<(StatePair, usize)> {
+        let ((number, pair), distance) = self.queue.pop()?;
+        let key = (pair.locations1.get_key(), pair.locations2.get_key());
 
         if let Some(vec) = self.map.get_mut(&key) {
-            vec.pop_front().unwrap();
+            vec.retain(|(put_number, _)| *put_number != number);
         };
 
-        Some(pair)
+        Some((pair, distance))
     }
 
     pub fn is_empty(&self) -> bool {
@@ -112,7 +128,7 @@ impl DepthFirstWaitingStateList {
 impl PassedStateListExt for PassedStateListFed {
     fn put(&mut self, mut pair: StatePair) {
         let mut fed = pair.take_zone();
-        let (loc1, loc2) = (pair.locations1.id, pair.locations2.id);
+        let (loc1, loc2) = (pair.locations1.get_key(), pair.locations2.get_key());
         let key = (loc1, loc2);
 
         if let Some(f) = self.get(&key) {
@@ -123,8 +139,8 @@ impl PassedStateListExt for PassedStateListFed {
 
     fn has(&self, pair: &StatePair) -> bool {
         let (loc1, loc2, fed) = (
-            pair.locations1.id.clone(),
-            pair.locations2.id.clone(),
+            pair.locations1.get_key(),
+            pair.locations2.get_key(),
             pair.ref_zone(),
         );
         let key = (loc1, loc2);
@@ -134,7 +150,7 @@ impl PassedStateListExt for PassedStateListFed {
         }
     }
 
-    fn zones(&self, key: &(LocationID, LocationID)) -> Vec<&OwnedFederation> {
+    fn zones(&self, key: &StatePairKey) -> Vec<&OwnedFederation> {
         match self.get(key) {
             Some(f) => vec![f],
             None => panic!("No zones for key: {:?}", key),
@@ -2,6 +2,7 @@ use edbm::util::constraints::{ClockIndex, Inequality};
 use edbm::zones::OwnedFederation;
 
 use crate::component::Declarations;
+use crate::EdgeEval::updater::evaluate_int;
 
 use crate::ModelObjects::representations::{ArithExpression, BoolExpression, Clock};
 
@@ -34,16 +35,25 @@ fn apply_constraints_to_state_helper(
             Ok(fed1 + fed2)
         }
         BoolExpression::LessEQ(left, right) => {
+            if let Some((l, r)) = get_int_values(left, right, decls)? {
+                return Ok(compare_ints(fed, l, r, |l, r| l <= r));
+            }
             let (i, j, c) = get_indices(left, right, decls)?;
             // i-j<=c
             Ok(fed.constrain(i, j, LE(c)))
         }
         BoolExpression::GreatEQ(left, right) => {
+            if let Some((l, r)) = get_int_values(left, right, decls)? {
+                return Ok(compare_ints(fed, l, r, |l, r| l >= r));
+            }
             let (i, j, c) = get_indices(right, left, decls)?;
             // j-i <= -c -> c <= i-j
             Ok(fed.constrain(i, j, LE(c)))
         }
         BoolExpression::EQ(left, right) => {
+            if let Some((l, r)) = get_int_values(left, right, decls)? {
+                return Ok(compare_ints(fed, l, r, |l, r| l == r));
+            }
             let (i, j, c) = get_indices(left, right, decls)?;
             // i-j <= c && j-i <= -c -> c <= i-j
 
@@ -51,11 +61,17 @@ fn apply_constraints_to_state_helper(
             Ok(fed.constrain(i, j, LE(c)).constrain(j, i, LE(-c)))
         }
         BoolExpression::LessT(left, right) => {
+            if let Some((l, r)) = get_int_values(left, right, decls)? {
+                return Ok(compare_ints(fed, l, r, |l, r| l < r));
+            }
             let (i, j, c) = get_indices(left, right, decls)?;
             // i-j < c
             Ok(fed.constrain(i, j, LS(c)))
         }
         BoolExpression::GreatT(left, right) => {
+            if let Some((l, r)) = get_int_values(left, right, decls)? {
+                return Ok(compare_ints(fed, l, r, |l, r| l > r));
+            }
             let (i, j, c) = get_indices(right, left, decls)?;
             // j-i < -c -> c < i-j
             Ok(fed.constrain(i, j, LS(c)))
@@ -67,7 +83,46 @@ fn apply_constraints_to_state_helper(
             }
             Ok(fed)
         }
-        _ => panic!("Unexpected BoolExpression"),
+        BoolExpression::Arithmetic(expr) => {
+            // An int or bool used as a condition holds when it is not 0
+            match get_int_values(expr, &ArithExpression::Int(0), decls)? {
+                Some((value, zero)) => Ok(compare_ints(fed, value, zero, |l, r| l != r)),
+                None => Err(format!(
+                    "Clocks can not be used as a condition in {}",
+                    guard.encode_expr()
+                )),
+            }
+        }
+    }
+}
+
+/// Evaluates both sides of a comparison which does not use any clocks, so only int variables and constants
+fn get_int_values(
+    left: &ArithExpression,
+    right: &ArithExpression,
+    decls: &Declarations,
+) -> Result<Option<(i32, i32)>, String> {
+    let left = replace_vars(left, decls)?.simplify()?;
+    let right = replace_vars(right, decls)?.simplify()?;
+    if left.clock_var_count() + right.clock_var_count() != 0 {
+        return Ok(None);
+    }
+    Ok(Some((
+        evaluate_int(&left, decls)?,
+        evaluate_int(&right, decls)?,
+    )))
+}
+
+fn compare_ints(
+    fed: OwnedFederation,
+    left: i32,
+    right: i32,
+    comparison: fn(i32, i32) -> bool,
+) -> OwnedFederation {
+    if comparison(left, right) {
+        fed
+    } else {
+        fed.set_empty()
     }
 }
 
@@ -160,7 +215,7 @@ fn replace_vars(expr: &ArithExpression, decls: &Declarations) -> Result<ArithExp
         ArithExpression::VarName(name) => {
             if let Some(x) = decls.get_clocks().get(name.as_str()).copied() {
                 Ok(ArithExpression::Clock(x))
-            } else if let Some(x) = decls.get_ints().get(name.as_str()).copied() {
+            } else if let Some(x) = decls.get_int_value(name) {
                 Ok(ArithExpression::Int(x))
             } else {
                 Err(name.to_string())
@@ -174,7 +229,7 @@ fn get_const(expr: &ArithExpression, decls: &Declarations) -> i32 {
     match expr {
         ArithExpression::Int(x) => *x,
         ArithExpression::Clock(_) => 0,
-        ArithExpression::VarName(name) => decls.get_ints().get(name).copied().unwrap_or(0),
+        ArithExpression::VarName(name) => decls.get_int_value(name).unwrap_or(0),
         ArithExpression::Parentheses(x) => get_const(x, decls),
         ArithExpression::Difference(l, r) => get_const(l, decls) - get_const(r, decls),
         ArithExpression::Addition(l, r) => get_const(l, decls) + get_const(r, decls),
@@ -263,6 +318,7 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            ..Declarations::empty()
         };
 
         let left = ArithExpression::Int(3);
@@ -277,6 +333,7 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            ..Declarations::empty()
         };
 
         let left = ArithExpression::Clock(1);
@@ -291,6 +348,7 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            ..Declarations::empty()
         };
 
         // i-j <= 0 -> i can at most be the value of j
@@ -306,6 +364,7 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            ..Declarations::empty()
         };
         // i-j < c -> c1-c2 < 3
         let left = ArithExpression::ADif(ArithExpression::Clock(1), ArithExpression::Clock(2));
@@ -329,6 +388,7 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            ..Declarations::empty()
         };
         // i-j < c -> c1-c2 > 3 -> c2-c1 < -3
         let left = ArithExpression::Int(3);
@@ -343,6 +403,7 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            ..Declarations::empty()
         };
         let left = ArithExpression::Addition(
             Box::new(ArithExpression::Clock(1)),
@@ -359,6 +420,7 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            ..Declarations::empty()
         };
         let left = ArithExpression::Clock(1);
         let right = ArithExpression::ADif(ArithExpression::Clock(2), ArithExpression::Int(3));
@@ -378,6 +440,7 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            ..Declarations::empty()
         };
 
         let left = ArithExpression::Addition(
@@ -426,6 +489,7 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            ..Declarations::empty()
         };
         // i-j < c -> c1-c2 > 3 -> c2-c1 < -3
         let left = ArithExpression::ADif(ArithExpression::Clock(1), ArithExpression::Int(2));
@@ -444,6 +508,7 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            ..Declarations::empty()
         };
         let left = ArithExpression::Clock(1);
         let right = ArithExpression::Addition(
@@ -465,6 +530,7 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            ..Declarations::empty()
         };
         let left = ArithExpression::Int(3);
         let right = ArithExpression::Addition(
@@ -481,6 +547,7 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            ..Declarations::empty()
         };
         let left = ArithExpression::Multiplication(
             Box::new(ArithExpression::Clock(2)),
@@ -544,6 +611,7 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            ..Declarations::empty()
         };
         let left = ArithExpression::ADif(ArithExpression::Clock(1), ArithExpression::Clock(2));
         let right = Box::new(ArithExpression::Clock(3));
@@ -557,6 +625,7 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            ..Declarations::empty()
         };
         let left = ArithExpression::Int(1);
         let right = ArithExpression::Int(2);
@@ -589,6 +658,7 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            ..Declarations::empty()
         };
         let left = ArithExpression::ADif(
             // = 4
@@ -626,6 +696,7 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            ..Declarations::empty()
         };
         let left = ArithExpression::ADif(
             ArithExpression::Multiplication(
@@ -27,30 +27,14 @@ impl fmt::Display for CompiledUpdate {
 
 impl CompiledUpdate {
     pub fn compile(update: &parse_edge::Update, decl: &Declarations) -> CompiledUpdate {
-        match update.get_expression() {
-            BoolExpression::Arithmetic(x) => match **x {
-                ArithExpression::Int(val) => {
-                    if let Some(&clock_index) =
-                        decl.get_clock_index_by_name(update.get_variable_name())
-                    {
-                        CompiledUpdate {
-                            clock_index,
-                            value: val,
-                        }
-                    } else {
-                        panic!(
-                                "Attempting to compile an update with a clock \"{}\" which is not in decl",
-                                update.get_variable_name()
-                            )
-                    }
-                }
-                _ => {
-                    panic!("Should not be able to assign to {:?} in update", update)
-                }
-            },
-            _ => {
-                panic!("Should not be able to assign to {:?} in update", update)
-            }
+        let value = evaluate_update(update, decl).unwrap_or_else(|err| panic!("{}", err));
+        if let Some(&clock_index) = decl.get_clock_index_by_name(update.get_variable_name()) {
+            CompiledUpdate { clock_index, value }
+        } else {
+            panic!(
+                "Attempting to compile an update with a clock \"{}\" which is not in decl",
+                update.get_variable_name()
+            )
         }
     }
 
@@ -75,3 +59,77 @@ impl CompiledUpdate {
         fed.constrain_eq(self.clock_index, self.value)
     }
 }
+
+/// Applies `updates` in order, so the integer variables in `decl` are given their new values
+/// and every clock update is compiled with the values of the integers at that point.
+/// Fails if an int is assigned a value outside its range or a constant is assigned to.
+pub fn compile_updates(
+    updates: &[parse_edge::Update],
+    decl: &mut Declarations,
+) -> Result<Vec<CompiledUpdate>, String> {
+    let mut compiled = vec![];
+    for update in updates {
+        let name = update.get_variable_name();
+        if decl.get_consts().contains_key(name) {
+            return Err(format!("The constant \"{}\" can not be assigned to", name));
+        } else if decl.get_ints().contains_key(name) {
+            let value = evaluate_update(update, decl)?;
+            let (min, max) = decl.get_range(name);
+            if value < min || value > max {
+                return Err(format!(
+                    "Assigning {} to \"{}\" is outside its range [{},{}]",
+                    value, name, min, max
+                ));
+            }
+            decl.get_mut_ints().insert(name.to_string(), value);
+        } else {
+            compiled.push(CompiledUpdate::compile(update, decl));
+        }
+    }
+    Ok(compiled)
+}
+
+fn evaluate_update(update: &parse_edge::Update, decl: &Declarations) -> Result<i32, String> {
+    match update.get_expression() {
+        BoolExpression::Arithmetic(expr) => evaluate_int(expr, decl),
+        BoolExpression::Bool(value) => Ok(*value as i32),
+        _ => Err(format!(
+            "Should not be able to assign to {:?} in update",
+            update
+        )),
+    }
+}
+
+/// Evaluates an expression over integer variables with the values they have in `decl`
+pub fn evaluate_int(expr: &ArithExpression, decl: &Declarations) -> Result<i32, String> {
+    let (left, right) = match expr {
+        ArithExpression::Int(value) => return Ok(*value),
+        ArithExpression::Parentheses(inner) => return evaluate_int(inner, decl),
+        ArithExpression::VarName(name) => {
+            return decl
+                .get_int_value(name)
+                .ok_or_else(|| format!("\"{}\" is not an integer variable or constant", name))
+        }
+        ArithExpression::Clock(_) => {
+            return Err(format!(
+                "Clocks can not be used in the integer expression {}",
+                expr.encode_expr()
+            ))
+        }
+        ArithExpression::Difference(l, r)
+        | ArithExpression::Addition(l, r)
+        | ArithExpression::Multiplication(l, r)
+        | ArithExpression::Division(l, r)
+        | ArithExpression::Modulo(l, r) => (evaluate_int(l, decl)?, evaluate_int(r, decl)?),
+    };
+
+    let value = match expr {
+        ArithExpression::Difference(..) => left.checked_sub(right),
+        ArithExpression::Addition(..) => left.checked_add(right),
+        ArithExpression::Multiplication(..) => left.checked_mul(right),
+        ArithExpression::Division(..) => left.checked_div(right),
+        ArithExpression::Modulo(..) => left.checked_rem(right),
+        _ => unreachable!(),
+    };
+    value.ok_or_else(|| format!("Could not evaluate {}", expr.encode_expr()))
+}
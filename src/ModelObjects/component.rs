@@ -6,7 +6,7 @@ use crate::DataReader::serialization::{
 };
 
 use crate::EdgeEval::constraint_applyer::apply_constraints_to_state;
-use crate::EdgeEval::updater::CompiledUpdate;
+use crate::EdgeEval::updater::{compile_updates, CompiledUpdate};
 use edbm::util::bounds::Bounds;
 use edbm::util::constraints::ClockIndex;
 
@@ -77,6 +77,41 @@ impl Component {
     pub fn get_edges(&self) -> &Vec<Edge> {
         &self.edges
     }
+
+    /// The global clocks and ints which are assigned to in an update of the component
+    pub fn get_assigned_globals(&self) -> HashSet<&str> {
+        self.edges
+            .iter()
+            .flat_map(|edge| edge.get_update_clocks())
+            .filter(|name| self.declarations.globals.contains(*name))
+            .collect()
+    }
+
+    /// The global clocks and ints which are assigned to or read in the invariants, guards or updates of the component
+    pub fn get_used_globals(&self) -> HashSet<&str> {
+        let invariants = self
+            .locations
+            .iter()
+            .filter_map(|location| location.get_invariant().as_ref());
+        let guards = self
+            .edges
+            .iter()
+            .filter_map(|edge| edge.get_guard().as_ref());
+        let updates = self
+            .edges
+            .iter()
+            .filter_map(|edge| edge.get_update().as_ref())
+            .flatten()
+            .map(|update| update.get_expression());
+
+        invariants
+            .chain(guards)
+            .chain(updates)
+            .flat_map(|expression| expression.get_varnames())
+            .chain(self.get_assigned_globals())
+            .filter(|name| self.declarations.globals.contains(*name))
+            .collect()
+    }
     pub fn get_mut_edges(&mut self) -> &mut Vec<Edge> {
         &mut self.edges
     }
@@ -212,23 +247,26 @@ impl Component {
 
     pub fn get_max_bounds(&self, dimensions: ClockIndex) -> Bounds {
         let mut max_bounds = Bounds::new(dimensions);
+        let constraints = self
+            .edges
+            .iter()
+            .filter_map(|edge| edge.get_guard().as_ref())
+            .chain(
+                self.locations
+                    .iter()
+                    .filter_map(|location| location.get_invariant().as_ref()),
+            );
+
         for (clock_name, clock_id) in &self.declarations.clocks {
             let mut max_bound = 0;
-            for edge in &self.edges {
-                if let Some(guard) = edge.get_guard() {
-                    let new_bound = guard.get_max_constant(*clock_id, clock_name);
-                    if max_bound < new_bound {
-                        max_bound = new_bound;
-                    }
-                }
-            }
-
-            for location in &self.locations {
-                if let Some(inv) = location.get_invariant() {
-                    let new_bound = inv.get_max_constant(*clock_id, clock_name);
-                    if max_bound < new_bound {
-                        max_bound = new_bound;
-                    }
+            for constraint in constraints.clone() {
+                let mut constraint = constraint.clone();
+                constraint.replace_with_values(self.declarations.get_consts());
+                let new_bound = constraint
+                    .get_max_constant(*clock_id, clock_name)
+                    .max(self.get_max_int_bound(&constraint, clock_name));
+                if max_bound < new_bound {
+                    max_bound = new_bound;
                 }
             }
 
@@ -240,6 +278,29 @@ impl Component {
         max_bounds
     }
 
+    /// The bound of a clock compared to int variables, where any value of the ints has to be assumed
+    fn get_max_int_bound(&self, constraint: &BoolExpression, clock_name: &str) -> i32 {
+        let mut max_bound = 0;
+        constraint.iterate_constraints(&mut |left, right| {
+            let names: Vec<&str> = left
+                .get_varnames()
+                .into_iter()
+                .chain(right.get_varnames())
+                .collect();
+            if names.contains(&clock_name) {
+                for name in names {
+                    if self.declarations.get_ints().contains_key(name) {
+                        let (min, max) = self.declarations.get_range(name);
+                        max_bound = max_bound
+                            .max(min.saturating_abs())
+                            .max(max.saturating_abs());
+                    }
+                }
+            }
+        });
+        max_bound
+    }
+
     /// Find [`Edge`] in the component given the edges `id`.
     pub fn find_edge_from_id(&self, id: &str) -> Option<&Edge> {
         self.get_edges().iter().find(|e| e.id.contains(id))
@@ -479,33 +540,62 @@ impl Transition {
         }
     }
 
-    pub fn from(comp: &Component, edge: &Edge, dim: ClockIndex) -> Transition {
-        //let (comp, edge) = edges;
+    /// Create the transition of an edge, or an error if its updates can not be compiled
+    pub fn from(comp: &Component, edge: &Edge, dim: ClockIndex) -> Result<Transition, String> {
+        let guard_zone = Transition::combine_edge_guards(&vec![(comp, edge)], dim);
+        Transition::build(comp, edge, comp.get_declarations(), guard_zone, dim)
+    }
+
+    /// Create the transition of an edge taken with the int values in `decl`,
+    /// or `None` if the guard of the edge can not be satisfied with those values.
+    /// Fails if the updates can not be applied to the values, such as when an int leaves its range
+    pub fn from_declarations(
+        comp: &Component,
+        edge: &Edge,
+        decl: &Declarations,
+        dim: ClockIndex,
+    ) -> Result<Option<Transition>, String> {
+        let guard_zone = edge.apply_guard(decl, OwnedFederation::universe(dim));
+        if guard_zone.is_empty() {
+            return Ok(None);
+        }
+        Transition::build(comp, edge, decl, guard_zone, dim).map(Some)
+    }
 
-        let target_loc_name = &edge.target_location;
-        let target_loc = comp.get_location_by_name(target_loc_name);
+    fn build(
+        comp: &Component,
+        edge: &Edge,
+        decl: &Declarations,
+        guard_zone: OwnedFederation,
+        dim: ClockIndex,
+    ) -> Result<Transition, String> {
+        let mut target_decl = decl.clone();
+        let updates = match edge.get_update() {
+            Some(updates) => compile_updates(updates, &mut target_decl).map_err(|err| {
+                format!(
+                    "Could not take edge {} in {}: {}",
+                    edge.id,
+                    comp.get_name(),
+                    err
+                )
+            })?,
+            None => vec![],
+        };
+
+        let target_loc = comp.get_location_by_name(&edge.target_location);
         let target_locations = LocationTuple::simple(
             target_loc,
             Some(comp.get_name().to_owned()),
-            comp.get_declarations(),
+            &target_decl,
             dim,
         );
 
-        let mut compiled_updates = vec![];
-        if let Some(updates) = edge.get_update() {
-            compiled_updates.extend(
-                updates
-                    .iter()
-                    .map(|update| CompiledUpdate::compile(update, comp.get_declarations())),
-            );
-        }
-
-        Transition {
+        Ok(Transition {
             id: TransitionID::Simple(edge.id.clone()),
-            guard_zone: Transition::combine_edge_guards(&vec![(comp, edge)], dim),
+            guard_zone,
             target_locations,
-            updates: compiled_updates,
-        }
+            updates,
+        })
     }
 
     pub fn use_transition(&self, state: &mut State) -> bool {
@@ -853,11 +943,26 @@ pub trait DeclarationProvider {
     fn get_declarations(&self) -> &Declarations;
 }
 
-/// The declaration struct is used to hold the indices for each clock, and is meant to be the owner of int variables once implemented
+/// The range of an `int` declared without a range, as in UPPAAL
+pub const DEFAULT_INT_RANGE: (i32, i32) = (-32768, 32767);
+
+/// The declaration struct is used to hold the indices for each clock and the values of the int variables
 #[derive(Debug, Deserialize, Clone, PartialEq, Eq, Serialize)]
 pub struct Declarations {
     pub ints: HashMap<String, i32>,
     pub clocks: HashMap<String, ClockIndex>,
+    /// Constants can be used wherever an int can, but are never assigned to
+    #[serde(default)]
+    pub consts: HashMap<String, i32>,
+    /// The ranges of the ints declared with one, such as `int[0,10] n`, and of bools
+    #[serde(default)]
+    pub ranges: HashMap<String, (i32, i32)>,
+    /// Channels declared with `broadcast chan`, where an output synchronises with any number of inputs
+    #[serde(default)]
+    pub broadcast_channels: HashSet<String>,
+    /// The names of the clocks and ints which were added from the global declarations of the project
+    #[serde(default)]
+    pub globals: HashSet<String>,
 }
 
 #[allow(dead_code)]
@@ -866,13 +971,68 @@ impl Declarations {
         Declarations {
             ints: HashMap::new(),
             clocks: HashMap::new(),
+            consts: HashMap::new(),
+            ranges: HashMap::new(),
+            broadcast_channels: HashSet::new(),
+            globals: HashSet::new(),
+        }
+    }
+
+    /// Adds the global declarations of a project which are not shadowed by a local declaration.
+    /// Every component gets its own copy of the global clocks and ints, with the clocks placed after the local ones,
+    /// so a system must not assign to a global in one component and use it in another, see [Component::get_assigned_globals]
+    pub fn add_global_declarations(&mut self, global: &Declarations) {
+        let mut global_clocks: Vec<_> = global.clocks.iter().collect();
+        global_clocks.sort_by_key(|(_, index)| **index);
+        let mut next_index = self.get_max_clock_index() + 1;
+        for (name, _) in global_clocks {
+            if !self.clocks.contains_key(name) {
+                self.clocks.insert(name.clone(), next_index);
+                self.globals.insert(name.clone());
+                next_index += 1;
+            }
+        }
+
+        for (name, value) in &global.consts {
+            if self.get_int_value(name).is_none() {
+                self.consts.insert(name.clone(), *value);
+            }
+        }
+        for (name, value) in &global.ints {
+            if self.get_int_value(name).is_none() {
+                self.ints.insert(name.clone(), *value);
+                self.globals.insert(name.clone());
+                if let Some(range) = global.ranges.get(name) {
+                    self.ranges.insert(name.clone(), *range);
+                }
+            }
         }
+
+        self.broadcast_channels
+            .extend(global.broadcast_channels.iter().cloned());
     }
 
     pub fn get_ints(&self) -> &HashMap<String, i32> {
         &self.ints
     }
 
+    pub fn get_consts(&self) -> &HashMap<String, i32> {
+        &self.consts
+    }
+
+    /// Gets the value of an int or a constant
+    pub fn get_int_value(&self, name: &str) -> Option<i32> {
+        self.ints
+            .get(name)
+            .or_else(|| self.consts.get(name))
+            .copied()
+    }
+
+    /// Gets the range an int has to stay within, which is [`DEFAULT_INT_RANGE`] unless it was declared with one
+    pub fn get_range(&self, name: &str) -> (i32, i32) {
+        self.ranges.get(name).copied().unwrap_or(DEFAULT_INT_RANGE)
+    }
+
     pub fn get_mut_ints(&mut self) -> &mut HashMap<String, i32> {
         &mut self.ints
     }
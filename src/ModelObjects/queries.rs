@@ -1,6 +1,7 @@
 use crate::DataReader::parse_queries;
 use crate::ModelObjects::representations;
 use serde::{Deserialize, Deserializer};
+use std::collections::{HashMap, HashSet};
 
 /// The struct containing a single query
 #[derive(Debug, Deserialize, Clone)]
@@ -8,14 +9,59 @@ pub struct Query {
     #[serde(deserialize_with = "decode_query")]
     pub query: Option<representations::QueryExpression>,
     pub comment: String,
+    /// Maps input actions to whether they are ignored by the query, as written by the GUI
+    #[serde(default, rename = "ignoredInputs")]
+    pub ignored_inputs: HashMap<String, bool>,
+    /// Maps output actions to whether they are ignored by the query, as written by the GUI
+    #[serde(default, rename = "ignoredOutputs")]
+    pub ignored_outputs: HashMap<String, bool>,
+    /// Whether a successful refinement check should return its relation
+    #[serde(skip)]
+    pub save_relation: bool,
 }
 
 impl Query {
+    pub fn new(query: representations::QueryExpression, comment: &str) -> Query {
+        Query {
+            query: Some(query),
+            comment: comment.to_string(),
+            ignored_inputs: HashMap::new(),
+            ignored_outputs: HashMap::new(),
+            save_relation: false,
+        }
+    }
+
+    /// Marks the given inputs and outputs as ignored
+    pub fn ignore_actions(&mut self, inputs: &[String], outputs: &[String]) {
+        for input in inputs {
+            self.ignored_inputs.insert(input.clone(), true);
+        }
+        for output in outputs {
+            self.ignored_outputs.insert(output.clone(), true);
+        }
+    }
+
+    pub fn get_ignored_inputs(&self) -> HashSet<String> {
+        ignored(&self.ignored_inputs)
+    }
+
+    pub fn get_ignored_outputs(&self) -> HashSet<String> {
+        ignored(&self.ignored_outputs)
+    }
+
     pub fn get_query(&self) -> &Option<representations::QueryExpression> {
         &self.query
     }
 }
 
+fn ignored(actions: &HashMap<String, bool>) -> HashSet<String> {
+    actions
+        .iter()
+        .filter(|(_, is_ignored)| **is_ignored)
+        .map(|(action, _)| action.clone())
+        .collect()
+}
+
 /// Function used for deserializing queries
 pub fn decode_query<'de, D>(
     deserializer: D,
@@ -469,6 +469,27 @@ impl BoolExpression {
         }
     }
 
+    /// Replaces all occurrences of `ArithExpression::VarName` found in `values` with their value
+    pub fn replace_with_values(&mut self, values: &HashMap<String, i32>) {
+        match self {
+            BoolExpression::Parentheses(p) => p.replace_with_values(values),
+            BoolExpression::AndOp(e1, e2) | BoolExpression::OrOp(e1, e2) => {
+                e1.replace_with_values(values);
+                e2.replace_with_values(values);
+            }
+            BoolExpression::LessEQ(e1, e2)
+            | BoolExpression::GreatEQ(e1, e2)
+            | BoolExpression::LessT(e1, e2)
+            | BoolExpression::GreatT(e1, e2)
+            | BoolExpression::EQ(e1, e2) => {
+                e1.replace_with_values(values);
+                e2.replace_with_values(values);
+            }
+            BoolExpression::Bool(_) => (),
+            BoolExpression::Arithmetic(a) => a.replace_with_values(values),
+        }
+    }
+
     pub fn BLessEQ(left: ArithExpression, right: ArithExpression) -> BoolExpression {
         BoolExpression::LessEQ(Box::new(left), Box::new(right))
     }
@@ -1061,6 +1082,27 @@ impl ArithExpression {
         }
     }
 
+    /// Replaces all occurrences of `ArithExpression::VarName` found in `values` with their value
+    pub fn replace_with_values(&mut self, values: &HashMap<String, i32>) {
+        match self {
+            ArithExpression::Parentheses(p) => p.replace_with_values(values),
+            ArithExpression::Difference(a1, a2)
+            | ArithExpression::Addition(a1, a2)
+            | ArithExpression::Multiplication(a1, a2)
+            | ArithExpression::Division(a1, a2)
+            | ArithExpression::Modulo(a1, a2) => {
+                a1.replace_with_values(values);
+                a2.replace_with_values(values);
+            }
+            ArithExpression::Clock(_) | ArithExpression::Int(_) => (),
+            ArithExpression::VarName(name) => {
+                if let Some(value) = values.get(name) {
+                    *self = ArithExpression::Int(*value);
+                }
+            }
+        }
+    }
+
     pub fn clock_var_count(&self) -> u32 {
         match self {
             ArithExpression::Clock(_) => 1,
@@ -1271,10 +1313,19 @@ pub enum QueryExpression {
         Box<Option<QueryExpression>>,
         Box<QueryExpression>,
     ),
+    FastestReachability(
+        Box<QueryExpression>,
+        Box<Option<QueryExpression>>,
+        Box<QueryExpression>,
+    ),
     State(Vec<Box<QueryExpression>>, Option<Box<BoolExpression>>),
     LocName(String),
     Implementation(Box<QueryExpression>),
     Determinism(Box<QueryExpression>),
+    Deadlock(Box<QueryExpression>),
+    Timelock(Box<QueryExpression>),
+    NonZeno(Box<QueryExpression>),
+    Strategy(Box<QueryExpression>),
     Specification(Box<QueryExpression>),
     GetComponent(Box<QueryExpression>),
     Prune(Box<QueryExpression>),
@@ -1309,9 +1360,14 @@ impl QueryExpression {
                 left.pretty_string(),
                 right.pretty_string()
             ),
-            QueryExpression::Reachability(automata, start, end) => {
+            QueryExpression::Reachability(automata, start, end)
+            | QueryExpression::FastestReachability(automata, start, end) => {
                 format!(
-                    "reachability: {} -> {}; {}",
+                    "{}: {} -> {}; {}",
+                    match self {
+                        QueryExpression::FastestReachability(..) => "fastest-reachability",
+                        _ => "reachability",
+                    },
                     automata.pretty_string(),
                     match start.as_ref() {
                         Some(expr) => expr.pretty_string(),
@@ -1323,6 +1379,24 @@ impl QueryExpression {
             QueryExpression::Consistency(system) => {
                 format!("consistency: {}", system.pretty_string())
             }
+            QueryExpression::Implementation(system) => {
+                format!("implementation: {}", system.pretty_string())
+            }
+            QueryExpression::Specification(system) => {
+                format!("specification: {}", system.pretty_string())
+            }
+            QueryExpression::Deadlock(system) => {
+                format!("deadlock: {}", system.pretty_string())
+            }
+            QueryExpression::Timelock(system) => {
+                format!("timelock: {}", system.pretty_string())
+            }
+            QueryExpression::NonZeno(system) => {
+                format!("non-zeno: {}", system.pretty_string())
+            }
+            QueryExpression::Strategy(system) => {
+                format!("strategy: {}", system.pretty_string())
+            }
             QueryExpression::GetComponent(comp) => {
                 format!("get-component: {}", comp.pretty_string())
             }
@@ -1341,6 +1415,9 @@ impl QueryExpression {
             QueryExpression::Prune(comp) => {
                 format!("prune: {}", comp.pretty_string())
             }
+            QueryExpression::BisimMinimize(comp) => {
+                format!("bisim-minim: {}", comp.pretty_string())
+            }
             QueryExpression::Parentheses(system) => format!("({})", system.pretty_string()),
             QueryExpression::VarName(name) | QueryExpression::LocName(name) => name.clone(),
             QueryExpression::State(locs, clock) => {
@@ -1362,6 +1439,40 @@ impl QueryExpression {
                         .replace(" && ", ", ")
                 )
             }
+            QueryExpression::ComponentExpression(system, formula) => {
+                format!("{}: {}", system.pretty_string(), formula.pretty_string())
+            }
+            QueryExpression::Possibly(predicate) => format!("E<> {}", predicate.pretty_string()),
+            QueryExpression::Invariantly(predicate) => {
+                format!("A[] {}", predicate.pretty_string())
+            }
+            QueryExpression::EventuallyAlways(predicate) => {
+                format!("E[] {}", predicate.pretty_string())
+            }
+            QueryExpression::Potentially(predicate) => {
+                format!("A<> {}", predicate.pretty_string())
+            }
+            QueryExpression::AndOp(left, right) => {
+                format!("{} && {}", left.pretty_string(), right.pretty_string())
+            }
+            QueryExpression::OrOp(left, right) => {
+                format!("{} || {}", left.pretty_string(), right.pretty_string())
+            }
+            QueryExpression::LessEQ(left, right) => {
+                format!("{} <= {}", left.pretty_string(), right.pretty_string())
+            }
+            QueryExpression::GreatEQ(left, right) => {
+                format!("{} >= {}", left.pretty_string(), right.pretty_string())
+            }
+            QueryExpression::LessT(left, right) => {
+                format!("{} < {}", left.pretty_string(), right.pretty_string())
+            }
+            QueryExpression::GreatT(left, right) => {
+                format!("{} > {}", left.pretty_string(), right.pretty_string())
+            }
+            QueryExpression::Not(inner) => format!("not {}", inner.pretty_string()),
+            QueryExpression::Bool(value) => value.to_string(),
+            QueryExpression::Int(value) => value.to_string(),
             _ => panic!("Rule not implemented yet {:?}", self),
         }
     }
@@ -1,7 +1,8 @@
 use edbm::zones::OwnedFederation;
 
-use crate::TransitionSystems::{LocationTuple, TransitionSystemPtr};
+use crate::TransitionSystems::{LocationTuple, TransitionID, TransitionSystemPtr};
 use std::fmt::{Display, Formatter};
+use std::sync::Arc;
 
 #[derive(Clone, Debug)]
 pub struct StatePair {
@@ -9,6 +10,18 @@ pub struct StatePair {
     pub locations2: LocationTuple,
     /// The sentinel (Option) allows us to take ownership of the internal fed from a mutable reference
     zone_sentinel: Option<OwnedFederation>,
+    /// How the refinement check reached this pair, `None` for the initial pair
+    predecessor: Option<Arc<StatePairStep>>,
+}
+
+/// A step of a refinement check, taking `action` from `previous` with `transition1` in the
+/// left system and `transition2` in the right system
+#[derive(Clone, Debug)]
+pub struct StatePairStep {
+    pub previous: StatePair,
+    pub action: String,
+    pub transition1: TransitionID,
+    pub transition2: TransitionID,
 }
 
 impl StatePair {
@@ -23,7 +36,48 @@ impl StatePair {
             locations1,
             locations2,
             zone_sentinel: Some(zone),
+            predecessor: None,
+        }
+    }
+
+    pub fn set_predecessor(&mut self, step: StatePairStep) {
+        self.predecessor = Some(Arc::new(step));
+    }
+
+    /// Returns the steps taken from the initial pair to this pair
+    pub fn get_trace(&self) -> Vec<&StatePairStep> {
+        let mut trace = vec![];
+        let mut current = self;
+        while let Some(step) = &current.predecessor {
+            trace.push(step.as_ref());
+            current = &step.previous;
         }
+        trace.reverse();
+        trace
+    }
+
+    /// Returns the actions taken from the initial pair to this pair
+    pub fn get_trace_actions(&self) -> Vec<String> {
+        self.get_trace()
+            .into_iter()
+            .map(|step| step.action.clone())
+            .collect()
+    }
+
+    /// Formats the symbolic trace from the initial pair to this pair, one state pair or step per line
+    pub fn trace_to_string(&self) -> String {
+        let mut lines = vec![];
+        for step in self.get_trace() {
+            lines.push(step.previous.to_string());
+            lines.push(format!(
+                "  --{}--> by 1:{} 2:{}",
+                step.action,
+                format_transition_id(&step.transition1),
+                format_transition_id(&step.transition2)
+            ));
+        }
+        lines.push(self.to_string());
+        lines.join("\n")
     }
 
     pub fn get_locations1(&self) -> &LocationTuple {
@@ -81,6 +135,13 @@ impl StatePair {
     }
 }
 
+fn format_transition_id(id: &TransitionID) -> String {
+    match id {
+        TransitionID::None => "no edge".to_string(),
+        id => id.to_string(),
+    }
+}
+
 impl Display for StatePair {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.write_fmt(format_args!(
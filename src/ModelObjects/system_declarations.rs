@@ -1,4 +1,5 @@
-use crate::ModelObjects::component::Component;
+use crate::DataReader::serialization::decode_declarations;
+use crate::ModelObjects::component::{Component, Declarations};
 use log::debug;
 use serde::{Deserialize, Deserializer};
 use std::collections::HashMap;
@@ -40,6 +41,19 @@ impl SystemDeclarations {
     }
 }
 
+/// The declarations shared by all components of a project, such as global clocks, ints and broadcast channels
+#[derive(Debug, Deserialize, Clone)]
+pub struct GlobalDeclarations {
+    #[serde(deserialize_with = "decode_declarations")]
+    pub(crate) declarations: Declarations,
+}
+
+impl GlobalDeclarations {
+    pub fn get_declarations(&self) -> &Declarations {
+        &self.declarations
+    }
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct SystemSpecification {
     pub(crate) components: Vec<String>,
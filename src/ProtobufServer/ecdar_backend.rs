@@ -5,6 +5,7 @@ use crate::ProtobufServer::services::{
     QueryRequest, QueryResponse, SimulationStartRequest, SimulationStepRequest,
     SimulationStepResponse, UserTokenResponse,
 };
+use crate::System::limits::CancellationToken;
 use futures::FutureExt;
 use std::panic::UnwindSafe;
 use std::sync::atomic::{AtomicI32, Ordering};
@@ -87,8 +88,10 @@ impl EcdarBackend for ConcreteEcdarBackend {
         &self,
         request: Request<QueryRequest>,
     ) -> Result<Response<QueryResponse>, Status> {
-        self.handle_request(request, ConcreteEcdarBackend::handle_send_query)
-            .await
+        self.handle_request(request, |request, cache| {
+            ConcreteEcdarBackend::handle_send_query(request, cache, CancellationToken::unlimited())
+        })
+        .await
     }
 
     async fn start_simulation(
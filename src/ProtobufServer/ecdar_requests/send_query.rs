@@ -1,20 +1,22 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use crate::component::Component;
+use crate::component::{self, Component, Transition};
 use crate::extract_system_rep::SystemRecipeFailure;
+use crate::logging::{self, LogCapture};
 use crate::xml_parser::parse_xml_from_str;
-use crate::DataReader::component_loader::ModelCache;
+use crate::DataReader::component_loader::{ModelCache, QueryKey};
 use crate::DataReader::json_reader::json_to_component;
-use crate::DataReader::json_writer::component_to_json;
+use crate::DataReader::json_writer::{component_to_json, strategy_to_json};
 use crate::DataReader::parse_queries;
 use crate::ModelObjects::queries::Query;
+use crate::ModelObjects::representations::QueryExpression;
 use crate::ModelObjects::statepair::StatePair;
 use crate::ProtobufServer::services::component::Rep;
 use crate::ProtobufServer::services::query_response::{
     ComponentResult, ConsistencyResult as ProtobufConsistencyResult,
-    DeterminismResult as ProtobufDeterminismResult, ReachabilityResult, RefinementResult,
-    Result as ProtobufResult,
+    DeterminismResult as ProtobufDeterminismResult, Information, ReachabilityResult,
+    RefinementResult, Result as ProtobufResult,
 };
 use crate::ProtobufServer::services::{
     self, Component as ProtobufComponent, ComponentClock as ProtobufComponentClock,
@@ -24,24 +26,85 @@ use crate::ProtobufServer::services::{
 };
 use crate::ProtobufServer::ConcreteEcdarBackend;
 use crate::System::executable_query::QueryResult;
+use crate::System::limits::CancellationToken;
 use crate::System::local_consistency::{
     ConsistencyFailure, ConsistencyResult, DeterminismFailure, DeterminismResult,
+    ImplementationFailure, ImplementationResult, SpecificationFailure, SpecificationResult,
 };
-use crate::System::refine::{self, RefinementFailure};
-use crate::System::{extract_system_rep, input_enabler};
+use crate::System::refine::{self, RefinementFailure, RefinementRelation};
+use crate::System::strategy::StrategyResult;
+use crate::System::zeno::NonZenoResult;
+use crate::System::{extract_system_rep, input_enabler, reachability};
 use crate::TransitionSystems::{self, LocationID, TransitionID};
-use log::trace;
+use edbm::zones::OwnedFederation;
+use log::{info, trace, Level};
 use tonic::Status;
 
+/// The most pairs of a refinement relation sent in a response, as larger relations make the response too large to send
+const MAX_RELATION_PAIRS: usize = 1000;
+
 impl ConcreteEcdarBackend {
+    /// Handles a query request, which is stopped when `token` is cancelled or hits its limits.
+    ///
+    /// The response to an identical query on the same model with the same settings is reused from the cache,
+    /// unless the query could not be answered.
+    /// The log records of the query are sent as information in the response
     pub fn handle_send_query(
         query_request: QueryRequest,
-        mut model_cache: ModelCache,
+        model_cache: ModelCache,
+        token: CancellationToken,
     ) -> Result<QueryResponse, Status> {
         trace!("Received query: {:?}", query_request);
+        let mut query = parse_query(&query_request)?;
+        if let Some(ignored) = &query_request.ignored_input_outputs {
+            if !matches!(query.get_query(), Some(QueryExpression::Refinement(..))) {
+                return Err(Status::unimplemented(
+                    "ignored input outputs are only supported for refinement queries",
+                ));
+            }
+            query.ignore_actions(&ignored.ignored_inputs, &ignored.ignored_outputs);
+        }
+        // The relation is only sent when it is small enough, see MAX_RELATION_PAIRS
+        query.save_relation = true;
+
+        let key = QueryKey::new(
+            query_request.user_id,
+            query_request
+                .components_info
+                .as_ref()
+                .unwrap()
+                .components_hash,
+            &query,
+            query_request
+                .settings
+                .as_ref()
+                .unwrap_or(&crate::DEFAULT_SETTINGS),
+        );
+        if let Some(response) = model_cache.get_response(&key) {
+            return Ok(QueryResponse {
+                query_id: query_request.query_id,
+                ..response
+            });
+        }
+
+        let response = logging::with_capture(Some(LogCapture::default()), || {
+            Self::run_query(query_request, query, model_cache.clone(), token)
+        })?;
+        // Errors and inconclusive results are not cached, so the query is run again when it is sent again
+        if !matches!(response.result, Some(ProtobufResult::Error(_))) {
+            model_cache.insert_response(key, response.clone());
+        }
+        Ok(response)
+    }
+
+    fn run_query(
+        query_request: QueryRequest,
+        query: Query,
+        mut model_cache: ModelCache,
+        token: CancellationToken,
+    ) -> Result<QueryResponse, Status> {
         let components_info = query_request.components_info.as_ref().unwrap();
         let proto_components = &components_info.components;
-        let query = parse_query(&query_request)?;
         let user_id = query_request.user_id;
 
         let mut component_container =
@@ -63,27 +126,27 @@ impl ConcreteEcdarBackend {
             };
         component_container.set_settings(query_request.settings.unwrap_or(crate::DEFAULT_SETTINGS));
 
-        if query_request.ignored_input_outputs.is_some() {
-            return Err(Status::unimplemented(
-                "ignored input outputs are currently not supported",
-            ));
-        }
-
-        let executable_query =
-            match extract_system_rep::create_executable_query(&query, &mut component_container) {
-                Ok(query) => query,
-                Err(e) => {
-                    return Err(Status::invalid_argument(format!(
-                        "Creation of query failed: {}",
-                        e
-                    )))
-                }
-            };
+        let executable_query = match extract_system_rep::create_executable_query_with_token(
+            &query,
+            &mut component_container,
+            token,
+        ) {
+            Ok(query) => query,
+            Err(e) => {
+                return Err(Status::invalid_argument(format!(
+                    "Creation of query failed: {}",
+                    e
+                )))
+            }
+        };
         let result = executable_query.execute();
 
         let reply = QueryResponse {
             query_id: query_request.query_id,
-            info: vec![], // TODO: Should be logs
+            info: logging::get_messages()
+                .into_iter()
+                .map(make_information)
+                .collect(),
             result: convert_ecdar_result(&result),
         };
 
@@ -91,6 +154,14 @@ impl ConcreteEcdarBackend {
     }
 }
 
+/// Makes the information of a captured log record, with the level in its message
+fn make_information((level, message): (Level, String)) -> Information {
+    Information {
+        message: format!("{}: {}", level, message),
+        ..Default::default()
+    }
+}
+
 fn parse_query(query_request: &QueryRequest) -> Result<Query, Status> {
     let mut queries = parse_queries::parse_to_query(&query_request.query);
 
@@ -109,7 +180,7 @@ fn parse_components_if_some(
     if let Some(rep) = &proto_component.rep {
         match rep {
             Rep::Json(json) => parse_json_component(json),
-            Rep::Xml(xml) => Ok(parse_xml_components(xml)),
+            Rep::Xml(xml) => parse_xml_components(xml),
         }
     } else {
         Ok(vec![])
@@ -119,15 +190,21 @@ fn parse_components_if_some(
 fn parse_json_component(json: &str) -> Result<Vec<Component>, tonic::Status> {
     match json_to_component(json) {
         Ok(comp) => Ok(vec![comp]),
-        Err(_) => Err(tonic::Status::invalid_argument(
-            "Failed to parse json component",
-        )),
+        Err(err) => Err(tonic::Status::invalid_argument(format!(
+            "Failed to parse json component: {}",
+            err
+        ))),
     }
 }
 
-fn parse_xml_components(xml: &str) -> Vec<Component> {
-    let (comps, _, _) = parse_xml_from_str(xml);
-    comps
+fn parse_xml_components(xml: &str) -> Result<Vec<Component>, tonic::Status> {
+    match parse_xml_from_str(xml) {
+        Ok((comps, _, _)) => Ok(comps),
+        Err(err) => Err(tonic::Status::invalid_argument(format!(
+            "Failed to parse xml components: {}",
+            err
+        ))),
+    }
 }
 
 fn create_components(components: Vec<Component>) -> HashMap<String, Component> {
@@ -150,11 +227,11 @@ fn create_components(components: Vec<Component>) -> HashMap<String, Component> {
 fn convert_ecdar_result(query_result: &QueryResult) -> Option<ProtobufResult> {
     match query_result {
         QueryResult::Refinement(refines) => match refines {
-            refine::RefinementResult::Success => {
+            refine::RefinementResult::Success(relation) => {
                 Some(ProtobufResult::Refinement(RefinementResult {
                     success: true,
                     reason: "".to_string(),
-                    relation: vec![],
+                    relation: relation.as_ref().map_or(vec![], make_proto_relation),
                     state: None,
                     action: vec![], // Empty vec![] is used, when no failing action is available.
                 }))
@@ -162,48 +239,109 @@ fn convert_ecdar_result(query_result: &QueryResult) -> Option<ProtobufResult> {
             refine::RefinementResult::Failure(failure) => convert_refinement_failure(failure),
         },
 
-        QueryResult::Reachability(res) => {
-            let proto_path = TransitionID::split_into_component_lists(
-                &res.path
+        QueryResult::Reachability(res) => match make_component_paths(res.path.as_ref().unwrap()) {
+            Ok(component_paths) => Some(ProtobufResult::Reachability(ReachabilityResult {
+                success: res.was_reachable,
+                reason: if res.was_reachable {
+                    reachable_reason(res)
+                } else {
+                    "No path exists".to_string()
+                },
+                state: None,
+                component_paths,
+            })),
+            Err(e) => Some(ProtobufResult::Error(format!(
+                "Internal error occurred during reachability check: {}",
+                e
+            ))),
+        },
+        QueryResult::Tctl(res) => {
+            // There is no TCTL result in the protobuf, so the witness or counterexample is sent as a reachability path
+            let path = res
+                .path
+                .as_ref()
+                .and_then(|path| path.path.as_deref())
+                .unwrap_or_default();
+            match make_component_paths(path) {
+                Ok(component_paths) => Some(ProtobufResult::Reachability(ReachabilityResult {
+                    success: res.satisfied,
+                    reason: if res.satisfied {
+                        "".to_string()
+                    } else {
+                        "Property is not satisfied".to_string()
+                    },
+                    state: None,
+                    component_paths,
+                })),
+                Err(e) => Some(ProtobufResult::Error(format!(
+                    "Internal error occurred during TCTL check: {}",
+                    e
+                ))),
+            }
+        }
+
+        QueryResult::Lock(res) => {
+            // There is no lock result in the protobuf, so it is sent as a consistency result with the locked state
+            Some(ProtobufResult::Consistency(ProtobufConsistencyResult {
+                success: res.is_lock_free(),
+                reason: match &res.locked {
+                    Some((state, path)) => format!(
+                        "Found {} in location {} after the edges [{}]",
+                        res.kind,
+                        state.get_location().id,
+                        path.path
+                            .iter()
+                            .flatten()
+                            .map(|transition| transition.id.to_string())
+                            .collect::<Vec<String>>()
+                            .join(", ")
+                    ),
+                    None => "".to_string(),
+                },
+                state: res
+                    .locked
                     .as_ref()
-                    .unwrap()
-                    .iter()
-                    .map(|t| t.id.clone())
-                    .collect(),
-            );
-
-            match proto_path {
-                Ok(p) => {
-                    // Format into result expected by protobuf
-                    let component_paths = p
-                        .iter()
-                        .map(|component_path| services::Path {
-                            edge_ids: component_path
-                                .concat() // Concat to break the edges of the transitions into one vec instead a vec of vecs.
-                                .iter()
-                                .map(|id| id.to_string())
-                                .collect(),
-                        })
-                        .collect();
-
-                    Some(ProtobufResult::Reachability(ReachabilityResult {
-                        success: res.was_reachable,
-                        reason: if res.was_reachable {
-                            "".to_string()
-                        } else {
-                            "No path exists".to_string()
-                        },
-                        state: None,
-                        component_paths,
-                    }))
-                }
+                    .map(|(state, _)| make_proto_locked_state(state)),
+                action: vec![],
+            }))
+        }
+
+        QueryResult::NonZeno(res) => {
+            // There is no non-Zeno result in the protobuf, so the cycle is sent as a reachability path
+            let cycle = match res {
+                NonZenoResult::Success => vec![],
+                NonZenoResult::Failure(cycle) => cycle.transitions.clone(),
+            };
+            match make_component_paths_from_ids(&cycle) {
+                Ok(component_paths) => Some(ProtobufResult::Reachability(ReachabilityResult {
+                    success: matches!(res, NonZenoResult::Success),
+                    reason: match res {
+                        NonZenoResult::Success => "".to_string(),
+                        NonZenoResult::Failure(cycle) => cycle.to_string(),
+                    },
+                    state: None,
+                    component_paths,
+                })),
                 Err(e) => Some(ProtobufResult::Error(format!(
-                    "Internal error occurred during reachability check: {}",
+                    "Internal error occurred during non-Zeno check: {}",
                     e
                 ))),
             }
         }
 
+        QueryResult::Strategy(res) => match res {
+            // There is no strategy result in the protobuf, so the strategy is sent as json in a consistency result
+            StrategyResult::Success(strategy, _) => {
+                Some(ProtobufResult::Consistency(ProtobufConsistencyResult {
+                    success: true,
+                    reason: strategy_to_json(strategy),
+                    state: None,
+                    action: vec![],
+                }))
+            }
+            StrategyResult::Failure(failure) => convert_consistency_failure(failure),
+        },
+
         QueryResult::GetComponent(comp) => Some(ProtobufResult::Component(ComponentResult {
             component: Some(ProtobufComponent {
                 rep: Some(Rep::Json(component_to_json(comp))),
@@ -218,47 +356,55 @@ fn convert_ecdar_result(query_result: &QueryResult) -> Option<ProtobufResult> {
                     action: vec![],
                 }))
             }
-            ConsistencyResult::Failure(failure) => match failure {
-                ConsistencyFailure::NoInitialLocation | ConsistencyFailure::EmptyInitialState => {
-                    Some(ProtobufResult::Consistency(ProtobufConsistencyResult {
-                        success: false,
-                        reason: failure.to_string(),
-                        state: None,
-                        action: vec![],
-                    }))
-                }
-                ConsistencyFailure::NotConsistentFrom(location_id, action)
-                | ConsistencyFailure::NotDeterministicFrom(location_id, action) => {
-                    Some(ProtobufResult::Consistency(ProtobufConsistencyResult {
-                        success: false,
-                        reason: failure.to_string(),
-                        state: Some(State {
-                            location_tuple: Some(LocationTuple {
-                                locations: vec![Location {
-                                    id: location_id.to_string(),
-                                    specific_component: Some(SpecificComponent {
-                                        component_name: location_id.get_component_id()?,
-                                        component_index: 0,
-                                    }),
-                                }],
-                            }),
-                            federation: None,
-                        }),
-                        action: vec![action.to_string()],
-                    }))
-                }
-                ConsistencyFailure::NotDisjoint(srf) => {
-                    Some(ProtobufResult::Consistency(ProtobufConsistencyResult {
-                        success: false,
-                        reason: srf.reason.to_string(),
-                        state: Some(State {
-                            location_tuple: Some(make_location_vec_from_srf(srf))?,
-                            federation: None,
-                        }),
-                        action: srf.actions.clone(),
-                    }))
-                }
-            },
+            ConsistencyResult::Failure(failure) => convert_consistency_failure(failure),
+        },
+        QueryResult::Implementation(is_implementation) => match is_implementation {
+            // There is no implementation result in the protobuf, so it is sent as a consistency result
+            ImplementationResult::Success => {
+                Some(ProtobufResult::Consistency(ProtobufConsistencyResult {
+                    success: true,
+                    reason: "".to_string(),
+                    state: None,
+                    action: vec![],
+                }))
+            }
+            ImplementationResult::Failure(ImplementationFailure::NotConsistent(failure)) => {
+                convert_consistency_failure(failure)
+            }
+            ImplementationResult::Failure(
+                failure @ (ImplementationFailure::NotInputEnabled(location_id, action)
+                | ImplementationFailure::NotOutputUrgent(location_id, action)),
+            ) => Some(ProtobufResult::Consistency(ProtobufConsistencyResult {
+                success: false,
+                reason: failure.to_string(),
+                state: Some(make_state_from_location(location_id)?),
+                action: vec![action.to_string()],
+            })),
+        },
+        QueryResult::Specification(is_specification) => match is_specification {
+            // There is no specification result in the protobuf, so it is sent as the result of the failing check
+            SpecificationResult::Success => {
+                Some(ProtobufResult::Consistency(ProtobufConsistencyResult {
+                    success: true,
+                    reason: "".to_string(),
+                    state: None,
+                    action: vec![],
+                }))
+            }
+            SpecificationResult::Failure(SpecificationFailure::NotDeterministic(failure)) => {
+                convert_determinism_failure(failure)
+            }
+            SpecificationResult::Failure(SpecificationFailure::NotConsistent(failure)) => {
+                convert_consistency_failure(failure)
+            }
+            SpecificationResult::Failure(
+                failure @ SpecificationFailure::NotInputEnabled(location_id, action),
+            ) => Some(ProtobufResult::Consistency(ProtobufConsistencyResult {
+                success: false,
+                reason: failure.to_string(),
+                state: Some(make_state_from_location(location_id)?),
+                action: vec![action.to_string()],
+            })),
         },
         QueryResult::Determinism(is_deterministic) => match is_deterministic {
             DeterminismResult::Success => {
@@ -269,12 +415,68 @@ fn convert_ecdar_result(query_result: &QueryResult) -> Option<ProtobufResult> {
                     action: vec![],
                 }))
             }
-            DeterminismResult::Failure(DeterminismFailure::NotDeterministicFrom(
-                location_id,
-                action,
-            )) => Some(ProtobufResult::Determinism(ProtobufDeterminismResult {
+            DeterminismResult::Failure(failure) => convert_determinism_failure(failure),
+        },
+
+        // There is no inconclusive result in the protobuf, so the reason is sent as an error
+        QueryResult::Inconclusive(inconclusive) => {
+            Some(ProtobufResult::Error(inconclusive.to_string()))
+        }
+        QueryResult::Error(message) => Some(ProtobufResult::Error(message.clone())),
+    }
+}
+
+/// Describes which target a reachability query reached, how fast, and the concrete trace to it
+fn reachable_reason(path: &reachability::Path) -> String {
+    let mut lines = vec![];
+    if let Some(target) = &path.reached_target {
+        lines.push(format!("Reached target: {}", target));
+    }
+    if let Some(time) = &path.minimal_time {
+        lines.push(format!("Minimal time: {}", time));
+    }
+    if let Some(trace) = &path.concrete_trace {
+        lines.push(format!("Concrete trace:\n{}", trace));
+    }
+    lines.join("\n")
+}
+
+/// Splits the transitions of a path into the edges taken in each component
+fn make_component_paths(path: &[Transition]) -> Result<Vec<services::Path>, String> {
+    make_component_paths_from_ids(&path.iter().map(|t| t.id.clone()).collect())
+}
+
+fn make_component_paths_from_ids(path: &Vec<TransitionID>) -> Result<Vec<services::Path>, String> {
+    let proto_path = TransitionID::split_into_component_lists(path)?;
+
+    // Format into result expected by protobuf
+    Ok(proto_path
+        .iter()
+        .map(|component_path| services::Path {
+            edge_ids: component_path
+                .concat() // Concat to break the edges of the transitions into one vec instead a vec of vecs.
+                .iter()
+                .map(|id| id.to_string())
+                .collect(),
+        })
+        .collect())
+}
+
+fn convert_consistency_failure(failure: &ConsistencyFailure) -> Option<ProtobufResult> {
+    match failure {
+        ConsistencyFailure::NoInitialLocation | ConsistencyFailure::EmptyInitialState => {
+            Some(ProtobufResult::Consistency(ProtobufConsistencyResult {
+                success: false,
+                reason: failure.to_string(),
+                state: None,
+                action: vec![],
+            }))
+        }
+        ConsistencyFailure::NotConsistentFrom(location_id, action)
+        | ConsistencyFailure::NotDeterministicFrom(location_id, action) => {
+            Some(ProtobufResult::Consistency(ProtobufConsistencyResult {
                 success: false,
-                reason: "Not deterministic From Location".to_string(),
+                reason: failure.to_string(),
                 state: Some(State {
                     location_tuple: Some(LocationTuple {
                         locations: vec![Location {
@@ -288,21 +490,57 @@ fn convert_ecdar_result(query_result: &QueryResult) -> Option<ProtobufResult> {
                     federation: None,
                 }),
                 action: vec![action.to_string()],
-            })),
-            DeterminismResult::Failure(DeterminismFailure::NotDisjoint(srf)) => {
-                Some(ProtobufResult::Determinism(ProtobufDeterminismResult {
-                    success: false,
-                    reason: srf.reason.to_string(),
-                    state: Some(State {
-                        location_tuple: Some(make_location_vec_from_srf(srf))?,
-                        federation: None,
-                    }),
-                    action: srf.actions.clone(),
-                }))
-            }
-        },
+            }))
+        }
+        ConsistencyFailure::NotDisjoint(srf) => {
+            Some(ProtobufResult::Consistency(ProtobufConsistencyResult {
+                success: false,
+                reason: srf.reason.to_string(),
+                state: Some(State {
+                    location_tuple: Some(make_location_vec_from_srf(srf))?,
+                    federation: None,
+                }),
+                action: srf.actions.clone(),
+            }))
+        }
+    }
+}
 
-        QueryResult::Error(message) => Some(ProtobufResult::Error(message.clone())),
+fn convert_determinism_failure(failure: &DeterminismFailure) -> Option<ProtobufResult> {
+    match failure {
+        DeterminismFailure::NotDeterministicFrom(location_id, action, overlapping) => {
+            Some(ProtobufResult::Determinism(ProtobufDeterminismResult {
+                success: false,
+                reason: format!("Not deterministic From Location: {}", overlapping),
+                state: Some(State {
+                    location_tuple: Some(LocationTuple {
+                        locations: vec![Location {
+                            id: location_id.to_string(),
+                            specific_component: Some(SpecificComponent {
+                                component_name: location_id.get_component_id()?,
+                                component_index: 0,
+                            }),
+                        }],
+                    }),
+                    federation: Some(make_proto_federation(
+                        &overlapping.overlap,
+                        value_in_component(Some(location_id)),
+                    )),
+                }),
+                action: vec![action.to_string()],
+            }))
+        }
+        DeterminismFailure::NotDisjoint(srf) => {
+            Some(ProtobufResult::Determinism(ProtobufDeterminismResult {
+                success: false,
+                reason: srf.reason.to_string(),
+                state: Some(State {
+                    location_tuple: Some(make_location_vec_from_srf(srf))?,
+                    federation: None,
+                }),
+                action: srf.actions.clone(),
+            }))
+        }
     }
 }
 
@@ -322,15 +560,14 @@ fn convert_refinement_failure(failure: &RefinementFailure) -> Option<ProtobufRes
         }
         RefinementFailure::NotSubset
         | RefinementFailure::EmptySpecification
-        | RefinementFailure::EmptyImplementation => {
-            Some(ProtobufResult::Refinement(RefinementResult {
-                success: false,
-                relation: vec![],
-                state: None,
-                reason: failure.to_string(),
-                action: vec![],
-            }))
-        }
+        | RefinementFailure::EmptyImplementation
+        | RefinementFailure::UpdateError(_) => Some(ProtobufResult::Refinement(RefinementResult {
+            success: false,
+            relation: vec![],
+            state: None,
+            reason: failure.to_string(),
+            action: vec![],
+        })),
         RefinementFailure::NotDisjoint(srf) => Some(ProtobufResult::Refinement(RefinementResult {
             success: false,
             relation: vec![],
@@ -357,8 +594,8 @@ fn convert_refinement_failure(failure: &RefinementFailure) -> Option<ProtobufRes
                         ),
                     }),
                 }),
-                reason: failure.to_string(),
-                action: vec![],
+                reason: format!("{}\nTrace:\n{}", failure, state_pair.trace_to_string()),
+                action: state_pair.get_trace_actions(),
             }))
         }
         RefinementFailure::ConsistencyFailure(location_id, action)
@@ -402,6 +639,35 @@ fn make_location_vec_from_srf(srf: &SystemRecipeFailure) -> Option<LocationTuple
     Some(LocationTuple { locations: a })
 }
 
+fn make_state_from_location(location_id: &LocationID) -> Option<State> {
+    Some(State {
+        location_tuple: Some(LocationTuple {
+            locations: vec![Location {
+                id: location_id.to_string(),
+                specific_component: Some(SpecificComponent {
+                    component_name: location_id.get_component_id()?,
+                    component_index: 0,
+                }),
+            }],
+        }),
+        federation: None,
+    })
+}
+
+/// Converts a state found by a deadlock or timelock check to a protobuf state
+fn make_proto_locked_state(state: &component::State) -> State {
+    let location_id = &state.get_location().id;
+    State {
+        location_tuple: Some(LocationTuple {
+            locations: vec![make_relation_location(location_id)],
+        }),
+        federation: Some(make_proto_federation(
+            state.zone_ref(),
+            specific_component_of(location_id),
+        )),
+    }
+}
+
 fn make_location_vec(
     locations1: &TransitionSystems::LocationTuple,
     locations2: &TransitionSystems::LocationTuple,
@@ -426,18 +692,26 @@ fn make_location_vec(
 }
 
 fn make_proto_zone(state_pair: &StatePair) -> Option<Federation> {
-    let disjunction = state_pair.ref_zone().minimal_constraints();
+    let component = value_in_component(Some(&state_pair.locations1.id));
+    Some(make_proto_federation(state_pair.ref_zone(), component))
+}
+
+fn make_proto_federation(
+    federation: &OwnedFederation,
+    component: Option<SpecificComponent>,
+) -> Federation {
+    let disjunction = federation.minimal_constraints();
     let mut conjunctions: Vec<ProtobufConjunction> = vec![];
     for conjunction in disjunction.conjunctions.iter() {
         let mut constraints: Vec<ProtobufConstraint> = vec![];
         for constraint in conjunction.constraints.iter() {
             constraints.push(ProtobufConstraint {
                 x: Some(ProtobufComponentClock {
-                    specific_component: value_in_component(Some(&state_pair.locations1.id)),
+                    specific_component: component.clone(),
                     clock_name: constraint.i.to_string(),
                 }),
                 y: Some(ProtobufComponentClock {
-                    specific_component: value_in_component(Some(&state_pair.locations1.id)),
+                    specific_component: component.clone(),
                     clock_name: constraint.j.to_string(),
                 }),
                 strict: constraint.ineq().is_strict(),
@@ -446,9 +720,59 @@ fn make_proto_zone(state_pair: &StatePair) -> Option<Federation> {
         }
         conjunctions.push(ProtobufConjunction { constraints })
     }
-    Some(Federation {
+    Federation {
         disjunction: Some(ProtobufDisjunction { conjunctions }),
-    })
+    }
+}
+
+/// Converts the relation of a refinement check to a state for each pair of locations in it.
+/// A relation with more than [MAX_RELATION_PAIRS] pairs is left out
+fn make_proto_relation(relation: &RefinementRelation) -> Vec<State> {
+    if relation.pairs.len() > MAX_RELATION_PAIRS {
+        info!(
+            "The relation has {} pairs, more than the {} which are sent, so it is left out",
+            relation.pairs.len(),
+            MAX_RELATION_PAIRS
+        );
+        return vec![];
+    }
+
+    relation
+        .pairs
+        .iter()
+        .map(|pair| {
+            let (location1, location2) = (&pair.locations1.0, &pair.locations2.0);
+            State {
+                location_tuple: Some(LocationTuple {
+                    locations: vec![
+                        make_relation_location(location1),
+                        make_relation_location(location2),
+                    ],
+                }),
+                federation: Some(make_proto_federation(
+                    &pair.federation,
+                    specific_component_of(location1),
+                )),
+            }
+        })
+        .collect()
+}
+
+fn make_relation_location(location_id: &LocationID) -> Location {
+    Location {
+        id: location_id.to_string(),
+        specific_component: specific_component_of(location_id),
+    }
+}
+
+/// Like [value_in_component], but without a component for locations of composed systems
+fn specific_component_of(location_id: &LocationID) -> Option<SpecificComponent> {
+    location_id
+        .get_component_id()
+        .map(|component_name| SpecificComponent {
+            component_name,
+            component_index: 0,
+        })
 }
 
 fn value_in_location(maybe_location: &Option<LocationID>) -> String {
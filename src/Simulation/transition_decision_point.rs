@@ -1,5 +1,6 @@
 use crate::{
     component::{State, Transition},
+    System::strategy::{ConsistencyStrategy, StrategyMove},
     TransitionSystems::TransitionSystemPtr,
 };
 
@@ -42,6 +43,40 @@ impl TransitionDecisionPoint {
     pub fn possible_decisions(&self) -> &[Transition] {
         self.possible_decisions.as_ref()
     }
+
+    /// Returns the decision the `strategy` takes in the `source` state, or `None` if it delays.
+    pub fn strategy_decision(&self, strategy: &ConsistencyStrategy) -> Option<&Transition> {
+        match strategy.get_move(&self.source)? {
+            StrategyMove::Delay => None,
+            StrategyMove::Output { transition, .. } => self
+                .possible_decisions
+                .iter()
+                .find(|decision| &decision.id == transition),
+        }
+    }
+
+    /// Simulates the outputs the `strategy` takes from the `source` state, until it delays or reaches a state it has already been in.
+    /// Returns the transitions taken
+    pub fn follow_strategy(
+        &self,
+        system: &TransitionSystemPtr,
+        strategy: &ConsistencyStrategy,
+    ) -> Vec<Transition> {
+        let mut taken = vec![];
+        let mut visited = vec![self.source.clone()];
+        let mut decision_point = self.clone();
+
+        while let Some(transition) = decision_point.strategy_decision(strategy) {
+            let mut state = decision_point.source.clone();
+            if !transition.use_transition(&mut state) || state.is_contained_in_list(&visited) {
+                break;
+            }
+            taken.push(transition.clone());
+            visited.push(state.clone());
+            decision_point = TransitionDecisionPoint::from(system, &state);
+        }
+        taken
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,198 @@
+use log::debug;
+
+use crate::DataReader::parse_edge::Update;
+use crate::ModelObjects::component::{Component, Edge, Location, LocationType, SyncType};
+use crate::ModelObjects::representations::BoolExpression;
+
+/// The outgoing behaviour of a location, where the target is given by the block it belongs to
+type EdgeSignature<'a> = (
+    &'a str,
+    SyncType,
+    &'a Option<BoolExpression>,
+    &'a Option<Vec<Update>>,
+    usize,
+);
+
+/// Merges the timed bisimilar locations of a [`Component`].
+///
+/// Locations are bisimilar when they have the same invariant and every edge of one has an edge in the other
+/// with the same action, guard and updates leading to a bisimilar location.
+/// This is found by partition refinement, so the result is the coarsest such partition.
+pub fn minimize(component: &Component) -> Component {
+    let locations = component.get_locations();
+    let mut blocks = initial_partition(locations);
+    let mut block_count = count_blocks(&blocks);
+
+    loop {
+        let signatures: Vec<Vec<EdgeSignature>> = locations
+            .iter()
+            .map(|location| edge_signatures(component, location, locations, &blocks))
+            .collect();
+
+        let mut new_blocks = vec![0; locations.len()];
+        let mut representatives: Vec<usize> = vec![];
+        for index in 0..locations.len() {
+            let existing = representatives.iter().position(|&other| {
+                blocks[index] == blocks[other]
+                    && same_signatures(&signatures[index], &signatures[other])
+            });
+            new_blocks[index] = match existing {
+                Some(block) => block,
+                None => {
+                    representatives.push(index);
+                    representatives.len() - 1
+                }
+            };
+        }
+
+        blocks = new_blocks;
+        if representatives.len() == block_count {
+            break;
+        }
+        block_count = representatives.len();
+    }
+
+    debug!(
+        "Minimized component {} from {} to {} locations",
+        component.get_name(),
+        locations.len(),
+        block_count
+    );
+
+    build_quotient_component(component, &blocks, block_count)
+}
+
+/// Locations can only be bisimilar if they agree on their invariant and on being universal or inconsistent
+fn initial_partition(locations: &[Location]) -> Vec<usize> {
+    let mut representatives: Vec<&Location> = vec![];
+    locations
+        .iter()
+        .map(|location| {
+            match representatives.iter().position(|other| {
+                other.invariant == location.invariant
+                    && normalized_type(&other.location_type)
+                        == normalized_type(&location.location_type)
+            }) {
+                Some(block) => block,
+                None => {
+                    representatives.push(location);
+                    representatives.len() - 1
+                }
+            }
+        })
+        .collect()
+}
+
+/// Being initial does not change the behaviour of a location, so it is ignored when partitioning
+fn normalized_type(location_type: &LocationType) -> LocationType {
+    match location_type {
+        LocationType::Initial => LocationType::Normal,
+        other => other.clone(),
+    }
+}
+
+fn count_blocks(blocks: &[usize]) -> usize {
+    blocks.iter().max().map_or(0, |max| max + 1)
+}
+
+fn edge_signatures<'a>(
+    component: &'a Component,
+    location: &Location,
+    locations: &[Location],
+    blocks: &[usize],
+) -> Vec<EdgeSignature<'a>> {
+    component
+        .get_edges()
+        .iter()
+        .filter(|edge| edge.source_location == location.id)
+        .map(|edge| {
+            (
+                edge.sync.as_str(),
+                edge.sync_type,
+                &edge.guard,
+                &edge.update,
+                blocks[location_index(locations, &edge.target_location)],
+            )
+        })
+        .collect()
+}
+
+fn same_signatures(left: &[EdgeSignature], right: &[EdgeSignature]) -> bool {
+    left.iter().all(|edge| right.contains(edge)) && right.iter().all(|edge| left.contains(edge))
+}
+
+fn location_index(locations: &[Location], id: &str) -> usize {
+    locations
+        .iter()
+        .position(|location| location.id == id)
+        .unwrap_or_else(|| panic!("Edge refers to unknown location {}", id))
+}
+
+/// Builds the component with one location per block, using the first location of each block as representative
+fn build_quotient_component(
+    component: &Component,
+    blocks: &[usize],
+    block_count: usize,
+) -> Component {
+    let locations = component.get_locations();
+    let representatives: Vec<&Location> = (0..block_count)
+        .map(|block| &locations[blocks.iter().position(|b| *b == block).unwrap()])
+        .collect();
+
+    let new_locations = representatives
+        .iter()
+        .enumerate()
+        .map(|(block, representative)| {
+            let is_initial = locations.iter().zip(blocks).any(|(location, b)| {
+                *b == block && location.location_type == LocationType::Initial
+            });
+            Location {
+                location_type: if is_initial {
+                    LocationType::Initial
+                } else {
+                    representative.location_type.clone()
+                },
+                ..(*representative).clone()
+            }
+        })
+        .collect();
+
+    let mut new_edges: Vec<Edge> = vec![];
+    for edge in component.get_edges() {
+        let source_block = blocks[location_index(locations, &edge.source_location)];
+        if representatives[source_block].id != edge.source_location {
+            continue;
+        }
+        let target_block = blocks[location_index(locations, &edge.target_location)];
+        let new_edge = Edge {
+            target_location: representatives[target_block].id.clone(),
+            ..edge.clone()
+        };
+        if !new_edges
+            .iter()
+            .any(|other| same_behaviour(other, &new_edge))
+        {
+            new_edges.push(new_edge);
+        }
+    }
+
+    let mut minimized = Component {
+        name: component.name.clone(),
+        declarations: component.declarations.clone(),
+        locations: new_locations,
+        edges: new_edges,
+        input_edges: None,
+        output_edges: None,
+    };
+    minimized.create_edge_io_split();
+    minimized
+}
+
+fn same_behaviour(left: &Edge, right: &Edge) -> bool {
+    left.source_location == right.source_location
+        && left.target_location == right.target_location
+        && left.sync == right.sync
+        && left.sync_type == right.sync_type
+        && left.guard == right.guard
+        && left.update == right.update
+}
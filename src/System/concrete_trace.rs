@@ -0,0 +1,463 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::{Add, Sub};
+
+use edbm::util::constraints::{ClockIndex, Conjunction};
+use edbm::zones::OwnedFederation;
+
+use crate::ModelObjects::component::{State, Transition};
+use crate::TransitionSystems::TransitionID;
+
+/// A point in time, delay or clock value.
+/// Guards only use integer bounds, but strict bounds can force a trace to use fractions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimeValue {
+    numerator: i64,
+    denominator: i64,
+}
+
+impl TimeValue {
+    pub fn new(numerator: i64, denominator: i64) -> Self {
+        assert!(
+            denominator != 0,
+            "A time value cannot have a zero denominator"
+        );
+        let sign = if denominator < 0 { -1 } else { 1 };
+        let divisor = gcd(numerator.abs(), denominator.abs()).max(1);
+        TimeValue {
+            numerator: sign * numerator / divisor,
+            denominator: sign * denominator / divisor,
+        }
+    }
+
+    pub fn zero() -> Self {
+        Self::from(0)
+    }
+
+    pub fn numerator(&self) -> i64 {
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> i64 {
+        self.denominator
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denominator == 1
+    }
+
+    fn half(self) -> Self {
+        TimeValue::new(self.numerator, self.denominator * 2)
+    }
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+impl From<i32> for TimeValue {
+    fn from(value: i32) -> Self {
+        TimeValue::new(value as i64, 1)
+    }
+}
+
+impl Add for TimeValue {
+    type Output = TimeValue;
+
+    fn add(self, other: TimeValue) -> TimeValue {
+        TimeValue::new(
+            self.numerator * other.denominator + other.numerator * self.denominator,
+            self.denominator * other.denominator,
+        )
+    }
+}
+
+impl Sub for TimeValue {
+    type Output = TimeValue;
+
+    fn sub(self, other: TimeValue) -> TimeValue {
+        TimeValue::new(
+            self.numerator * other.denominator - other.numerator * self.denominator,
+            self.denominator * other.denominator,
+        )
+    }
+}
+
+impl Ord for TimeValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.numerator * other.denominator).cmp(&(other.numerator * self.denominator))
+    }
+}
+
+impl PartialOrd for TimeValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for TimeValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+/// The values of the clocks of a system, indexed by [ClockIndex]. Index 0 is the reference clock which is always 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClockValuation {
+    values: Vec<TimeValue>,
+}
+
+impl ClockValuation {
+    pub fn get(&self, clock: ClockIndex) -> TimeValue {
+        self.values[clock]
+    }
+
+    pub fn values(&self) -> &[TimeValue] {
+        &self.values
+    }
+
+    fn delay(&self, delay: TimeValue) -> ClockValuation {
+        let mut values = self.values.clone();
+        for value in values.iter_mut().skip(1) {
+            *value = *value + delay;
+        }
+        ClockValuation { values }
+    }
+
+    fn apply_updates(&self, transition: &Transition) -> ClockValuation {
+        let mut values = self.values.clone();
+        for update in &transition.updates {
+            values[update.clock_index] = TimeValue::from(update.value);
+        }
+        ClockValuation { values }
+    }
+
+    fn difference(&self, i: ClockIndex, j: ClockIndex) -> TimeValue {
+        self.values[i] - self.values[j]
+    }
+}
+
+/// One step of a concrete trace: wait for `delay` time units, then take the `transition`
+#[derive(Debug, Clone)]
+pub struct ConcreteStep {
+    pub delay: TimeValue,
+    pub transition: TransitionID,
+    /// The clock values when the transition is taken, before its updates
+    pub valuation: ClockValuation,
+}
+
+/// A timed trace with concrete delays and clock values which follows a symbolic path
+#[derive(Debug, Clone)]
+pub struct ConcreteTrace {
+    pub initial_valuation: ClockValuation,
+    pub steps: Vec<ConcreteStep>,
+    /// The time waited in the last location to reach the end state
+    pub final_delay: TimeValue,
+    pub final_valuation: ClockValuation,
+    pub clock_names: HashMap<String, ClockIndex>,
+}
+
+impl ConcreteTrace {
+    /// The total time the trace takes
+    pub fn duration(&self) -> TimeValue {
+        self.steps
+            .iter()
+            .fold(self.final_delay, |total, step| total + step.delay)
+    }
+
+    /// Returns the value of the clock named `clock` when the end state is reached
+    pub fn final_value(&self, clock: &str) -> Option<TimeValue> {
+        self.clock_names
+            .get(clock)
+            .map(|index| self.final_valuation.get(*index))
+    }
+
+    fn format_valuation(&self, valuation: &ClockValuation) -> String {
+        let mut names: Vec<(&String, &ClockIndex)> = self.clock_names.iter().collect();
+        names.sort_by_key(|(name, index)| (**index, (*name).clone()));
+        names
+            .into_iter()
+            .map(|(name, index)| format!("{}={}", name, valuation.get(*index)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl fmt::Display for ConcreteTrace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut time = TimeValue::zero();
+        writeln!(
+            f,
+            "t=0: start with {}",
+            self.format_valuation(&self.initial_valuation)
+        )?;
+        for step in &self.steps {
+            time = time + step.delay;
+            writeln!(
+                f,
+                "t={}: delay {}, take {} with {}",
+                time,
+                step.delay,
+                step.transition,
+                self.format_valuation(&step.valuation)
+            )?;
+        }
+        time = time + self.final_delay;
+        write!(
+            f,
+            "t={}: delay {}, reach end state with {}",
+            time,
+            self.final_delay,
+            self.format_valuation(&self.final_valuation)
+        )
+    }
+}
+
+/// Turns the symbolic `path` from `start_state` to `end_state`, as found by [find_path](super::reachability::find_path),
+/// into a concrete timed trace. The trace takes every transition as early as possible.
+///
+/// The zones are first computed forwards along the path, and then restricted backwards
+/// to the valuations from which the rest of the path can be followed to the end state.
+/// Returns `None` if the path cannot be followed.
+pub fn concretise_path(
+    start_state: &State,
+    end_state: &State,
+    path: &[Transition],
+    clock_names: HashMap<String, ClockIndex>,
+) -> Option<ConcreteTrace> {
+    let start_zone = start_state
+        .get_location()
+        .apply_invariants(start_state.zone_ref().clone());
+
+    // The valuations reachable in each location along the path
+    let mut reachable = vec![start_zone.clone()];
+    let mut state = start_state.clone();
+    state.set_zone(start_zone.clone());
+    for transition in path {
+        if !transition.use_transition(&mut state) {
+            return None;
+        }
+        reachable.push(state.zone_ref().clone());
+    }
+
+    // The valuations from which each transition can be taken and the rest of the path followed
+    let mut leaving = vec![OwnedFederation::empty(start_zone.dim()); path.len() + 1];
+    leaving[path.len()] = reachable[path.len()]
+        .clone()
+        .intersection(end_state.zone_ref());
+    for (index, transition) in path.iter().enumerate().rev() {
+        let entering = transition
+            .target_locations
+            .apply_invariants(leaving[index + 1].clone().down());
+        leaving[index] = transition
+            .apply_guards(transition.inverse_apply_updates(entering))
+            .intersection(&reachable[index]);
+    }
+
+    let initial_valuation = pick_valuation(&start_zone.intersection(&leaving[0].clone().down()))?;
+
+    let mut valuation = initial_valuation.clone();
+    let mut steps = Vec::with_capacity(path.len());
+    for (index, transition) in path.iter().enumerate() {
+        let delay = earliest_delay(&valuation, &leaving[index])?;
+        let taken_at = valuation.delay(delay);
+        valuation = taken_at.apply_updates(transition);
+        steps.push(ConcreteStep {
+            delay,
+            transition: transition.id.clone(),
+            valuation: taken_at,
+        });
+    }
+    let final_delay = earliest_delay(&valuation, &leaving[path.len()])?;
+
+    Some(ConcreteTrace {
+        initial_valuation,
+        steps,
+        final_delay,
+        final_valuation: valuation.delay(final_delay),
+        clock_names,
+    })
+}
+
+/// The earliest delay after which `valuation` is in `federation`, if there is one.
+fn earliest_delay(valuation: &ClockValuation, federation: &OwnedFederation) -> Option<TimeValue> {
+    federation
+        .minimal_constraints()
+        .conjunctions
+        .iter()
+        .filter_map(|conjunction| earliest_delay_in_zone(valuation, conjunction))
+        .min()
+}
+
+/// A lower or upper bound on a value, and whether it is strict
+type Bound = (TimeValue, bool);
+
+fn earliest_delay_in_zone(valuation: &ClockValuation, zone: &Conjunction) -> Option<TimeValue> {
+    let mut lower: Bound = (TimeValue::zero(), false);
+    let mut upper: Option<Bound> = None;
+
+    for constraint in zone.iter() {
+        let (i, j) = (constraint.i, constraint.j);
+        let ineq = constraint.ineq();
+        let bound = TimeValue::from(ineq.bound());
+        let strict = ineq.is_strict();
+
+        match (i, j) {
+            (0, 0) => {}
+            // valuation_i + delay < bound
+            (i, 0) => {
+                let candidate = (bound - valuation.get(i), strict);
+                if upper.map_or(true, |upper| is_tighter_upper(candidate, upper)) {
+                    upper = Some(candidate);
+                }
+            }
+            // -(valuation_j + delay) < bound
+            (0, j) => {
+                let candidate = (TimeValue::zero() - bound - valuation.get(j), strict);
+                if is_tighter_lower(candidate, lower) {
+                    lower = candidate;
+                }
+            }
+            // Differences between clocks do not change when time passes
+            (i, j) => {
+                let difference = valuation.difference(i, j);
+                if difference > bound || (strict && difference == bound) {
+                    return None;
+                }
+            }
+        }
+    }
+
+    pick_in_interval(lower, upper)
+}
+
+fn is_tighter_upper(candidate: Bound, current: Bound) -> bool {
+    candidate.0 < current.0 || (candidate.0 == current.0 && candidate.1)
+}
+
+fn is_tighter_lower(candidate: Bound, current: Bound) -> bool {
+    candidate.0 > current.0 || (candidate.0 == current.0 && candidate.1)
+}
+
+/// Picks the smallest value in the interval, or one close to the lower bound if it is strict
+fn pick_in_interval(lower: Bound, upper: Option<Bound>) -> Option<TimeValue> {
+    let (low, low_strict) = lower;
+    match upper {
+        Some((high, high_strict)) => {
+            if low > high || (low == high && (low_strict || high_strict)) {
+                None
+            } else if low_strict {
+                Some(low + (high - low).half().min(TimeValue::new(1, 2)))
+            } else {
+                Some(low)
+            }
+        }
+        None if low_strict => Some(low + TimeValue::new(1, 2)),
+        None => Some(low),
+    }
+}
+
+/// Picks a valuation in `federation`, choosing every clock as small as possible
+fn pick_valuation(federation: &OwnedFederation) -> Option<ClockValuation> {
+    let dim = federation.dim();
+    federation
+        .minimal_constraints()
+        .conjunctions
+        .iter()
+        .find_map(|conjunction| pick_valuation_in_zone(conjunction, dim))
+}
+
+/// The closed bounds of a zone: `bounds[i][j]` is the tightest bound on `clock_i - clock_j` and whether it is strict.
+type ClosedBounds = Vec<Vec<Option<(i64, bool)>>>;
+
+/// Makes all bounds implied by the constraints of a zone explicit. Returns `None` if the zone is empty.
+fn close_zone(zone: &Conjunction, dim: ClockIndex) -> Option<ClosedBounds> {
+    let mut bounds: ClosedBounds = vec![vec![None; dim]; dim];
+    for (i, row) in bounds.iter_mut().enumerate() {
+        row[i] = Some((0, false));
+    }
+    // Clocks are never negative
+    for j in 1..dim {
+        bounds[0][j] = Some((0, false));
+    }
+    for constraint in zone.iter() {
+        let ineq = constraint.ineq();
+        let candidate = (ineq.bound() as i64, ineq.is_strict());
+        let current = &mut bounds[constraint.i][constraint.j];
+        if current.map_or(true, |current| is_tighter_raw(candidate, current)) {
+            *current = Some(candidate);
+        }
+    }
+    for k in 0..dim {
+        for i in 0..dim {
+            for j in 0..dim {
+                if let (Some((a, a_strict)), Some((b, b_strict))) = (bounds[i][k], bounds[k][j]) {
+                    let candidate = (a + b, a_strict || b_strict);
+                    if bounds[i][j].map_or(true, |current| is_tighter_raw(candidate, current)) {
+                        bounds[i][j] = Some(candidate);
+                    }
+                }
+            }
+        }
+    }
+    if (0..dim).any(|i| bounds[i][i].map_or(false, |(bound, strict)| bound < 0 || strict)) {
+        return None;
+    }
+    Some(bounds)
+}
+
+/// The smallest value `clock` has in `federation`, and whether it is only approached but never reached.
+/// Returns `None` if the federation is empty.
+pub fn clock_infimum(federation: &OwnedFederation, clock: ClockIndex) -> Option<(TimeValue, bool)> {
+    let dim = federation.dim();
+    federation
+        .minimal_constraints()
+        .conjunctions
+        .iter()
+        .filter_map(|conjunction| close_zone(conjunction, dim))
+        .filter_map(|bounds| bounds[0][clock])
+        .map(|(bound, strict)| (TimeValue::new(-bound, 1), strict))
+        .min()
+}
+
+fn pick_valuation_in_zone(zone: &Conjunction, dim: ClockIndex) -> Option<ClockValuation> {
+    let bounds = close_zone(zone, dim)?;
+
+    let mut values = vec![TimeValue::zero(); dim];
+    for clock in 1..dim {
+        let mut lower: Bound = (TimeValue::zero(), false);
+        let mut upper: Option<Bound> = None;
+        for other in 0..clock {
+            // other - clock <= bound, so clock >= other - bound
+            if let Some((bound, strict)) = bounds[other][clock] {
+                let candidate = (values[other] - TimeValue::new(bound, 1), strict);
+                if is_tighter_lower(candidate, lower) {
+                    lower = candidate;
+                }
+            }
+            // clock - other <= bound, so clock <= other + bound
+            if let Some((bound, strict)) = bounds[clock][other] {
+                let candidate = (values[other] + TimeValue::new(bound, 1), strict);
+                if upper.map_or(true, |upper| is_tighter_upper(candidate, upper)) {
+                    upper = Some(candidate);
+                }
+            }
+        }
+        values[clock] = pick_in_interval(lower, upper)?;
+    }
+
+    Some(ClockValuation { values })
+}
+
+fn is_tighter_raw(candidate: (i64, bool), current: (i64, bool)) -> bool {
+    candidate.0 < current.0 || (candidate.0 == current.0 && candidate.1 && !current.1)
+}
@@ -0,0 +1,124 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use edbm::util::constraints::ClockIndex;
+use edbm::zones::OwnedFederation;
+
+use crate::DataTypes::SearchOrder;
+use crate::ModelObjects::component::State;
+use crate::System::local_consistency::delayable_federation;
+use crate::System::reachability::{make_path, search_zone_graph, Path};
+use crate::TransitionSystems::{LocationTuple, TransitionSystem};
+
+/// The kinds of states a system should not be able to reach
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockKind {
+    /// No action is enabled and time cannot pass
+    Deadlock,
+    /// Time is blocked by the invariant and no output will ever be enabled.
+    /// Inputs are not considered, as they depend on the environment and are enabled everywhere in input-enabled systems
+    Timelock,
+}
+
+impl fmt::Display for LockKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LockKind::Deadlock => write!(f, "deadlock"),
+            LockKind::Timelock => write!(f, "timelock"),
+        }
+    }
+}
+
+/// The result of a deadlock or timelock check.
+/// `locked` is the first reachable state with a lock, restricted to the locked valuations, and the path to it.
+pub struct LockResult {
+    pub kind: LockKind,
+    pub locked: Option<(State, Path)>,
+}
+
+impl LockResult {
+    /// Whether the system is free of the kind of lock
+    pub fn is_lock_free(&self) -> bool {
+        self.locked.is_none()
+    }
+}
+
+/// Searches the zone graph of the system from its initial state for a state with a lock of the given kind
+pub fn find_lock(system: &dyn TransitionSystem, kind: LockKind) -> Result<LockResult, String> {
+    let mut initial_state = system
+        .get_initial_state()
+        .ok_or_else(|| "No initial state in the transition system".to_string())?;
+    // The locks can happen after a delay in the initial location as well
+    let zone = initial_state.take_zone().up();
+    initial_state.set_zone(zone);
+
+    let mut locked_zone = None;
+    let sub_path = search_zone_graph(
+        &initial_state,
+        system,
+        SearchOrder::BreadthFirst,
+        |_| 0,
+        |state| {
+            let zone = get_locked_zone(state, system, kind);
+            let found = !zone.is_empty();
+            locked_zone = Some(zone);
+            found
+        },
+    );
+
+    Ok(LockResult {
+        kind,
+        locked: sub_path.map(|sub_path| {
+            let mut state = sub_path.destination_state.clone();
+            state.set_zone(locked_zone.unwrap());
+            (state, make_path(sub_path))
+        }),
+    })
+}
+
+/// Returns the valuations of `state` which have a lock of the given kind
+pub fn get_locked_zone(
+    state: &State,
+    system: &dyn TransitionSystem,
+    kind: LockKind,
+) -> OwnedFederation {
+    let location = state.get_location();
+    let zone = state.zone_ref().clone();
+
+    match kind {
+        LockKind::Deadlock => zone
+            .subtraction(&enabled_federation(location, system, system.get_actions()))
+            .subtraction(&delayable_federation(location, system.get_dim())),
+        LockKind::Timelock => {
+            let enabled = enabled_federation(location, system, system.get_output_actions());
+            zone.subtraction(&unbounded_federation(location, system.get_dim()))
+                .subtraction(&location.apply_invariants(enabled).down())
+        }
+    }
+}
+
+/// Returns the valuations in the invariant of `location` from which time can pass forever.
+/// These are checked per valuation, as some disjuncts of a zone can delay indefinitely while others cannot.
+fn unbounded_federation(location: &LocationTuple, dim: ClockIndex) -> OwnedFederation {
+    match location.get_invariants() {
+        Some(invariant) => invariant
+            .clone()
+            .subtraction(&invariant.clone().inverse().down()),
+        None => OwnedFederation::universe(dim),
+    }
+}
+
+/// Returns the valuations where a transition with one of the `actions` can be taken from `location`
+fn enabled_federation(
+    location: &LocationTuple,
+    system: &dyn TransitionSystem,
+    actions: HashSet<String>,
+) -> OwnedFederation {
+    let mut enabled = OwnedFederation::empty(system.get_dim());
+    for action in actions {
+        for transition in system.next_transitions(location, &action) {
+            enabled += transition.get_allowed_federation();
+        }
+    }
+    enabled
+}
@@ -1,19 +1,31 @@
+use edbm::util::bounds::Bounds;
 use edbm::util::constraints::ClockIndex;
 
 use crate::component::Transition;
 use crate::DataReader::component_loader::ComponentLoader;
+use crate::DataTypes::SearchOrder;
 use crate::ModelObjects::component::Component;
 use crate::ModelObjects::component::State;
+use crate::Simulation::transition_decision_point::TransitionDecisionPoint;
+use crate::System::bisimulation;
+use crate::System::deadlock::{self, LockKind, LockResult};
+use crate::System::limits::{self, CancellationToken, Inconclusive};
 use crate::System::reachability;
-use crate::System::reachability::Path;
+use crate::System::reachability::{Path, ReachabilityTarget};
 use crate::System::refine;
 use crate::System::save_component::combine_components;
+use crate::System::strategy::StrategyResult;
+use crate::System::tctl::{self, Predicate, TctlQuantifier, TctlResult};
+use crate::System::zeno::{self, NonZenoResult};
 use crate::TransitionSystems::transition_system::PrecheckResult;
-use crate::TransitionSystems::TransitionSystemPtr;
+use crate::TransitionSystems::{TransitionSystem, TransitionSystemPtr};
 
 use super::extract_system_rep::SystemRecipe;
-use super::local_consistency::{ConsistencyFailure, ConsistencyResult, DeterminismResult};
-use super::refine::RefinementResult;
+use super::local_consistency::{
+    self, ConsistencyFailure, ConsistencyResult, DeterminismResult, ImplementationFailure,
+    ImplementationResult, SpecificationFailure, SpecificationResult,
+};
+use super::refine::{IgnoredActions, RefinementFailure, RefinementOptions, RefinementResult};
 use super::save_component::PruningStrategy;
 
 pub enum QueryResult {
@@ -22,22 +34,47 @@ pub enum QueryResult {
     GetComponent(Component),
     Consistency(ConsistencyResult),
     Determinism(DeterminismResult),
+    Implementation(ImplementationResult),
+    Specification(SpecificationResult),
+    Tctl(TctlResult),
+    Lock(LockResult),
+    NonZeno(NonZenoResult),
+    Strategy(StrategyResult),
+    /// The query was stopped by its limits or cancelled before it was decided
+    Inconclusive(Inconclusive),
     Error(String),
 }
 
 impl QueryResult {
     pub fn print_result(&self, query_str: &str) {
         match self {
-            QueryResult::Refinement(RefinementResult::Success) => satisfied(query_str),
+            QueryResult::Refinement(RefinementResult::Success(relation)) => {
+                satisfied(query_str);
+                if let Some(relation) = relation {
+                    println!("With relation:\n{}", relation);
+                }
+            }
             QueryResult::Refinement(RefinementResult::Failure(failure)) => {
                 not_satisfied(query_str);
                 println!("\nGot failure: {}", failure);
+                if let Some(state_pair) = failure.get_state_pair() {
+                    println!("Trace:\n{}", state_pair.trace_to_string());
+                }
             }
 
             QueryResult::Reachability(path) => {
                 if path.was_reachable {
                     satisfied(query_str);
+                    if let Some(target) = &path.reached_target {
+                        println!("Reached target: {}", target);
+                    }
+                    if let Some(time) = &path.minimal_time {
+                        println!("Minimal time: {}", time);
+                    }
                     print_path(path.path.as_ref().unwrap());
+                    if let Some(trace) = &path.concrete_trace {
+                        println!("Concrete trace:\n{}", trace);
+                    }
                 } else {
                     not_satisfied(query_str)
                 }
@@ -47,11 +84,78 @@ impl QueryResult {
             QueryResult::Consistency(ConsistencyResult::Failure(_)) => not_satisfied(query_str),
 
             QueryResult::Determinism(DeterminismResult::Success) => satisfied(query_str),
-            QueryResult::Determinism(DeterminismResult::Failure(_)) => not_satisfied(query_str),
+            QueryResult::Determinism(DeterminismResult::Failure(failure)) => {
+                not_satisfied(query_str);
+                println!("\nGot failure: {}", failure);
+            }
+
+            QueryResult::Implementation(ImplementationResult::Success) => satisfied(query_str),
+            QueryResult::Implementation(ImplementationResult::Failure(failure)) => {
+                not_satisfied(query_str);
+                println!("\nGot failure: {}", failure);
+            }
+
+            QueryResult::Specification(SpecificationResult::Success) => satisfied(query_str),
+            QueryResult::Specification(SpecificationResult::Failure(failure)) => {
+                not_satisfied(query_str);
+                println!("\nGot failure: {}", failure);
+            }
+
+            QueryResult::Tctl(result) => {
+                if result.satisfied {
+                    satisfied(query_str);
+                } else {
+                    not_satisfied(query_str);
+                }
+                if let Some(path) = result.path.as_ref().and_then(|path| path.path.as_ref()) {
+                    print_path(path);
+                }
+            }
+
+            QueryResult::Lock(result) => match &result.locked {
+                None => satisfied(query_str),
+                Some((state, path)) => {
+                    not_satisfied(query_str);
+                    println!(
+                        "\nFound {} in location {}",
+                        result.kind,
+                        state.get_location().id
+                    );
+                    if let Some(path) = &path.path {
+                        print_path(path);
+                    }
+                }
+            },
+
+            QueryResult::NonZeno(NonZenoResult::Success) => satisfied(query_str),
+            QueryResult::NonZeno(NonZenoResult::Failure(cycle)) => {
+                not_satisfied(query_str);
+                println!("\nGot failure: {}", cycle);
+            }
+
+            QueryResult::Strategy(StrategyResult::Success(strategy, initial_outputs)) => {
+                satisfied(query_str);
+                println!("With strategy:\n{}", strategy);
+                if !initial_outputs.is_empty() {
+                    let outputs: Vec<String> =
+                        initial_outputs.iter().map(|id| id.to_string()).collect();
+                    println!(
+                        "From the initial state it outputs by {}",
+                        outputs.join(", ")
+                    );
+                }
+            }
+            QueryResult::Strategy(StrategyResult::Failure(failure)) => {
+                not_satisfied(query_str);
+                println!("\nGot failure: {}", failure);
+            }
 
             QueryResult::GetComponent(_) => {
                 println!("{} -- Component succesfully created", query_str)
             }
+            QueryResult::Inconclusive(inconclusive) => {
+                println!("{} -- {}", query_str, inconclusive)
+            }
             QueryResult::Error(_) => println!("{} -- Failed", query_str),
         };
     }
@@ -76,20 +180,69 @@ pub trait ExecutableQuery {
     fn execute(self: Box<Self>) -> QueryResult;
 }
 
+/// Returns the `result` of a query on `system`, unless an edge of the system could not be taken while it was found
+fn or_update_error(system: &dyn TransitionSystem, result: QueryResult) -> QueryResult {
+    match system.get_update_error() {
+        Some(error) => QueryResult::Error(error),
+        None => result,
+    }
+}
+
+/// Executes a query with `token` as the token of its thread, so its searches stop at the limits of the token.
+/// If they do, the query is inconclusive, whatever it returned.
+pub struct LimitedExecutor<'a> {
+    pub query: Box<dyn ExecutableQuery + 'a>,
+    pub token: CancellationToken,
+}
+
+impl<'a> ExecutableQuery for LimitedExecutor<'a> {
+    fn execute(self: Box<Self>) -> QueryResult {
+        let LimitedExecutor { query, token } = *self;
+        if let Some(inconclusive) = token.stopped() {
+            return QueryResult::Inconclusive(inconclusive);
+        }
+
+        let result = limits::with_token(&token, || query.execute());
+        match token.stopped() {
+            Some(inconclusive) => QueryResult::Inconclusive(inconclusive),
+            None => result,
+        }
+    }
+}
+
+/// A query which was stopped before it could be executed
+pub struct InconclusiveExecutor {
+    pub inconclusive: Inconclusive,
+}
+
+impl ExecutableQuery for InconclusiveExecutor {
+    fn execute(self: Box<Self>) -> QueryResult {
+        QueryResult::Inconclusive(self.inconclusive)
+    }
+}
+
 pub struct RefinementExecutor {
     pub sys1: TransitionSystemPtr,
     pub sys2: TransitionSystemPtr,
+    pub ignored: IgnoredActions,
+    pub save_relation: bool,
+    pub options: RefinementOptions,
 }
 
 impl ExecutableQuery for RefinementExecutor {
     fn execute(self: Box<Self>) -> QueryResult {
         let (sys1, sys2) = (self.sys1, self.sys2);
 
-        match refine::check_refinement(sys1, sys2) {
-            RefinementResult::Success => QueryResult::Refinement(RefinementResult::Success),
-            RefinementResult::Failure(the_failure) => {
-                QueryResult::Refinement(RefinementResult::Failure(the_failure))
+        let result = if self.save_relation {
+            refine::check_refinement_with_relation(sys1, sys2, &self.ignored, &self.options)
+        } else {
+            refine::check_refinement_with_options(sys1, sys2, &self.ignored, &self.options)
+        };
+        match result {
+            RefinementResult::Failure(RefinementFailure::UpdateError(error)) => {
+                QueryResult::Error(error)
             }
+            result => QueryResult::Refinement(result),
         }
     }
 }
@@ -102,16 +255,32 @@ pub struct ReachabilityExecutor {
     // s_state is the start state
     pub start_state: State,
 
-    // e_state is the end state, where we want to see whether end state is reachable from start state
-    pub end_state: State,
+    // targets are the end states, where we want to see whether any of them is reachable from start state
+    pub targets: Vec<ReachabilityTarget>,
+
+    // time_clock is the clock measuring the time since the start state, if the fastest path should be found
+    pub time_clock: Option<ClockIndex>,
+
+    // search_order is the order the states are searched in, when any path is to be found
+    pub search_order: SearchOrder,
 }
 impl ExecutableQuery for ReachabilityExecutor {
     fn execute(self: Box<Self>) -> QueryResult {
-        match reachability::find_path(
-            self.start_state,
-            self.end_state,
-            self.transition_system.as_ref(),
-        ) {
+        let result = match self.time_clock {
+            Some(time_clock) => reachability::find_fastest_path(
+                self.start_state,
+                &self.targets,
+                self.transition_system.as_ref(),
+                time_clock,
+            ),
+            None => reachability::find_path_to_any(
+                self.start_state,
+                &self.targets,
+                self.transition_system.as_ref(),
+                self.search_order,
+            ),
+        };
+        match result {
             Ok(res) => QueryResult::Reachability(res),
             Err(err_msg) => QueryResult::Error(err_msg),
         }
@@ -127,6 +296,32 @@ pub struct GetComponentExecutor<'a> {
 impl<'a> ExecutableQuery for GetComponentExecutor<'a> {
     fn execute(self: Box<Self>) -> QueryResult {
         let mut comp = combine_components(&self.system, PruningStrategy::Reachable);
+        if let Some(error) = self.system.get_update_error() {
+            return QueryResult::Error(error);
+        }
+        comp.name = self.comp_name;
+
+        comp.remake_edge_ids();
+
+        self.component_loader.save_component(comp.clone());
+
+        QueryResult::GetComponent(comp)
+    }
+}
+
+pub struct BisimMinimizeExecutor<'a> {
+    pub system: TransitionSystemPtr,
+    pub comp_name: String,
+    pub component_loader: &'a mut dyn ComponentLoader,
+}
+
+impl<'a> ExecutableQuery for BisimMinimizeExecutor<'a> {
+    fn execute(self: Box<Self>) -> QueryResult {
+        let comp = combine_components(&self.system, PruningStrategy::Reachable);
+        if let Some(error) = self.system.get_update_error() {
+            return QueryResult::Error(error);
+        }
+        let mut comp = bisimulation::minimize(&comp);
         comp.name = self.comp_name;
 
         comp.remake_edge_ids();
@@ -145,17 +340,20 @@ pub struct ConsistencyExecutor {
 impl ExecutableQuery for ConsistencyExecutor {
     fn execute(self: Box<Self>) -> QueryResult {
         let res = match self.recipe.compile(self.dim) {
-            Ok(system) => match system.precheck_sys_rep() {
-                PrecheckResult::Success => QueryResult::Consistency(ConsistencyResult::Success),
-                PrecheckResult::NotDeterministic(location, action) => {
-                    QueryResult::Consistency(ConsistencyResult::Failure(
-                        ConsistencyFailure::NotDeterministicFrom(location, action),
-                    ))
-                }
-                PrecheckResult::NotConsistent(failure) => {
-                    QueryResult::Consistency(ConsistencyResult::Failure(failure))
-                }
-            },
+            Ok(system) => {
+                let result = match system.precheck_sys_rep() {
+                    PrecheckResult::Success => QueryResult::Consistency(ConsistencyResult::Success),
+                    PrecheckResult::NotDeterministic(location, action) => {
+                        QueryResult::Consistency(ConsistencyResult::Failure(
+                            ConsistencyFailure::NotDeterministicFrom(location, action),
+                        ))
+                    }
+                    PrecheckResult::NotConsistent(failure) => {
+                        QueryResult::Consistency(ConsistencyResult::Failure(failure))
+                    }
+                };
+                or_update_error(system.as_ref(), result)
+            }
             Err(error) => QueryResult::Consistency(ConsistencyResult::Failure(
                 ConsistencyFailure::NotDisjoint(error),
             )),
@@ -171,6 +369,129 @@ pub struct DeterminismExecutor {
 impl ExecutableQuery for DeterminismExecutor {
     fn execute(self: Box<Self>) -> QueryResult {
         let is_deterministic = self.system.is_deterministic();
-        QueryResult::Determinism(is_deterministic)
+        or_update_error(
+            self.system.as_ref(),
+            QueryResult::Determinism(is_deterministic),
+        )
+    }
+}
+
+pub struct ImplementationExecutor {
+    pub recipe: Box<SystemRecipe>,
+    pub dim: ClockIndex,
+}
+
+impl ExecutableQuery for ImplementationExecutor {
+    fn execute(self: Box<Self>) -> QueryResult {
+        match self.recipe.compile(self.dim) {
+            Ok(system) => {
+                let result = local_consistency::is_implementation(system.as_ref());
+                or_update_error(system.as_ref(), QueryResult::Implementation(result))
+            }
+            Err(error) => QueryResult::Implementation(ImplementationResult::Failure(
+                ImplementationFailure::NotConsistent(ConsistencyFailure::NotDisjoint(error)),
+            )),
+        }
+    }
+}
+
+pub struct SpecificationExecutor {
+    pub recipe: Box<SystemRecipe>,
+    pub dim: ClockIndex,
+}
+
+impl ExecutableQuery for SpecificationExecutor {
+    fn execute(self: Box<Self>) -> QueryResult {
+        match self.recipe.compile(self.dim) {
+            Ok(system) => {
+                let result = local_consistency::is_specification(system.as_ref());
+                or_update_error(system.as_ref(), QueryResult::Specification(result))
+            }
+            Err(error) => QueryResult::Specification(SpecificationResult::Failure(
+                SpecificationFailure::NotConsistent(ConsistencyFailure::NotDisjoint(error)),
+            )),
+        }
+    }
+}
+
+/// Used to store input for the TCTL model checker
+pub struct TctlExecutor {
+    pub system: TransitionSystemPtr,
+    pub quantifier: TctlQuantifier,
+    pub predicate: Predicate,
+    // The bounds of the clock constraints in the predicate
+    pub bounds: Bounds,
+}
+
+impl ExecutableQuery for TctlExecutor {
+    fn execute(self: Box<Self>) -> QueryResult {
+        let result = tctl::check(
+            self.system.as_ref(),
+            self.quantifier,
+            self.predicate,
+            &self.bounds,
+        );
+        or_update_error(self.system.as_ref(), QueryResult::Tctl(result))
+    }
+}
+
+/// Used to store input for the deadlock and timelock checks
+pub struct LockExecutor {
+    pub system: TransitionSystemPtr,
+    pub kind: LockKind,
+}
+
+impl ExecutableQuery for LockExecutor {
+    fn execute(self: Box<Self>) -> QueryResult {
+        match deadlock::find_lock(self.system.as_ref(), self.kind) {
+            Ok(result) => or_update_error(self.system.as_ref(), QueryResult::Lock(result)),
+            Err(err_msg) => QueryResult::Error(err_msg),
+        }
+    }
+}
+
+pub struct StrategyExecutor {
+    pub system: TransitionSystemPtr,
+}
+
+impl ExecutableQuery for StrategyExecutor {
+    fn execute(self: Box<Self>) -> QueryResult {
+        let strategy =
+            match local_consistency::synthesize_consistency_strategy(self.system.as_ref()) {
+                Ok(strategy) => strategy,
+                Err(failure) => {
+                    return or_update_error(
+                        self.system.as_ref(),
+                        QueryResult::Strategy(StrategyResult::Failure(failure)),
+                    )
+                }
+            };
+
+        // Simulating the strategy shows the outputs it takes before it can delay
+        let initial_outputs = match TransitionDecisionPoint::initial(&self.system) {
+            Some(initial) => initial
+                .follow_strategy(&self.system, &strategy)
+                .into_iter()
+                .map(|transition| transition.id)
+                .collect(),
+            None => vec![],
+        };
+        or_update_error(
+            self.system.as_ref(),
+            QueryResult::Strategy(StrategyResult::Success(strategy, initial_outputs)),
+        )
+    }
+}
+
+pub struct NonZenoExecutor {
+    pub system: TransitionSystemPtr,
+}
+
+impl ExecutableQuery for NonZenoExecutor {
+    fn execute(self: Box<Self>) -> QueryResult {
+        match zeno::check_non_zeno(self.system.as_ref()) {
+            Ok(result) => or_update_error(self.system.as_ref(), QueryResult::NonZeno(result)),
+            Err(err_msg) => QueryResult::Error(err_msg),
+        }
     }
 }
@@ -4,6 +4,7 @@ use crate::extract_system_rep::SystemRecipe;
 use crate::EdgeEval::constraint_applyer::apply_constraints_to_state;
 use crate::ModelObjects::component::State;
 use crate::ModelObjects::representations::{BoolExpression, QueryExpression};
+use crate::System::reachability::ReachabilityTarget;
 use crate::TransitionSystems::{CompositionType, LocationID, LocationTuple, TransitionSystemPtr};
 use std::slice::Iter;
 
@@ -18,6 +19,22 @@ pub fn get_state(
     machine: &SystemRecipe,
     system: &TransitionSystemPtr,
 ) -> Result<State, String> {
+    let target = get_target(state_query, machine, system)?;
+    if !target.excluded_locations.is_empty() {
+        return Err(format!(
+            "Negated locations are only allowed in the end state, but got \"{}\"",
+            target.description
+        ));
+    }
+    Ok(target.state)
+}
+
+/// Like [get_state], but the locations may also be negated as `!L`, meaning any location of the component except `L`.
+pub fn get_target(
+    state_query: &QueryExpression,
+    machine: &SystemRecipe,
+    system: &TransitionSystemPtr,
+) -> Result<ReachabilityTarget, String> {
     if let QueryExpression::State(loc, clock) = state_query {
         let mut locations: Vec<&str> = Vec::new();
 
@@ -28,10 +45,20 @@ pub fn get_state(
             };
         }
 
-        Ok(State::create(
-            build_location_tuple(&mut locations.iter(), machine, system)?,
-            create_zone_given_constraints(clock.as_deref(), system)?,
-        ))
+        let mut excluded_locations = vec![];
+        Ok(ReachabilityTarget {
+            state: State::create(
+                build_location_tuple(
+                    &mut locations.iter(),
+                    machine,
+                    system,
+                    &mut excluded_locations,
+                )?,
+                create_zone_given_constraints(clock.as_deref(), system)?,
+            ),
+            excluded_locations,
+            description: state_query.pretty_string(),
+        })
     } else {
         Err(format!(
             "The following information \"{}\" could not be used to create a State",
@@ -44,13 +71,21 @@ fn create_zone_given_constraints(
     constraints: Option<&BoolExpression>,
     system: &TransitionSystemPtr,
 ) -> Result<OwnedFederation, String> {
+    // Clocks can also be referred to as `Component.clock`
+    let mut decls = system.get_combined_decls();
+    for ((clock, component), index) in system.clock_name_and_component_to_index_map() {
+        decls
+            .clocks
+            .insert(format!("{}.{}", component, clock), index);
+    }
+
     constraints
         .map_or_else(
             || Ok(OwnedFederation::universe(system.get_dim())),
             |clock| {
                 apply_constraints_to_state(
                     clock,
-                    &system.get_combined_decls(),
+                    &decls,
                     OwnedFederation::universe(system.get_dim()),
                 )
             },
@@ -62,34 +97,50 @@ fn build_location_tuple(
     locations: &mut Iter<&str>,
     machine: &SystemRecipe,
     system: &TransitionSystemPtr,
+    excluded_locations: &mut Vec<LocationID>,
 ) -> Result<LocationTuple, String> {
     match machine {
         SystemRecipe::Composition(left, right) => {
             let (left_system, right_system) = system.get_children();
             Ok(LocationTuple::compose(
-                &build_location_tuple(locations, left, left_system)?,
-                &build_location_tuple(locations, right, right_system)?,
+                &build_location_tuple(locations, left, left_system, excluded_locations)?,
+                &build_location_tuple(locations, right, right_system, excluded_locations)?,
                 CompositionType::Composition,
             ))
         }
         SystemRecipe::Conjunction(left, right) => {
             let (left_system, right_system) = system.get_children();
             Ok(LocationTuple::compose(
-                &build_location_tuple(locations, left, left_system)?,
-                &build_location_tuple(locations, right, right_system)?,
+                &build_location_tuple(locations, left, left_system, excluded_locations)?,
+                &build_location_tuple(locations, right, right_system, excluded_locations)?,
                 CompositionType::Conjunction,
             ))
         }
         SystemRecipe::Quotient(left, right, ..) => {
             let (left_system, right_system) = system.get_children();
             Ok(LocationTuple::merge_as_quotient(
-                &build_location_tuple(locations, left, left_system)?,
-                &build_location_tuple(locations, right, right_system)?,
+                &build_location_tuple(locations, left, left_system, excluded_locations)?,
+                &build_location_tuple(locations, right, right_system, excluded_locations)?,
             ))
         }
         SystemRecipe::Component(component) => match locations.next().unwrap().trim() {
             // It is ensured .next() will not give a None, since the number of location is same as number of component. This is also being checked in validate_reachability_input function, that is called before get_state
             "_" => Ok(LocationTuple::build_any_location_tuple()),
+            str if str.starts_with('!') => {
+                let id = LocationID::Simple {
+                    location_id: str[1..].trim().to_string(),
+                    component_id: Some(component.get_name().clone()),
+                };
+                if system.get_location(&id).is_none() {
+                    return Err(format!(
+                        "Location {} does not exist in the component {}",
+                        &str[1..],
+                        component.get_name()
+                    ));
+                }
+                excluded_locations.push(id);
+                Ok(LocationTuple::build_any_location_tuple())
+            }
             str => system
                 .get_location(&LocationID::Simple {
                     location_id: str.to_string(),
@@ -2,11 +2,14 @@ use crate::DataReader::component_loader::ComponentLoader;
 use crate::ModelObjects::component::Component;
 use crate::ModelObjects::queries::Query;
 use crate::ModelObjects::representations::QueryExpression;
+use crate::System::deadlock::LockKind;
 use crate::System::executable_query::{
-    ConsistencyExecutor, DeterminismExecutor, ExecutableQuery, GetComponentExecutor,
-    ReachabilityExecutor, RefinementExecutor,
+    BisimMinimizeExecutor, ConsistencyExecutor, DeterminismExecutor, ExecutableQuery,
+    GetComponentExecutor, ImplementationExecutor, InconclusiveExecutor, LimitedExecutor,
+    LockExecutor, NonZenoExecutor, ReachabilityExecutor, RefinementExecutor, SpecificationExecutor,
+    StrategyExecutor, TctlExecutor,
 };
-use crate::System::extract_state::get_state;
+use crate::System::extract_state::{get_state, get_target};
 use std::collections::HashMap;
 
 use crate::TransitionSystems::{
@@ -14,8 +17,13 @@ use crate::TransitionSystems::{
 };
 
 use crate::component::State;
+use crate::DataTypes::SearchOrder;
+use crate::System::limits::{self, CancellationToken};
 use crate::System::pruning;
+use crate::System::refine::{IgnoredActions, RefinementOptions};
+use crate::System::tctl::{self, Predicate, TctlQuantifier};
 use crate::TransitionSystems::transition_system::ClockReductionInstruction;
+use edbm::util::bounds::Bounds;
 use edbm::util::constraints::ClockIndex;
 use log::debug;
 use simple_error::bail;
@@ -85,147 +93,404 @@ impl SystemRecipeFailure {
     }
 }
 
+/// Creates the query, limited by the limits in the options of the component loader
+pub fn create_executable_query<'a>(
+    full_query: &Query,
+    component_loader: &'a mut (dyn ComponentLoader + 'static),
+) -> Result<Box<dyn ExecutableQuery + 'a>, Box<dyn Error>> {
+    let token = CancellationToken::new(component_loader.get_options().limits);
+    create_executable_query_with_token(full_query, component_loader, token)
+}
+
+/// Creates the query, limited by `token`, which can also be used to cancel it.
+/// The limits apply while the query is created as well, as it checks the consistency of quotients and conjunctions
+pub fn create_executable_query_with_token<'a>(
+    full_query: &Query,
+    component_loader: &'a mut (dyn ComponentLoader + 'static),
+    token: CancellationToken,
+) -> Result<Box<dyn ExecutableQuery + 'a>, Box<dyn Error>> {
+    let query = limits::with_token(&token, || {
+        build_executable_query(full_query, component_loader)
+    });
+    if let Some(inconclusive) = token.stopped() {
+        // The query is inconclusive whether or not it could be created
+        return Ok(Box::new(InconclusiveExecutor { inconclusive }));
+    }
+    Ok(Box::new(LimitedExecutor {
+        query: query?,
+        token,
+    }))
+}
+
 /// This function fetches the appropriate components based on the structure of the query and makes the enum structure match the query
 /// this function also handles setting up the correct indices for clocks based on the amount of components in each system representation
-pub fn create_executable_query<'a>(
+fn build_executable_query<'a>(
     full_query: &Query,
     component_loader: &'a mut (dyn ComponentLoader + 'static),
 ) -> Result<Box<dyn ExecutableQuery + 'a>, Box<dyn Error>> {
     let mut dim: ClockIndex = 0;
 
+    if !matches!(
+        full_query.get_query(),
+        Some(QueryExpression::Refinement(..))
+    ) && !(full_query.get_ignored_inputs().is_empty()
+        && full_query.get_ignored_outputs().is_empty())
+    {
+        bail!("Ignored inputs and outputs are only supported for refinement queries")
+    }
+
     if let Some(query) = full_query.get_query() {
         match query {
             QueryExpression::Refinement(left_side, right_side) => {
                 let mut quotient_index = None;
 
-                let mut left = get_system_recipe(left_side, component_loader, &mut dim, &mut quotient_index);
-                let mut right = get_system_recipe(right_side, component_loader, &mut dim, &mut quotient_index);
+                let mut left =
+                    get_system_recipe(left_side, component_loader, &mut dim, &mut quotient_index);
+                let mut right =
+                    get_system_recipe(right_side, component_loader, &mut dim, &mut quotient_index);
 
                 if !component_loader.get_settings().disable_clock_reduction {
-                    clock_reduction::clock_reduce(&mut left, Some(&mut right), &mut dim, quotient_index.is_some())?;
+                    clock_reduction::clock_reduce(
+                        &mut left,
+                        Some(&mut right),
+                        &mut dim,
+                        quotient_index.is_some(),
+                    )?;
                 }
 
                 Ok(Box::new(RefinementExecutor {
-                sys1: left.compile(dim)?,
-                sys2: right.compile(dim)?,
-            }))},
-            QueryExpression::Reachability(automata, start, end) => {
+                    sys1: left.compile(dim)?,
+                    sys2: right.compile(dim)?,
+                    ignored: IgnoredActions {
+                        inputs: full_query.get_ignored_inputs(),
+                        outputs: full_query.get_ignored_outputs(),
+                    },
+                    save_relation: full_query.save_relation,
+                    options: RefinementOptions {
+                        threads: component_loader.get_options().refinement_threads,
+                        search_order: component_loader
+                            .get_options()
+                            .search_order
+                            .unwrap_or(SearchOrder::DepthFirst),
+                    },
+                }))
+            }
+            QueryExpression::Reachability(automata, start, end)
+            | QueryExpression::FastestReachability(automata, start, end) => {
                 let machine = get_system_recipe(automata, component_loader, &mut dim, &mut None);
+                // The fastest path is found using an extra clock which measures the time since the start state
+                let time_clock = match query {
+                    QueryExpression::FastestReachability(..) => {
+                        dim += 1;
+                        Some(dim)
+                    }
+                    _ => None,
+                };
                 let transition_system = machine.clone().compile(dim)?;
 
-                validate_reachability_input(&machine, end)?;
+                let end_states = reachability_targets(end);
+                for end_state in &end_states {
+                    validate_reachability_input(&machine, end_state)?;
+                }
                 // Assign the start state to the initial state of the transition system if no start state is given by the query
                 let start_state: State = if let Some(state) = start.as_ref() {
                     validate_reachability_input(&machine, state)?;
-                    let state = get_state(state, &machine, &transition_system).map_err(|err| format!("Invalid Start state: {}",err))?;
+                    let state = get_state(state, &machine, &transition_system)
+                        .map_err(|err| format!("Invalid Start state: {}", err))?;
                     if state.get_location().id.is_partial_location() {
-                        return Err("Start state is a partial state, which it must not be".into())
+                        return Err("Start state is a partial state, which it must not be".into());
                     }
                     state
-                }
-                else {
+                } else {
                     match transition_system.get_initial_state() {
-                        Some(state)=> state,
-                        None => return Err("No start state in the transition system".into())
+                        Some(state) => state,
+                        None => return Err("No start state in the transition system".into()),
                     }
                 };
 
-                let end_state: State = get_state(end, &machine, &transition_system).map_err(|err| format!("Invalid End state: {}",err))?;
+                let targets = end_states
+                    .into_iter()
+                    .map(|end_state| {
+                        get_target(end_state, &machine, &transition_system)
+                            .map_err(|err| format!("Invalid End state: {}", err))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
 
                 Ok(Box::new(ReachabilityExecutor {
                     transition_system,
                     start_state,
-                    end_state,
+                    targets,
+                    time_clock,
+                    search_order: component_loader
+                        .get_options()
+                        .search_order
+                        .unwrap_or(SearchOrder::BreadthFirst),
                 }))
-            },
+            }
             QueryExpression::Consistency(query_expression) => {
                 let mut quotient_index = None;
                 let mut recipe = get_system_recipe(
                     query_expression,
                     component_loader,
                     &mut dim,
-                    &mut quotient_index
+                    &mut quotient_index,
                 );
 
                 if !component_loader.get_settings().disable_clock_reduction {
-                    clock_reduction::clock_reduce(&mut recipe, None, &mut dim, quotient_index.is_some())?;
+                    clock_reduction::clock_reduce(
+                        &mut recipe,
+                        None,
+                        &mut dim,
+                        quotient_index.is_some(),
+                    )?;
                 }
 
-                Ok(Box::new(ConsistencyExecutor {
-                    recipe,
-                    dim
-                }))
-            },
+                Ok(Box::new(ConsistencyExecutor { recipe, dim }))
+            }
+            QueryExpression::Implementation(query_expression) => {
+                let mut quotient_index = None;
+                let mut recipe = get_system_recipe(
+                    query_expression,
+                    component_loader,
+                    &mut dim,
+                    &mut quotient_index,
+                );
+
+                if !component_loader.get_settings().disable_clock_reduction {
+                    clock_reduction::clock_reduce(
+                        &mut recipe,
+                        None,
+                        &mut dim,
+                        quotient_index.is_some(),
+                    )?;
+                }
+
+                Ok(Box::new(ImplementationExecutor { recipe, dim }))
+            }
+            QueryExpression::Specification(query_expression) => {
+                let mut quotient_index = None;
+                let mut recipe = get_system_recipe(
+                    query_expression,
+                    component_loader,
+                    &mut dim,
+                    &mut quotient_index,
+                );
+
+                if !component_loader.get_settings().disable_clock_reduction {
+                    clock_reduction::clock_reduce(
+                        &mut recipe,
+                        None,
+                        &mut dim,
+                        quotient_index.is_some(),
+                    )?;
+                }
+
+                Ok(Box::new(SpecificationExecutor { recipe, dim }))
+            }
             QueryExpression::Determinism(query_expression) => {
                 let mut quotient_index = None;
                 let mut recipe = get_system_recipe(
                     query_expression,
                     component_loader,
                     &mut dim,
-                    &mut quotient_index
+                    &mut quotient_index,
                 );
 
                 if !component_loader.get_settings().disable_clock_reduction {
-                    clock_reduction::clock_reduce(&mut recipe, None, &mut dim, quotient_index.is_some())?;
+                    clock_reduction::clock_reduce(
+                        &mut recipe,
+                        None,
+                        &mut dim,
+                        quotient_index.is_some(),
+                    )?;
                 }
 
                 Ok(Box::new(DeterminismExecutor {
                     system: recipe.compile(dim)?,
                 }))
-            },
+            }
+            QueryExpression::Deadlock(query_expression)
+            | QueryExpression::Timelock(query_expression) => {
+                let mut quotient_index = None;
+                let mut recipe = get_system_recipe(
+                    query_expression,
+                    component_loader,
+                    &mut dim,
+                    &mut quotient_index,
+                );
+
+                if !component_loader.get_settings().disable_clock_reduction {
+                    clock_reduction::clock_reduce(
+                        &mut recipe,
+                        None,
+                        &mut dim,
+                        quotient_index.is_some(),
+                    )?;
+                }
+
+                Ok(Box::new(LockExecutor {
+                    system: recipe.compile(dim)?,
+                    kind: match query {
+                        QueryExpression::Deadlock(_) => LockKind::Deadlock,
+                        _ => LockKind::Timelock,
+                    },
+                }))
+            }
+            QueryExpression::NonZeno(query_expression) => {
+                let mut quotient_index = None;
+                let mut recipe = get_system_recipe(
+                    query_expression,
+                    component_loader,
+                    &mut dim,
+                    &mut quotient_index,
+                );
+
+                if !component_loader.get_settings().disable_clock_reduction {
+                    clock_reduction::clock_reduce(
+                        &mut recipe,
+                        None,
+                        &mut dim,
+                        quotient_index.is_some(),
+                    )?;
+                }
+
+                Ok(Box::new(NonZenoExecutor {
+                    system: recipe.compile(dim)?,
+                }))
+            }
+            QueryExpression::Strategy(query_expression) => {
+                let mut quotient_index = None;
+                let mut recipe = get_system_recipe(
+                    query_expression,
+                    component_loader,
+                    &mut dim,
+                    &mut quotient_index,
+                );
+
+                if !component_loader.get_settings().disable_clock_reduction {
+                    clock_reduction::clock_reduce(
+                        &mut recipe,
+                        None,
+                        &mut dim,
+                        quotient_index.is_some(),
+                    )?;
+                }
+
+                Ok(Box::new(StrategyExecutor {
+                    system: recipe.compile(dim)?,
+                }))
+            }
             QueryExpression::GetComponent(save_as_expression) => {
-                if let QueryExpression::SaveAs(query_expression, comp_name) = save_as_expression.as_ref() {
+                if let QueryExpression::SaveAs(query_expression, comp_name) =
+                    save_as_expression.as_ref()
+                {
                     let mut quotient_index = None;
                     let mut recipe = get_system_recipe(
                         query_expression,
                         component_loader,
                         &mut dim,
-                        &mut quotient_index
+                        &mut quotient_index,
                     );
 
                     if !component_loader.get_settings().disable_clock_reduction {
-                        clock_reduction::clock_reduce(&mut recipe, None, &mut dim, quotient_index.is_some())?;
+                        clock_reduction::clock_reduce(
+                            &mut recipe,
+                            None,
+                            &mut dim,
+                            quotient_index.is_some(),
+                        )?;
                     }
 
-                    Ok(Box::new(
-                        GetComponentExecutor {
-                            system: recipe.compile(dim)?,
-                            comp_name: comp_name.clone(),
-                            component_loader,
-                        }
-                    ))
-                }else{
+                    Ok(Box::new(GetComponentExecutor {
+                        system: recipe.compile(dim)?,
+                        comp_name: comp_name.clone(),
+                        component_loader,
+                    }))
+                } else {
+                    bail!("Unexpected expression type")
+                }
+            }
+            QueryExpression::BisimMinimize(save_as_expression) => {
+                if let QueryExpression::SaveAs(query_expression, comp_name) =
+                    save_as_expression.as_ref()
+                {
+                    let mut quotient_index = None;
+                    let mut recipe = get_system_recipe(
+                        query_expression,
+                        component_loader,
+                        &mut dim,
+                        &mut quotient_index,
+                    );
+
+                    if !component_loader.get_settings().disable_clock_reduction {
+                        clock_reduction::clock_reduce(
+                            &mut recipe,
+                            None,
+                            &mut dim,
+                            quotient_index.is_some(),
+                        )?;
+                    }
+
+                    Ok(Box::new(BisimMinimizeExecutor {
+                        system: recipe.compile(dim)?,
+                        comp_name: comp_name.clone(),
+                        component_loader,
+                    }))
+                } else {
                     bail!("Unexpected expression type")
                 }
             }
-            ,
             QueryExpression::Prune(save_as_expression) => {
-                if let QueryExpression::SaveAs(query_expression, comp_name) = save_as_expression.as_ref() {
+                if let QueryExpression::SaveAs(query_expression, comp_name) =
+                    save_as_expression.as_ref()
+                {
                     let mut quotient_index = None;
                     let mut recipe = get_system_recipe(
                         query_expression,
                         component_loader,
                         &mut dim,
-                        &mut quotient_index
+                        &mut quotient_index,
                     );
 
                     if !component_loader.get_settings().disable_clock_reduction {
-                        clock_reduction::clock_reduce(&mut recipe, None, &mut dim, quotient_index.is_some())?;
+                        clock_reduction::clock_reduce(
+                            &mut recipe,
+                            None,
+                            &mut dim,
+                            quotient_index.is_some(),
+                        )?;
                     }
 
-                    Ok(Box::new(
-                        GetComponentExecutor {
-                            system: pruning::prune_system(recipe.compile(dim)?, dim),
-                            comp_name: comp_name.clone(),
-                            component_loader
-                        }
-                    ))
-                }else{
+                    Ok(Box::new(GetComponentExecutor {
+                        system: pruning::prune_system(recipe.compile(dim)?, dim),
+                        comp_name: comp_name.clone(),
+                        component_loader,
+                    }))
+                } else {
                     bail!("Unexpected expression type")
                 }
             }
-            ,
-            // Should handle consistency, Implementation, determinism and specification here, but we cant deal with it atm anyway
+            QueryExpression::ComponentExpression(system_expression, formula) => {
+                create_tctl_executor(system_expression, formula, component_loader)
+            }
+            QueryExpression::Possibly(_)
+            | QueryExpression::Invariantly(_)
+            | QueryExpression::EventuallyAlways(_)
+            | QueryExpression::Potentially(_) => {
+                // Without an explicit system, the formula is checked on the composition of the components it refers to
+                let mut components = tctl::referenced_components(query)
+                    .into_iter()
+                    .map(QueryExpression::VarName);
+                let first = match components.next() {
+                    Some(component) => component,
+                    None => bail!("No system given for {}, either prefix it with a system or refer to locations as Component.Location", query.pretty_string()),
+                };
+                let system_expression = components.fold(first, |left, right| {
+                    QueryExpression::Composition(Box::new(left), Box::new(right))
+                });
+
+                create_tctl_executor(&system_expression, query, component_loader)
+            }
             _ => bail!("Not yet setup to handle {:?}", query),
         }
     } else {
@@ -233,6 +498,34 @@ pub fn create_executable_query<'a>(
     }
 }
 
+fn create_tctl_executor<'a>(
+    system_expression: &QueryExpression,
+    formula: &QueryExpression,
+    component_loader: &'a mut (dyn ComponentLoader + 'static),
+) -> Result<Box<dyn ExecutableQuery + 'a>, Box<dyn Error>> {
+    let (quantifier, predicate) = match TctlQuantifier::from_formula(formula) {
+        Some(formula) => formula,
+        None => bail!(
+            "Expected a TCTL formula but got {}",
+            formula.pretty_string()
+        ),
+    };
+
+    let mut dim: ClockIndex = 0;
+    let recipe = get_system_recipe(system_expression, component_loader, &mut dim, &mut None);
+    let system = recipe.compile(dim)?;
+
+    let mut bounds = Bounds::new(system.get_dim());
+    let predicate = Predicate::compile(predicate, system.as_ref(), &mut bounds)?;
+
+    Ok(Box::new(TctlExecutor {
+        system,
+        quantifier,
+        predicate,
+        bounds,
+    }))
+}
+
 #[derive(Clone)]
 pub enum SystemRecipe {
     Composition(Box<SystemRecipe>, Box<SystemRecipe>),
@@ -245,17 +538,22 @@ impl SystemRecipe {
     pub fn compile(self, dim: ClockIndex) -> Result<TransitionSystemPtr, SystemRecipeFailure> {
         match self {
             SystemRecipe::Composition(left, right) => {
+                check_shared_globals(&left, &right)?;
                 Composition::new(left.compile(dim)?, right.compile(dim)?, dim + 1)
             }
             SystemRecipe::Conjunction(left, right) => {
+                check_shared_globals(&left, &right)?;
                 Conjunction::new(left.compile(dim)?, right.compile(dim)?, dim + 1)
             }
-            SystemRecipe::Quotient(left, right, clock_index) => Quotient::new(
-                left.compile(dim)?,
-                right.compile(dim)?,
-                clock_index,
-                dim + 1,
-            ),
+            SystemRecipe::Quotient(left, right, clock_index) => {
+                check_shared_globals(&left, &right)?;
+                Quotient::new(
+                    left.compile(dim)?,
+                    right.compile(dim)?,
+                    clock_index,
+                    dim + 1,
+                )
+            }
             SystemRecipe::Component(comp) => match CompiledComponent::compile(*comp, dim + 1) {
                 Ok(comp) => Ok(comp),
                 Err(err) => Err(err),
@@ -313,6 +611,21 @@ impl SystemRecipe {
             SystemRecipe::Component(c) => vec![c],
         }
     }
+
+    /// Gets all components in `SystemRecipe` without allowing changes to them
+    fn components(&self) -> Vec<&Component> {
+        match self {
+            SystemRecipe::Composition(left, right)
+            | SystemRecipe::Conjunction(left, right)
+            | SystemRecipe::Quotient(left, right, _) => {
+                let mut o = left.components();
+                o.extend(right.components());
+                o
+            }
+            SystemRecipe::Component(c) => vec![c],
+        }
+    }
+
     fn change_quotient(&mut self, index: ClockIndex) {
         match self {
             SystemRecipe::Composition(l, r) | SystemRecipe::Conjunction(l, r) => {
@@ -329,6 +642,39 @@ impl SystemRecipe {
     }
 }
 
+/// Fails if a global clock or int is assigned to by a component on one side and used by a component on the other side,
+/// as every component has its own copy of the globals which would silently diverge
+fn check_shared_globals(
+    left: &SystemRecipe,
+    right: &SystemRecipe,
+) -> Result<(), SystemRecipeFailure> {
+    for l in left.components() {
+        for r in right.components() {
+            let conflict = l
+                .get_assigned_globals()
+                .intersection(&r.get_used_globals())
+                .chain(r.get_assigned_globals().intersection(&l.get_used_globals()))
+                .next()
+                .map(|name| name.to_string());
+
+            if let Some(name) = conflict {
+                return Err(SystemRecipeFailure {
+                    reason: format!(
+                        "The global variable {} is assigned to in {} or {} and used in the other, which is not supported",
+                        name,
+                        l.get_name(),
+                        r.get_name()
+                    ),
+                    left_name: Some(l.get_name().clone()),
+                    right_name: Some(r.get_name().clone()),
+                    actions: vec![],
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
 pub fn get_system_recipe(
     side: &QueryExpression,
     component_loader: &mut dyn ComponentLoader,
@@ -378,6 +724,18 @@ pub fn get_system_recipe(
     }
 }
 
+/// Returns the end states of a reachability query, which are several if they are separated by `||`
+fn reachability_targets(end: &QueryExpression) -> Vec<&QueryExpression> {
+    match end {
+        QueryExpression::OrOp(left, right) => {
+            let mut targets = reachability_targets(left);
+            targets.extend(reachability_targets(right));
+            targets
+        }
+        state => vec![state],
+    }
+}
+
 fn validate_reachability_input(
     machine: &SystemRecipe,
     state: &QueryExpression,
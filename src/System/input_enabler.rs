@@ -6,6 +6,11 @@ use crate::ModelObjects::component::DeclarationProvider;
 use crate::ModelObjects::representations::BoolExpression;
 
 pub fn make_input_enabled(component: &mut component::Component, inputs: &[String]) {
+    // Whether an input is enabled depends on the values of the ints, so it is done when the component is explored
+    if !component.get_declarations().get_ints().is_empty() {
+        return;
+    }
+
     let dimension = component.declarations.get_clock_count() + 1;
     let mut new_edges: Vec<component::Edge> = vec![];
 
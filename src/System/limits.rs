@@ -0,0 +1,246 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::fs;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Once};
+use std::time::{Duration, Instant};
+
+use log::{info, warn};
+
+/// How often, in explored states, the memory of the process is checked, as reading it is slow
+const MEMORY_CHECK_INTERVAL: u64 = 1024;
+
+/// The limits a query is stopped at, where `None` means unlimited
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct QueryLimits {
+    pub timeout: Option<Duration>,
+    pub max_states: Option<u64>,
+    /// The most memory in bytes the whole process may use, including any other running queries,
+    /// as the memory of a single query is not known
+    pub max_memory: Option<u64>,
+}
+
+impl QueryLimits {
+    pub const UNLIMITED: QueryLimits = QueryLimits {
+        timeout: None,
+        max_states: None,
+        max_memory: None,
+    };
+}
+
+/// Why a query was stopped
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StopReason {
+    Timeout(Duration),
+    StateLimit(u64),
+    MemoryLimit(u64),
+    Cancelled,
+}
+
+impl fmt::Display for StopReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StopReason::Timeout(timeout) => {
+                write!(f, "Timed out after {:.3}s", timeout.as_secs_f64())
+            }
+            StopReason::StateLimit(states) => write!(f, "Explored more than {} states", states),
+            StopReason::MemoryLimit(bytes) => {
+                write!(f, "Used more than {} MB of memory", bytes / (1024 * 1024))
+            }
+            StopReason::Cancelled => write!(f, "Cancelled"),
+        }
+    }
+}
+
+/// How far a query got before it was stopped
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SearchStats {
+    pub explored_states: u64,
+    pub elapsed: Duration,
+}
+
+impl fmt::Display for SearchStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "explored {} states in {:.3}s",
+            self.explored_states,
+            self.elapsed.as_secs_f64()
+        )
+    }
+}
+
+/// The result of a query which was stopped before it was decided
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Inconclusive {
+    pub reason: StopReason,
+    pub stats: SearchStats,
+}
+
+impl fmt::Display for Inconclusive {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Inconclusive: {} ({})", self.reason, self.stats)
+    }
+}
+
+/// The token a query checks its limits with as it explores states, which can also be used to cancel it from another thread.
+///
+/// Once the query is stopped, it stays stopped, so the searches can return early with any result,
+/// which is then replaced by [Inconclusive] when the query finishes.
+#[derive(Clone, Debug)]
+pub struct CancellationToken {
+    state: Arc<TokenState>,
+}
+
+#[derive(Debug)]
+struct TokenState {
+    limits: QueryLimits,
+    start: Instant,
+    explored_states: AtomicU64,
+    stopped: AtomicBool,
+    reason: Mutex<Option<StopReason>>,
+}
+
+impl CancellationToken {
+    pub fn new(limits: QueryLimits) -> Self {
+        CancellationToken {
+            state: Arc::new(TokenState {
+                limits,
+                start: Instant::now(),
+                explored_states: AtomicU64::new(0),
+                stopped: AtomicBool::new(false),
+                reason: Mutex::new(None),
+            }),
+        }
+    }
+
+    pub fn unlimited() -> Self {
+        Self::new(QueryLimits::default())
+    }
+
+    /// Stops the query the next time it checks the token
+    pub fn cancel(&self) {
+        self.stop(StopReason::Cancelled);
+    }
+
+    /// Counts an explored state, and returns whether the query has been stopped
+    pub fn explore_state(&self) -> bool {
+        let explored_states = self.state.explored_states.fetch_add(1, Ordering::Relaxed) + 1;
+        if self.is_stopped() {
+            return true;
+        }
+
+        let limits = &self.state.limits;
+        if let Some(max_states) = limits.max_states {
+            if explored_states > max_states {
+                self.stop(StopReason::StateLimit(max_states));
+            }
+        }
+        if let Some(timeout) = limits.timeout {
+            if self.state.start.elapsed() > timeout {
+                self.stop(StopReason::Timeout(timeout));
+            }
+        }
+        if let Some(max_memory) = limits.max_memory {
+            if explored_states % MEMORY_CHECK_INTERVAL == 0
+                && resident_memory().map_or(false, |memory| memory > max_memory)
+            {
+                self.stop(StopReason::MemoryLimit(max_memory));
+            }
+        }
+        self.is_stopped()
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.state.stopped.load(Ordering::Acquire)
+    }
+
+    /// Returns why and when the query was stopped, if it was
+    pub fn stopped(&self) -> Option<Inconclusive> {
+        let reason = self.state.reason.lock().unwrap().clone()?;
+        Some(Inconclusive {
+            reason,
+            stats: self.stats(),
+        })
+    }
+
+    pub fn stats(&self) -> SearchStats {
+        SearchStats {
+            explored_states: self.state.explored_states.load(Ordering::Relaxed),
+            elapsed: self.state.start.elapsed(),
+        }
+    }
+
+    fn stop(&self, reason: StopReason) {
+        let mut current = self.state.reason.lock().unwrap();
+        if current.is_none() {
+            info!("Stopping the query: {}", reason);
+            *current = Some(reason);
+            self.state.stopped.store(true, Ordering::Release);
+        }
+    }
+}
+
+/// Warns that the memory limit is not checked, once for the process rather than at every check
+static UNREADABLE_MEMORY: Once = Once::new();
+
+/// Returns the resident memory of the process in bytes, if it can be read
+fn resident_memory() -> Option<u64> {
+    let status = match fs::read_to_string("/proc/self/status") {
+        Ok(status) => status,
+        Err(_) => {
+            UNREADABLE_MEMORY.call_once(|| {
+                warn!(
+                    "The memory of the process can not be read, so the memory limit is not checked"
+                )
+            });
+            return None;
+        }
+    };
+    let kilobytes = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))?
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    Some(kilobytes * 1024)
+}
+
+thread_local! {
+    static CURRENT_TOKEN: RefCell<Option<CancellationToken>> = RefCell::new(None);
+}
+
+/// Runs `f` with `token` as the token of the current thread, which [should_stop] checks.
+/// The searches are reached through the methods of the transition systems, so the token is not passed to them.
+pub fn with_token<R>(token: &CancellationToken, f: impl FnOnce() -> R) -> R {
+    // Restores the previous token when dropped, even if `f` panics
+    struct Restore(Option<CancellationToken>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            CURRENT_TOKEN.with(|current| *current.borrow_mut() = self.0.take());
+        }
+    }
+
+    let _restore = Restore(CURRENT_TOKEN.with(|current| current.replace(Some(token.clone()))));
+    f()
+}
+
+/// Returns the token of the current thread, so it can be used by the threads a search spawns
+pub fn current_token() -> CancellationToken {
+    CURRENT_TOKEN
+        .with(|current| current.borrow().clone())
+        .unwrap_or_else(CancellationToken::unlimited)
+}
+
+/// Counts an explored state against the token of the current thread, if any, and returns whether the search should stop.
+/// A stopped search can return any result, as the query is inconclusive.
+pub fn should_stop() -> bool {
+    CURRENT_TOKEN.with(|current| {
+        current
+            .borrow()
+            .as_ref()
+            .map_or(false, CancellationToken::explore_state)
+    })
+}
@@ -1,11 +1,16 @@
 use std::fmt;
 
+use edbm::util::constraints::ClockIndex;
 use edbm::zones::OwnedFederation;
 use log::warn;
 
 use crate::extract_system_rep::SystemRecipeFailure;
-use crate::ModelObjects::component::State;
-use crate::TransitionSystems::{LocationID, TransitionSystem};
+use crate::ModelObjects::component::{State, Transition};
+use crate::ModelObjects::representations::BoolExpression;
+use crate::System::limits;
+use crate::System::strategy::{ConsistencyStrategy, StrategyMove};
+use crate::TransitionSystems::transition_system::PrecheckResult;
+use crate::TransitionSystems::{LocationID, LocationTuple, TransitionID, TransitionSystem};
 
 /// The result of a consistency check.
 /// If there was a failure, [ConsistencyFailure] will specify the failure.
@@ -67,32 +72,166 @@ pub enum DeterminismResult {
     Failure(DeterminismFailure),
 }
 
+#[derive(Debug)]
 pub enum DeterminismFailure {
-    NotDeterministicFrom(LocationID, String),
+    NotDeterministicFrom(LocationID, String, OverlappingTransitions),
     NotDisjoint(SystemRecipeFailure),
 }
 
+/// Two transitions with the same action from a location, which can both be taken in `overlap`
+#[derive(Debug)]
+pub struct OverlappingTransitions {
+    pub transitions: (TransitionID, TransitionID),
+    pub overlap: OwnedFederation,
+    /// `overlap` as a constraint on the clocks of the system
+    pub constraint: BoolExpression,
+}
+
+impl fmt::Display for OverlappingTransitions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} and {} overlap when {}",
+            self.transitions.0, self.transitions.1, self.constraint
+        )
+    }
+}
+
 impl fmt::Display for DeterminismResult {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             DeterminismResult::Success => write!(f, "Success"),
-            DeterminismResult::Failure(DeterminismFailure::NotDeterministicFrom(
-                location,
-                action,
-            )) => {
+            DeterminismResult::Failure(failure) => write!(f, "{}", failure),
+        }
+    }
+}
+
+impl fmt::Display for DeterminismFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DeterminismFailure::NotDeterministicFrom(location, action, overlapping) => {
                 write!(
                     f,
-                    "Not Deterministic From {} failing action {}",
-                    location, action
+                    "Not Deterministic From {} failing action {}: {}",
+                    location, action, overlapping
                 )
             }
-            DeterminismResult::Failure(DeterminismFailure::NotDisjoint(srf)) => {
+            DeterminismFailure::NotDisjoint(srf) => {
                 write!(f, "Not Disjoint: {} {:?}", srf.reason, srf.actions)
             }
         }
     }
 }
 
+/// The result of an implementation check.
+/// If there was a failure, [ImplementationFailure] will specify the failure.
+pub enum ImplementationResult {
+    Success,
+    Failure(ImplementationFailure),
+}
+
+/// The failure of an implementation check.
+/// Variants with [LocationID] are specific locations that cause the failure.
+#[derive(Debug)]
+pub enum ImplementationFailure {
+    NotConsistent(ConsistencyFailure),
+    NotInputEnabled(LocationID, String),
+    NotOutputUrgent(LocationID, String),
+}
+
+impl fmt::Display for ImplementationResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ImplementationResult::Success => write!(f, "Success"),
+            ImplementationResult::Failure(failure) => write!(f, "{}", failure),
+        }
+    }
+}
+
+impl fmt::Display for ImplementationFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ImplementationFailure::NotConsistent(failure) => write!(f, "{}", failure),
+            ImplementationFailure::NotInputEnabled(location, action) => {
+                write!(
+                    f,
+                    "Not Input Enabled From {} Failing action {}",
+                    location, action
+                )
+            }
+            ImplementationFailure::NotOutputUrgent(location, action) => {
+                write!(
+                    f,
+                    "Not Output Urgent From {} Failing action {}",
+                    location, action
+                )
+            }
+        }
+    }
+}
+
+/// The result of a specification check.
+/// If there was a failure, [SpecificationFailure] will specify which property failed.
+pub enum SpecificationResult {
+    Success,
+    Failure(SpecificationFailure),
+}
+
+/// The failure of a specification check.
+#[derive(Debug)]
+pub enum SpecificationFailure {
+    NotDeterministic(DeterminismFailure),
+    NotConsistent(ConsistencyFailure),
+    NotInputEnabled(LocationID, String),
+}
+
+impl fmt::Display for SpecificationResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SpecificationResult::Success => write!(f, "Success"),
+            SpecificationResult::Failure(failure) => write!(f, "{}", failure),
+        }
+    }
+}
+
+impl fmt::Display for SpecificationFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SpecificationFailure::NotDeterministic(failure) => write!(f, "{}", failure),
+            SpecificationFailure::NotConsistent(failure) => write!(f, "{}", failure),
+            SpecificationFailure::NotInputEnabled(location, action) => {
+                write!(
+                    f,
+                    "Not Input Enabled From {} Failing action {}",
+                    location, action
+                )
+            }
+        }
+    }
+}
+
+/// Checks if a [TransitionSystem] is a specification.
+/// That is, it is deterministic, consistent and every reachable state accepts every input.
+pub fn is_specification(system: &dyn TransitionSystem) -> SpecificationResult {
+    if let DeterminismResult::Failure(failure) = system.is_deterministic() {
+        return SpecificationResult::Failure(SpecificationFailure::NotDeterministic(failure));
+    }
+    if let ConsistencyResult::Failure(failure) = system.is_locally_consistent() {
+        return SpecificationResult::Failure(SpecificationFailure::NotConsistent(failure));
+    }
+
+    let failure = find_reachable_failure(system, |state| {
+        find_disabled_input(state, system).map(|input| {
+            SpecificationFailure::NotInputEnabled(state.get_location().id.clone(), input)
+        })
+    });
+
+    match failure {
+        Some(failure) => SpecificationResult::Failure(failure),
+        None => SpecificationResult::Success,
+    }
+}
+
 ///Local consistency check WITH pruning.
 pub fn is_least_consistent(system: &dyn TransitionSystem) -> ConsistencyResult {
     if system.get_initial_location().is_none() {
@@ -134,38 +273,55 @@ fn is_deterministic_helper(
     if state.is_contained_in_list(passed_list) {
         return DeterminismResult::Success;
     }
+    // A stopped check is inconclusive, so it returns the result which ends it soonest
+    if limits::should_stop() {
+        return DeterminismResult::Success;
+    }
 
     passed_list.push(state.clone());
 
     for action in system.get_actions() {
-        let mut location_fed = OwnedFederation::empty(system.get_dim());
-        for transition in &system.next_transitions(&state.decorated_locations, &action) {
+        // The transitions with the action taken so far, and where they are allowed
+        let mut taken: Vec<(&Transition, OwnedFederation)> = vec![];
+        let transitions = system.next_transitions(&state.decorated_locations, &action);
+        for transition in &transitions {
             let mut new_state = state.clone();
             if transition.use_transition(&mut new_state) {
                 let mut allowed_fed = transition.get_allowed_federation();
                 allowed_fed = state.decorated_locations.apply_invariants(allowed_fed);
-                if allowed_fed.has_intersection(&location_fed) {
+                if let Some((other, other_fed)) = taken
+                    .iter()
+                    .find(|(_, other_fed)| allowed_fed.has_intersection(other_fed))
+                {
                     warn!(
                         "Not deterministic from location {} failing action {}",
                         state.get_location().id,
                         action
                     );
+                    let overlap = allowed_fed.intersection(other_fed);
+                    let constraint = BoolExpression::from_disjunction(
+                        &overlap.minimal_constraints(),
+                        &system.get_combined_decls().clocks,
+                    )
+                    .unwrap_or(BoolExpression::Bool(true));
                     return DeterminismResult::Failure(DeterminismFailure::NotDeterministicFrom(
                         state.get_location().id.clone(),
                         action,
+                        OverlappingTransitions {
+                            transitions: (other.id.clone(), transition.id.clone()),
+                            overlap,
+                            constraint,
+                        },
                     ));
                 }
-                location_fed += allowed_fed;
+                taken.push((transition, allowed_fed));
                 new_state.extrapolate_max_bounds(system);
 
-                if let DeterminismResult::Failure(DeterminismFailure::NotDeterministicFrom(
-                    location,
-                    action,
-                )) = is_deterministic_helper(new_state, passed_list, system)
+                if let failure @ DeterminismResult::Failure(
+                    DeterminismFailure::NotDeterministicFrom(..),
+                ) = is_deterministic_helper(new_state, passed_list, system)
                 {
-                    return DeterminismResult::Failure(DeterminismFailure::NotDeterministicFrom(
-                        location, action,
-                    ));
+                    return failure;
                 }
             }
         }
@@ -173,6 +329,160 @@ fn is_deterministic_helper(
     DeterminismResult::Success
 }
 
+/// Checks if a [TransitionSystem] is an implementation.
+/// That is, it is consistent, every reachable state accepts every input and
+/// every output enabled where time is stopped can be taken.
+pub fn is_implementation(system: &dyn TransitionSystem) -> ImplementationResult {
+    match system.precheck_sys_rep() {
+        PrecheckResult::Success => (),
+        PrecheckResult::NotDeterministic(location, action) => {
+            return ImplementationResult::Failure(ImplementationFailure::NotConsistent(
+                ConsistencyFailure::NotDeterministicFrom(location, action),
+            ))
+        }
+        PrecheckResult::NotConsistent(failure) => {
+            return ImplementationResult::Failure(ImplementationFailure::NotConsistent(failure))
+        }
+    }
+
+    let failure = find_reachable_failure(system, |state| {
+        if let Some(input) = find_disabled_input(state, system) {
+            return Some(ImplementationFailure::NotInputEnabled(
+                state.get_location().id.clone(),
+                input,
+            ));
+        }
+        find_non_urgent_output(state, system).map(|output| {
+            ImplementationFailure::NotOutputUrgent(state.get_location().id.clone(), output)
+        })
+    });
+
+    match failure {
+        Some(failure) => ImplementationResult::Failure(failure),
+        None => ImplementationResult::Success,
+    }
+}
+
+/// Explores every reachable state of a [TransitionSystem] and returns the first failure found by `check`.
+/// Universal and inconsistent locations are not checked.
+fn find_reachable_failure<F>(
+    system: &dyn TransitionSystem,
+    check: impl Fn(&State) -> Option<F>,
+) -> Option<F> {
+    let mut state = system.get_initial_state()?;
+    let zone = state.take_zone().up();
+    let zone = state.decorated_locations.apply_invariants(zone);
+    state.set_zone(zone);
+    state.extrapolate_max_bounds(system);
+
+    let mut passed_list: Vec<State> = vec![];
+    let mut waiting_list = vec![state];
+
+    while let Some(state) = waiting_list.pop() {
+        if state.is_contained_in_list(&passed_list) {
+            continue;
+        }
+        if limits::should_stop() {
+            return None;
+        }
+        let location = &state.decorated_locations;
+        if location.is_universal() || location.is_inconsistent() {
+            passed_list.push(state);
+            continue;
+        }
+
+        if let Some(failure) = check(&state) {
+            return Some(failure);
+        }
+
+        for action in system.get_actions() {
+            for transition in system.next_transitions(location, &action) {
+                let mut new_state = state.clone();
+                if transition.use_transition(&mut new_state) {
+                    new_state.extrapolate_max_bounds(system);
+                    if !new_state.is_contained_in_list(&passed_list) {
+                        waiting_list.push(new_state);
+                    }
+                }
+            }
+        }
+        passed_list.push(state);
+    }
+
+    None
+}
+
+/// Returns an input which can not be taken from every valuation of the [State], if any.
+fn find_disabled_input(state: &State, system: &dyn TransitionSystem) -> Option<String> {
+    let location = &state.decorated_locations;
+    let zone = location.apply_invariants(state.zone_ref().clone());
+    for input in system.get_input_actions() {
+        let mut enabled = OwnedFederation::empty(system.get_dim());
+        for transition in system.next_inputs(location, &input) {
+            enabled += transition.get_allowed_federation();
+        }
+        if !zone.subset_eq(&enabled) {
+            warn!(
+                "Input \"{input}\" not enabled from {}",
+                state.get_location().id
+            );
+            return Some(input);
+        }
+    }
+    None
+}
+
+/// Returns an output whose guard holds where the invariant of the [State] stops time, but which can not be taken there, if any.
+/// Outputs may be delayed while time can pass, as the consistency check already requires a saving output before the invariant runs out.
+fn find_non_urgent_output(state: &State, system: &dyn TransitionSystem) -> Option<String> {
+    let location = &state.decorated_locations;
+    let stopped = location
+        .apply_invariants(state.zone_ref().clone())
+        .subtraction(&delayable_federation(location, system.get_dim()));
+    for output in system.get_output_actions() {
+        for transition in system.next_outputs(location, &output) {
+            let blocked = transition
+                .apply_guards(stopped.clone())
+                .subtraction(&transition.get_allowed_federation());
+            if !blocked.is_empty() {
+                warn!(
+                    "Output \"{output}\" is not urgent from {}",
+                    state.get_location().id
+                );
+                return Some(output);
+            }
+        }
+    }
+    None
+}
+
+/// Returns the valuations in the invariant of `location` from which time can pass.
+/// These are the valuations that do not lie on a non-strict upper bound of the invariant.
+pub(crate) fn delayable_federation(location: &LocationTuple, dim: ClockIndex) -> OwnedFederation {
+    let invariant = match location.get_invariants() {
+        Some(invariant) => invariant,
+        None => return OwnedFederation::universe(dim),
+    };
+
+    let mut delayable = OwnedFederation::empty(dim);
+    for conjunction in &invariant.minimal_constraints().conjunctions {
+        let mut zone = OwnedFederation::universe(dim);
+        for constraint in conjunction.iter() {
+            zone = zone.constrain(constraint.i, constraint.j, constraint.ineq());
+        }
+
+        let mut boundary = OwnedFederation::empty(dim);
+        for constraint in conjunction.iter() {
+            let ineq = constraint.ineq();
+            if constraint.i != 0 && constraint.j == 0 && !ineq.is_strict() {
+                boundary += zone.clone().constrain_eq(constraint.i, ineq.bound());
+            }
+        }
+        delayable += zone.subtraction(&boundary);
+    }
+    delayable
+}
+
 /// Local consistency check WITHOUT pruning
 #[allow(dead_code)]
 pub fn is_fully_consistent(system: &dyn TransitionSystem) -> ConsistencyResult {
@@ -189,17 +499,62 @@ pub fn is_fully_consistent(system: &dyn TransitionSystem) -> ConsistencyResult {
     consistency_fully_helper(state.unwrap(), &mut passed, system)
 }
 
+/// Local consistency check WITH pruning, which returns the strategy the outputs use to stay consistent if the system is consistent
+pub fn synthesize_consistency_strategy(
+    system: &dyn TransitionSystem,
+) -> Result<ConsistencyStrategy, ConsistencyFailure> {
+    if system.get_initial_location().is_none() {
+        return Err(ConsistencyFailure::NoInitialLocation);
+    }
+
+    let mut state = match system.get_initial_state() {
+        Some(state) => state,
+        None => {
+            warn!("Empty initial state");
+            return Err(ConsistencyFailure::EmptyInitialState);
+        }
+    };
+    state.extrapolate_max_bounds(system);
+
+    let mut strategy = ConsistencyStrategy::new(system.get_combined_decls().clocks);
+    match consistency_strategy_helper(state, &mut vec![], system, Some(&mut strategy)) {
+        ConsistencyResult::Success => Ok(strategy),
+        ConsistencyResult::Failure(failure) => Err(failure),
+    }
+}
+
 pub fn consistency_least_helper(
     state: State,
     passed_list: &mut Vec<State>,
     system: &dyn TransitionSystem,
+) -> ConsistencyResult {
+    consistency_strategy_helper(state, passed_list, system, None)
+}
+
+/// The least consistency check, recording the moves which keep the outputs consistent in `strategy` if given
+fn consistency_strategy_helper(
+    state: State,
+    passed_list: &mut Vec<State>,
+    system: &dyn TransitionSystem,
+    mut strategy: Option<&mut ConsistencyStrategy>,
 ) -> ConsistencyResult {
     let mut failing_action = String::new();
 
     if state.is_contained_in_list(passed_list) {
         return ConsistencyResult::Success;
     }
+    // A stopped check is inconclusive, so it returns the result which ends it soonest
+    if limits::should_stop() {
+        return ConsistencyResult::Success;
+    }
     if state.decorated_locations.is_universal() {
+        if let Some(strategy) = strategy {
+            strategy.add_move(
+                state.get_location(),
+                state.zone_ref().clone(),
+                StrategyMove::Delay,
+            );
+        }
         return ConsistencyResult::Success;
     }
     if state.decorated_locations.is_inconsistent() {
@@ -216,9 +571,12 @@ pub fn consistency_least_helper(
             let mut new_state = state.clone();
             if transition.use_transition(&mut new_state) {
                 new_state.extrapolate_max_bounds(system);
-                if let ConsistencyResult::Failure(failure) =
-                    consistency_least_helper(new_state, passed_list, system)
-                {
+                if let ConsistencyResult::Failure(failure) = consistency_strategy_helper(
+                    new_state,
+                    passed_list,
+                    system,
+                    strategy.as_deref_mut(),
+                ) {
                     warn!(
                         "Input \"{input}\" not consistent from {}",
                         state.get_location().id
@@ -232,6 +590,13 @@ pub fn consistency_least_helper(
     }
 
     if state.zone_ref().can_delay_indefinitely() {
+        if let Some(strategy) = strategy {
+            strategy.add_move(
+                state.get_location(),
+                state.zone_ref().clone(),
+                StrategyMove::Delay,
+            );
+        }
         return ConsistencyResult::Success;
     }
 
@@ -240,9 +605,37 @@ pub fn consistency_least_helper(
             let mut new_state = state.clone();
             if transition.use_transition(&mut new_state) {
                 new_state.extrapolate_max_bounds(system);
-                if let ConsistencyResult::Success =
-                    consistency_least_helper(new_state, passed_list, system)
-                {
+                // The moves after an output are only kept if the output saves the state
+                let mut output_strategy = strategy
+                    .as_ref()
+                    .map(|strategy| ConsistencyStrategy::new(strategy.clock_names.clone()));
+                if let ConsistencyResult::Success = consistency_strategy_helper(
+                    new_state,
+                    passed_list,
+                    system,
+                    output_strategy.as_mut(),
+                ) {
+                    if let (Some(strategy), Some(output_strategy)) = (strategy, output_strategy) {
+                        strategy.extend(output_strategy);
+                        // Wait until the output is enabled, then take it
+                        let enabled = transition
+                            .get_allowed_federation()
+                            .intersection(state.zone_ref());
+                        let waiting = enabled
+                            .clone()
+                            .down()
+                            .intersection(state.zone_ref())
+                            .subtraction(&enabled);
+                        strategy.add_move(state.get_location(), waiting, StrategyMove::Delay);
+                        strategy.add_move(
+                            state.get_location(),
+                            enabled,
+                            StrategyMove::Output {
+                                action: output.clone(),
+                                transition: transition.id.clone(),
+                            },
+                        );
+                    }
                     return ConsistencyResult::Success;
                 }
             } else {
@@ -268,6 +661,9 @@ fn consistency_fully_helper(
     if state.is_contained_in_list(passed_list) {
         return ConsistencyResult::Success;
     }
+    if limits::should_stop() {
+        return ConsistencyResult::Success;
+    }
     passed_list.push(state.clone());
 
     for input in system.get_input_actions() {
@@ -1,9 +1,16 @@
+pub mod bisimulation;
+pub mod concrete_trace;
+pub mod deadlock;
 pub mod executable_query;
 pub mod extract_state;
 pub mod extract_system_rep;
 pub mod input_enabler;
+pub mod limits;
 pub mod local_consistency;
 pub mod pruning;
 pub mod reachability;
 pub mod refine;
 pub mod save_component;
+pub mod strategy;
+pub mod tctl;
+pub mod zeno;
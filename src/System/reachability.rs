@@ -1,21 +1,91 @@
+use edbm::util::constraints::ClockIndex;
 use edbm::zones::OwnedFederation;
 
 use crate::component::LocationType;
+use crate::DataTypes::{SearchOrder, SearchQueue};
 use crate::ModelObjects::component::{State, Transition};
-use crate::TransitionSystems::{LocationID, TransitionSystem};
-use std::collections::{HashMap, VecDeque};
+use crate::System::concrete_trace::{clock_infimum, concretise_path, ConcreteTrace, TimeValue};
+use crate::System::limits;
+use crate::TransitionSystems::{LocationID, LocationKey, TransitionSystem};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap};
 use std::rc::Rc;
 
 /// This holds the result of a reachability query
 pub struct Path {
     pub path: Option<Vec<Transition>>,
     pub was_reachable: bool,
+    /// The path with concrete delays and clock values, if it could be found
+    pub concrete_trace: Option<ConcreteTrace>,
+    /// The description of the target that was reached, if any
+    pub reached_target: Option<String>,
+    /// The least time in which a target can be reached, if the path was found by [find_fastest_path]
+    pub minimal_time: Option<TimeValue>,
+}
+
+/// One of the states a reachability query searches for.
+/// The locations in `excluded_locations` are the ones written as `!L` in the query, which the state must not be in.
+#[derive(Clone)]
+pub struct ReachabilityTarget {
+    pub state: State,
+    pub excluded_locations: Vec<LocationID>,
+    pub description: String,
+}
+
+impl ReachabilityTarget {
+    pub fn new(state: State) -> Self {
+        let description = state.get_location().id.to_string();
+        ReachabilityTarget {
+            state,
+            excluded_locations: vec![],
+            description,
+        }
+    }
+
+    fn is_reached_by(&self, cur_state: &State) -> bool {
+        reached_end_state(cur_state, &self.state) && !self.is_excluded(cur_state)
+    }
+
+    /// Returns the part of the zone of `cur_state` which is in the target, if any
+    fn reached_zone(&self, cur_state: &State) -> Option<OwnedFederation> {
+        if !self.is_reached_by(cur_state) {
+            return None;
+        }
+        Some(
+            cur_state
+                .zone_ref()
+                .clone()
+                .intersection(self.state.zone_ref()),
+        )
+    }
+
+    /// Estimates how close `cur_state` is to the target, by the number of components not in their target location
+    fn distance_from(&self, cur_state: &State) -> usize {
+        let target_locations = self.state.get_location().id.inorder_vec_tranform();
+        let locations = cur_state.get_location().id.inorder_vec_tranform();
+        locations
+            .iter()
+            .zip(target_locations.iter())
+            .filter(|(location, target_location)| {
+                !matches!(target_location, LocationID::AnyLocation()) && location != target_location
+            })
+            .count()
+    }
+
+    fn is_excluded(&self, cur_state: &State) -> bool {
+        cur_state
+            .get_location()
+            .id
+            .inorder_vec_tranform()
+            .iter()
+            .any(|leaf| self.excluded_locations.contains(leaf))
+    }
 }
 // This holds which transition from which state (the destination_state of the previous_sub_path) we took to reach this state
-struct SubPath {
-    previous_sub_path: Option<Rc<SubPath>>,
-    destination_state: State,
-    transition: Option<Transition>,
+pub(crate) struct SubPath {
+    pub(crate) previous_sub_path: Option<Rc<SubPath>>,
+    pub(crate) destination_state: State,
+    pub(crate) transition: Option<Transition>,
 }
 
 fn is_trivially_unreachable(start_state: &State, end_state: &State) -> bool {
@@ -48,6 +118,8 @@ fn is_trivially_unreachable(start_state: &State, end_state: &State) -> bool {
 ///
 /// If it is not reachable, it returns None.
 ///
+/// It fails if an edge of the system could not be taken, such as when an update takes an int out of its range.
+///
 /// The start state can be omitted with None to use the start state of the transition system.
 ///
 ///## Checking if a state can reach another:
@@ -76,25 +148,107 @@ pub fn find_path(
     end_state: State,
     system: &dyn TransitionSystem,
 ) -> Result<Path, String> {
-    if is_trivially_unreachable(&start_state, &end_state) {
+    find_path_to_any(
+        start_state,
+        &[ReachabilityTarget::new(end_state)],
+        system,
+        SearchOrder::BreadthFirst,
+    )
+}
+
+/// Returns a path from a start state to the first of the `targets` which can be reached, like [find_path].
+/// The description of the reached target is in [Path::reached_target].
+///
+/// The states are searched in the given `order`, where [SearchOrder::ClosestToFailure] first explores the states
+/// with the fewest components outside the locations of a target.
+pub fn find_path_to_any(
+    start_state: State,
+    targets: &[ReachabilityTarget],
+    system: &dyn TransitionSystem,
+    order: SearchOrder,
+) -> Result<Path, String> {
+    let targets: Vec<&ReachabilityTarget> = targets
+        .iter()
+        .filter(|target| !is_trivially_unreachable(&start_state, &target.state))
+        .collect();
+    if targets.is_empty() {
         return Ok(Path {
             path: None,
             was_reachable: false,
+            concrete_trace: None,
+            reached_target: None,
+            minimal_time: None,
         });
     }
 
-    Ok(reachability_search(&start_state, &end_state, system))
+    let (mut path, reached) = reachability_search(&start_state, &targets, system, order);
+    if let Some(error) = system.get_update_error() {
+        return Err(error);
+    }
+    if let (Some(transitions), Some(target)) = (&path.path, reached) {
+        path.concrete_trace = concretise_path(
+            &start_state,
+            &target.state,
+            transitions,
+            system.get_combined_decls().clocks,
+        );
+        path.reached_target = Some(target.description.clone());
+    }
+    Ok(path)
 }
 
-/// Currently runs a BFS search on the transition system.
+/// Runs a BFS search on the transition system by default.
 /// BFS is preferable to a DFS, as it reduces the chance of "Mistakes", meaning
 /// having to revisit a state with a larger zone, forcing it to be readded ot the frontier.
 /// Inspired from http://link.springer.com/10.1007/978-3-319-22975-1_9, see article for possible optimizations and more explanation.
-fn reachability_search(
+fn reachability_search<'a>(
+    start_state: &State,
+    targets: &[&'a ReachabilityTarget],
+    system: &dyn TransitionSystem,
+    order: SearchOrder,
+) -> (Path, Option<&'a ReachabilityTarget>) {
+    let mut reached = None;
+    let distance = |state: &State| {
+        targets
+            .iter()
+            .map(|target| target.distance_from(state))
+            .min()
+            .unwrap_or(0)
+    };
+    let sub_path = search_zone_graph(start_state, system, order, distance, |state| {
+        reached = targets
+            .iter()
+            .copied()
+            .find(|target| target.is_reached_by(state));
+        reached.is_some()
+    });
+
+    match sub_path {
+        Some(sub_path) => (make_path(sub_path), reached),
+        // If nothing has been found, it is not reachable
+        None => (
+            Path {
+                path: None,
+                was_reachable: false,
+                concrete_trace: None,
+                reached_target: None,
+                minimal_time: None,
+            },
+            None,
+        ),
+    }
+}
+
+/// Searches the zone graph of the transition system from `start_state` in the given `order`,
+/// and returns the [SubPath] to the first state for which `found` returns true.
+/// `distance` estimates how close a state is to being found, for [SearchOrder::ClosestToFailure].
+pub(crate) fn search_zone_graph(
     start_state: &State,
-    end_state: &State,
     system: &dyn TransitionSystem,
-) -> Path {
+    order: SearchOrder,
+    distance: impl Fn(&State) -> usize,
+    mut found: impl FnMut(&State) -> bool,
+) -> Option<Rc<SubPath>> {
     // Apply the invariant of the start state to the start state
     let mut start_clone = start_state.clone();
     let start_zone = start_clone.take_zone();
@@ -102,31 +256,37 @@ fn reachability_search(
     start_clone.set_zone(zone);
 
     // hashmap linking every location to all its current zones
-    let mut visited_states: HashMap<LocationID, Vec<OwnedFederation>> = HashMap::new();
+    let mut visited_states: HashMap<LocationKey, Vec<OwnedFederation>> = HashMap::new();
 
     // List of states that are to be visited
-    let mut frontier_states: VecDeque<Rc<SubPath>> = VecDeque::new();
+    let mut frontier_states: SearchQueue<Rc<SubPath>> = SearchQueue::new(order);
 
     let mut actions: Vec<String> = system.get_actions().into_iter().collect();
     actions.sort();
 
     // Push start state to visited state
     visited_states.insert(
-        start_clone.get_location().id.clone(),
+        start_clone.get_location().get_key(),
         vec![start_clone.zone_ref().clone()],
     );
 
     // Push state state to frontier
-    frontier_states.push_back(Rc::new(SubPath {
-        previous_sub_path: None,
-        destination_state: start_clone,
-        transition: None,
-    }));
-
-    // Take the first state from the frontier and explore it
-    while let Some(sub_path) = frontier_states.pop_front() {
-        if reached_end_state(&sub_path.destination_state, end_state) {
-            return make_path(sub_path);
+    frontier_states.push(
+        Rc::new(SubPath {
+            previous_sub_path: None,
+            destination_state: start_clone,
+            transition: None,
+        }),
+        0,
+    );
+
+    // Take the next state from the frontier and explore it
+    while let Some((sub_path, _)) = frontier_states.pop() {
+        if limits::should_stop() {
+            return None;
+        }
+        if found(&sub_path.destination_state) {
+            return Some(sub_path);
         }
 
         for action in &actions {
@@ -139,15 +299,199 @@ fn reachability_search(
                     &mut frontier_states,
                     &mut visited_states,
                     system,
+                    &distance,
                 );
             }
         }
     }
-    // If nothing has been found, it is not reachable
-    Path {
-        path: None,
-        was_reachable: false,
+    None
+}
+
+/// Returns the path which reaches one of the `targets` from a start state in the least time, like [find_path_to_any].
+/// The least time is in [Path::minimal_time].
+///
+/// `time_clock` must be a clock which the system does not use, as it measures the time since the start state.
+/// The states are searched in order of the least time they can be reached in, using a priority queue.
+pub fn find_fastest_path(
+    start_state: State,
+    targets: &[ReachabilityTarget],
+    system: &dyn TransitionSystem,
+    time_clock: ClockIndex,
+) -> Result<Path, String> {
+    // The fastest search does not extrapolate the time clock, so it only terminates when a target can be reached.
+    // The breadth first search is only a guard for that: it explores at most every extrapolated state once, after
+    // which the fastest search stops at the first target it pops, having popped only the zones reached before it.
+    // The extra cost is thus bounded by one breadth first search of the system.
+    let path = find_path_to_any(
+        start_state.clone(),
+        targets,
+        system,
+        SearchOrder::BreadthFirst,
+    )?;
+    if !path.was_reachable {
+        return Ok(path);
+    }
+
+    let targets: Vec<&ReachabilityTarget> = targets
+        .iter()
+        .filter(|target| !is_trivially_unreachable(&start_state, &target.state))
+        .collect();
+    let (mut path, reached) = fastest_search(&start_state, &targets, system, time_clock);
+    if let Some(error) = system.get_update_error() {
+        return Err(error);
+    }
+    if let (Some(transitions), Some((target, time))) = (&path.path, reached) {
+        path.concrete_trace = concretise_path(
+            &start_state,
+            &target.state,
+            transitions,
+            system.get_combined_decls().clocks,
+        );
+        path.reached_target = Some(target.description.clone());
+        path.minimal_time = Some(time);
+    }
+    Ok(path)
+}
+
+/// An entry of the priority queue of [fastest_search], ordered by the least time of its zone.
+/// The entries with a target are the part of a state which is in that target.
+struct FastestEntry<'a> {
+    /// The least time, whether it is strict, and the order the entry was added in
+    key: Reverse<(TimeValue, bool, usize)>,
+    sub_path: Rc<SubPath>,
+    target: Option<&'a ReachabilityTarget>,
+}
+
+impl PartialEq for FastestEntry<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for FastestEntry<'_> {}
+
+impl PartialOrd for FastestEntry<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for FastestEntry<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+fn fastest_search<'a>(
+    start_state: &State,
+    targets: &[&'a ReachabilityTarget],
+    system: &dyn TransitionSystem,
+    time_clock: ClockIndex,
+) -> (Path, Option<(&'a ReachabilityTarget, TimeValue)>) {
+    // Apply the invariant of the start state to the start state, and start the time at 0
+    let mut start_clone = start_state.clone();
+    let start_zone = start_clone.take_zone();
+    let zone = start_clone
+        .decorated_locations
+        .apply_invariants(start_zone)
+        .update_clock_val(time_clock, 0);
+    start_clone.set_zone(zone);
+
+    let mut visited_states: HashMap<LocationKey, Vec<OwnedFederation>> = HashMap::new();
+    let mut queue: BinaryHeap<FastestEntry> = BinaryHeap::new();
+    let mut added = 0;
+    let mut push = |queue: &mut BinaryHeap<FastestEntry<'a>>,
+                    sub_path: Rc<SubPath>,
+                    zone: &OwnedFederation,
+                    target: Option<&'a ReachabilityTarget>| {
+        if let Some((time, strict)) = clock_infimum(zone, time_clock) {
+            added += 1;
+            queue.push(FastestEntry {
+                key: Reverse((time, strict, added)),
+                sub_path,
+                target,
+            });
+        }
+    };
+
+    let mut actions: Vec<String> = system.get_actions().into_iter().collect();
+    actions.sort();
+
+    visited_states.insert(
+        start_clone.get_location().get_key(),
+        vec![start_clone.zone_ref().clone()],
+    );
+    let start_zone = start_clone.zone_ref().clone();
+    push(
+        &mut queue,
+        Rc::new(SubPath {
+            previous_sub_path: None,
+            destination_state: start_clone,
+            transition: None,
+        }),
+        &start_zone,
+        None,
+    );
+
+    while let Some(entry) = queue.pop() {
+        if limits::should_stop() {
+            break;
+        }
+        let Reverse((time, ..)) = entry.key;
+        let sub_path = entry.sub_path;
+        if let Some(target) = entry.target {
+            return (make_path(sub_path), Some((target, time)));
+        }
+
+        // The targets are queued by the least time they are reached in, as it can be later than for the whole zone
+        for target in targets {
+            if let Some(zone) = target.reached_zone(&sub_path.destination_state) {
+                push(&mut queue, Rc::clone(&sub_path), &zone, Some(*target));
+            }
+        }
+
+        for action in &actions {
+            for transition in
+                &system.next_transitions(&sub_path.destination_state.decorated_locations, action)
+            {
+                let mut new_state = sub_path.destination_state.clone();
+                // The state is not extrapolated, as that would forget the time
+                if !transition.use_transition(&mut new_state) {
+                    continue;
+                }
+                let new_location_key = new_state.get_location().get_key();
+                let existing_zones = visited_states.entry(new_location_key).or_default();
+                if zone_subset_of_existing_zones(new_state.zone_ref(), existing_zones) {
+                    continue;
+                }
+                remove_existing_subsets_of_zone(new_state.zone_ref(), existing_zones);
+                existing_zones.push(new_state.zone_ref().clone());
+
+                let new_zone = new_state.zone_ref().clone();
+                push(
+                    &mut queue,
+                    Rc::new(SubPath {
+                        previous_sub_path: Some(Rc::clone(&sub_path)),
+                        destination_state: new_state,
+                        transition: Some(transition.clone()),
+                    }),
+                    &new_zone,
+                    None,
+                );
+            }
+        }
     }
+
+    (
+        Path {
+            path: None,
+            was_reachable: false,
+            concrete_trace: None,
+            reached_target: None,
+            minimal_time: None,
+        },
+        None,
+    )
 }
 
 fn reached_end_state(cur_state: &State, end_state: &State) -> bool {
@@ -160,30 +504,39 @@ fn reached_end_state(cur_state: &State, end_state: &State) -> bool {
 fn take_transition(
     sub_path: &Rc<SubPath>,
     transition: &Transition,
-    frontier_states: &mut VecDeque<Rc<SubPath>>,
-    visited_states: &mut HashMap<LocationID, Vec<OwnedFederation>>,
+    frontier_states: &mut SearchQueue<Rc<SubPath>>,
+    visited_states: &mut HashMap<LocationKey, Vec<OwnedFederation>>,
     system: &dyn TransitionSystem,
+    distance: &impl Fn(&State) -> usize,
 ) {
     let mut new_state = sub_path.destination_state.clone();
     if transition.use_transition(&mut new_state) {
         new_state.extrapolate_max_bounds(system); // Ensures the bounds cant grow infinitely, avoiding infinite loops in an edge case TODO: does not take end state zone into account, leading to a very rare edge case
-        let new_location_id = &new_state.get_location().id;
-        let existing_zones = visited_states.entry(new_location_id.clone()).or_default();
+        let new_location_key = new_state.get_location().get_key();
+        let existing_zones = visited_states.entry(new_location_key.clone()).or_default();
         // If this location has not already been reached (explored) with a larger zone
         if !zone_subset_of_existing_zones(new_state.zone_ref(), existing_zones) {
             // Remove the smaller zones for this location in visited_states
             remove_existing_subsets_of_zone(new_state.zone_ref(), existing_zones);
             // Add the new zone to the list of zones for this location in visited_states
             visited_states
-                .get_mut(new_location_id)
+                .get_mut(&new_location_key)
                 .unwrap()
                 .push(new_state.zone_ref().clone());
             // Add the new state to the frontier
-            frontier_states.push_back(Rc::new(SubPath {
-                previous_sub_path: Some(Rc::clone(sub_path)),
-                destination_state: new_state,
-                transition: Some(transition.clone()),
-            }));
+            let new_distance = if frontier_states.order().uses_distance() {
+                distance(&new_state)
+            } else {
+                0
+            };
+            frontier_states.push(
+                Rc::new(SubPath {
+                    previous_sub_path: Some(Rc::clone(sub_path)),
+                    destination_state: new_state,
+                    transition: Some(transition.clone()),
+                }),
+                new_distance,
+            );
         }
     }
 }
@@ -209,7 +562,7 @@ fn remove_existing_subsets_of_zone(
     existing_zones.retain(|existing_zone| !existing_zone.subset_eq(new_zone));
 }
 /// Makes the path from the last subpath
-fn make_path(mut sub_path: Rc<SubPath>) -> Path {
+pub(crate) fn make_path(mut sub_path: Rc<SubPath>) -> Path {
     let mut path: Vec<Transition> = Vec::new();
     // Traverse the subpaths to make the path (from end location to start location)
     while sub_path.previous_sub_path.is_some() {
@@ -222,5 +575,8 @@ fn make_path(mut sub_path: Rc<SubPath>) -> Path {
     Path {
         path: Some(path),
         was_reachable: true,
+        concrete_trace: None,
+        reached_target: None,
+        minimal_time: None,
     }
 }
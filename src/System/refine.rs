@@ -1,22 +1,33 @@
 use edbm::zones::OwnedFederation;
 use log::{debug, info, log_enabled, trace, warn, Level};
 
-use crate::DataTypes::{PassedStateList, PassedStateListExt, WaitingStateList};
+use crate::DataTypes::{
+    PassedStateList, PassedStateListExt, SearchOrder, SearchQueue, WaitingStateList,
+};
 use crate::ModelObjects::component::Transition;
+use crate::ModelObjects::representations::BoolExpression;
 
 use crate::extract_system_rep::SystemRecipeFailure;
-use crate::ModelObjects::statepair::StatePair;
+use crate::logging;
+use crate::ModelObjects::statepair::{StatePair, StatePairStep};
+use crate::System::limits;
 use crate::System::local_consistency::ConsistencyFailure;
 use crate::TransitionSystems::common::CollectionOperation;
 use crate::TransitionSystems::transition_system::PrecheckResult;
-use crate::TransitionSystems::{LocationID, LocationTuple, TransitionSystemPtr};
-use std::collections::HashSet;
+use crate::TransitionSystems::{LocationID, LocationKey, LocationTuple, TransitionSystemPtr};
+use edbm::util::constraints::ClockIndex;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::{Condvar, Mutex};
+use std::thread;
 
 /// The result of a refinement check. [RefinementFailure] specifies the failure.
 #[allow(clippy::large_enum_variant)] //TODO: consider boxing the large fields to reduce the total size of the enum
 pub enum RefinementResult {
-    Success,
+    /// The relation between the state pairs of the two systems, if it was asked for
+    Success(Option<RefinementRelation>),
     Failure(RefinementFailure),
 }
 
@@ -36,7 +47,97 @@ pub enum RefinementFailure {
     NotEmptyResult(StatePair),
     ConsistencyFailure(Option<LocationID>, Option<String>),
     DeterminismFailure(Option<LocationID>, Option<String>),
+    /// An edge of one of the systems could not be taken, such as when an update takes an int out of its range
+    UpdateError(String),
+}
+
+/// Actions which a refinement check leaves out, e.g. the ignored inputs and outputs of a query
+#[derive(Debug, Default, Clone)]
+pub struct IgnoredActions {
+    pub inputs: HashSet<String>,
+    pub outputs: HashSet<String>,
+}
+
+/// The simulation relation found by a successful refinement check
+#[derive(Debug, Clone, Default)]
+pub struct RefinementRelation {
+    pub pairs: Vec<RelationPair>,
+    /// The names of the clocks of both systems, to print the federations of the pairs with
+    pub clock_names: HashMap<String, ClockIndex>,
+}
+
+/// A pair of locations (with their int values) in the relation and the federation they are related in
+#[derive(Debug, Clone)]
+pub struct RelationPair {
+    pub locations1: LocationKey,
+    pub locations2: LocationKey,
+    pub federation: OwnedFederation,
+}
+
+impl RefinementRelation {
+    fn new(
+        passed_list: &PassedStateList,
+        sys1: &TransitionSystemPtr,
+        sys2: &TransitionSystemPtr,
+    ) -> RefinementRelation {
+        let mut pairs = vec![];
+        for key in passed_list.keys() {
+            for zone in passed_list.zones(key) {
+                pairs.push(RelationPair {
+                    locations1: key.0.clone(),
+                    locations2: key.1.clone(),
+                    federation: zone.clone(),
+                });
+            }
+        }
+        pairs.sort_by_key(|pair| {
+            format!(
+                "1:{}, 2:{}",
+                format_key(&pair.locations1),
+                format_key(&pair.locations2)
+            )
+        });
+
+        // Clocks with the same name in different components are told apart by their index
+        let mut clock_names: HashMap<String, ClockIndex> = HashMap::new();
+        for decls in sys1.get_decls().into_iter().chain(sys2.get_decls()) {
+            for (name, index) in &decls.clocks {
+                let name = match clock_names.get(name) {
+                    Some(other) if other != index => format!("{}_{}", name, index),
+                    _ => name.clone(),
+                };
+                clock_names.insert(name, *index);
+            }
+        }
+
+        RefinementRelation { pairs, clock_names }
+    }
+
+    /// Formats a federation of the relation using the names of the clocks
+    pub fn format_federation(&self, federation: &OwnedFederation) -> String {
+        match BoolExpression::from_disjunction(&federation.minimal_constraints(), &self.clock_names)
+        {
+            Some(expression) => expression.to_string(),
+            None => "true".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for RefinementRelation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for pair in &self.pairs {
+            writeln!(
+                f,
+                "1:{} 2:{} {}",
+                format_key(&pair.locations1),
+                format_key(&pair.locations2),
+                self.format_federation(&pair.federation)
+            )?;
+        }
+        Ok(())
+    }
 }
+
 enum StatePairResult {
     Valid,
     EmptyTransition2s,
@@ -74,6 +175,20 @@ impl fmt::Display for RefinementFailure {
                     action.as_ref().unwrap()
                 )
             }
+            RefinementFailure::UpdateError(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl RefinementFailure {
+    /// Returns the state pair the check failed in, if the failure happened while exploring state pairs
+    pub fn get_state_pair(&self) -> Option<&StatePair> {
+        match self {
+            RefinementFailure::CutsDelaySolutions(state_pair)
+            | RefinementFailure::InitialState(state_pair)
+            | RefinementFailure::EmptyTransition2s(state_pair)
+            | RefinementFailure::NotEmptyResult(state_pair) => Some(state_pair),
+            _ => None,
         }
     }
 }
@@ -108,39 +223,234 @@ fn extra_actions(
     }
 }
 
+fn without_ignored(actions: HashSet<String>, ignored: &HashSet<String>) -> HashSet<String> {
+    actions.difference(ignored).cloned().collect()
+}
+
 struct RefinementContext<'a> {
     pub passed_list: PassedStateList,
     pub waiting_list: WaitingStateList,
     pub sys1: &'a TransitionSystemPtr,
     pub sys2: &'a TransitionSystemPtr,
+    pub actions: &'a RefinementActions,
+    /// The passed list of all workers, if this is the context of a worker in a parallel refinement check
+    pub shared_passed_list: Option<&'a SharedPassedList>,
 }
 
 impl<'a> RefinementContext<'a> {
-    fn new(sys1: &'a TransitionSystemPtr, sys2: &'a TransitionSystemPtr) -> RefinementContext<'a> {
+    fn new(
+        sys1: &'a TransitionSystemPtr,
+        sys2: &'a TransitionSystemPtr,
+        actions: &'a RefinementActions,
+        order: SearchOrder,
+    ) -> RefinementContext<'a> {
         RefinementContext {
             passed_list: PassedStateList::new(),
-            waiting_list: WaitingStateList::new(),
+            waiting_list: WaitingStateList::new(order),
             sys1,
             sys2,
+            actions,
+            shared_passed_list: None,
+        }
+    }
+
+    /// Whether the pair is included in an explored or waiting pair
+    fn has(&self, pair: &StatePair) -> bool {
+        self.passed_list.has(pair)
+            || self.waiting_list.has(pair)
+            || self
+                .shared_passed_list
+                .map_or(false, |shared_passed_list| shared_passed_list.has(pair))
+    }
+}
+
+/// The actions a refinement check compares the systems on
+struct RefinementActions {
+    inputs: HashSet<String>,
+    outputs: HashSet<String>,
+    extra_inputs: HashSet<String>,
+    extra_outputs: HashSet<String>,
+}
+
+/// The passed list of a parallel refinement check, sharded by the locations of the state pairs,
+/// so the workers only lock the shard of the pair they look up
+struct SharedPassedList {
+    shards: Vec<Mutex<PassedStateList>>,
+}
+
+impl SharedPassedList {
+    fn new(shard_count: usize) -> Self {
+        SharedPassedList {
+            shards: (0..shard_count)
+                .map(|_| Mutex::new(PassedStateList::new()))
+                .collect(),
+        }
+    }
+
+    fn shard(&self, pair: &StatePair) -> &Mutex<PassedStateList> {
+        let mut hasher = DefaultHasher::new();
+        (pair.locations1.get_key(), pair.locations2.get_key()).hash(&mut hasher);
+        &self.shards[hasher.finish() as usize % self.shards.len()]
+    }
+
+    fn has(&self, pair: &StatePair) -> bool {
+        self.shard(pair).lock().unwrap().has(pair)
+    }
+
+    /// Puts the pair in the passed list, unless it is already included in it.
+    /// Returns whether the pair was put, meaning the caller has to explore it
+    fn put_if_new(&self, pair: &StatePair) -> bool {
+        let mut shard = self.shard(pair).lock().unwrap();
+        if shard.has(pair) {
+            return false;
+        }
+        shard.put(pair.clone());
+        true
+    }
+
+    fn into_passed_list(self) -> PassedStateList {
+        let mut passed_list = PassedStateList::new();
+        for shard in self.shards {
+            // The shards have no keys in common
+            passed_list.extend(shard.into_inner().unwrap());
+        }
+        passed_list
+    }
+}
+
+/// The state pairs waiting to be explored by the workers of a parallel refinement check
+struct SharedWaitingList {
+    state: Mutex<SharedWaitingState>,
+    changed: Condvar,
+}
+
+struct SharedWaitingState {
+    waiting: SearchQueue<StatePair>,
+    /// The number of workers exploring a pair, which can add more pairs to `waiting`
+    active_workers: usize,
+    failure: Option<RefinementFailure>,
+    /// Whether the limits of the query were hit
+    stopped: bool,
+}
+
+/// How a refinement check searches the state pairs
+#[derive(Clone, Copy, Debug)]
+pub struct RefinementOptions {
+    /// The number of threads exploring the state pairs
+    pub threads: usize,
+    pub search_order: SearchOrder,
+}
+
+impl Default for RefinementOptions {
+    fn default() -> Self {
+        RefinementOptions {
+            threads: 1,
+            search_order: SearchOrder::DepthFirst,
         }
     }
 }
 
 /// Checks if sys1 refines sys2
 pub fn check_refinement(sys1: TransitionSystemPtr, sys2: TransitionSystemPtr) -> RefinementResult {
-    let mut context = RefinementContext::new(&sys1, &sys2);
+    check_refinement_ignoring(sys1, sys2, &IgnoredActions::default())
+}
+
+/// Checks if sys1 refines sys2 without considering the `ignored` actions
+pub fn check_refinement_ignoring(
+    sys1: TransitionSystemPtr,
+    sys2: TransitionSystemPtr,
+    ignored: &IgnoredActions,
+) -> RefinementResult {
+    check_refinement_with_options(sys1, sys2, ignored, &RefinementOptions::default())
+}
+
+/// Checks if sys1 refines sys2 without considering the `ignored` actions, searching the state pairs as the `options` say.
+/// With more than one thread, or another search order, the verdict is the same, but a failure may be found in another state pair.
+///
+/// The search stops early if the limits of the current query are hit, see [limits::should_stop].
+pub fn check_refinement_with_options(
+    sys1: TransitionSystemPtr,
+    sys2: TransitionSystemPtr,
+    ignored: &IgnoredActions,
+    options: &RefinementOptions,
+) -> RefinementResult {
+    let result = search_refinement(&sys1, &sys2, ignored, options).0;
+    or_update_error(result, &sys1, &sys2)
+}
+
+/// Checks if sys1 refines sys2 without considering the `ignored` actions, like [check_refinement_with_options].
+/// If it does, the relation between the state pairs of the two systems is returned with the success
+pub fn check_refinement_with_relation(
+    sys1: TransitionSystemPtr,
+    sys2: TransitionSystemPtr,
+    ignored: &IgnoredActions,
+    options: &RefinementOptions,
+) -> RefinementResult {
+    let result = match search_refinement(&sys1, &sys2, ignored, options) {
+        (RefinementResult::Success(_), passed_list) => {
+            RefinementResult::Success(Some(RefinementRelation::new(&passed_list, &sys1, &sys2)))
+        }
+        (failure, _) => failure,
+    };
+    or_update_error(result, &sys1, &sys2)
+}
+
+/// Replaces the result by the error met when an edge of one of the systems could not be taken, if any
+fn or_update_error(
+    result: RefinementResult,
+    sys1: &TransitionSystemPtr,
+    sys2: &TransitionSystemPtr,
+) -> RefinementResult {
+    match sys1.get_update_error().or_else(|| sys2.get_update_error()) {
+        Some(error) => RefinementResult::Failure(RefinementFailure::UpdateError(error)),
+        None => result,
+    }
+}
+
+/// Searches the state pairs of the systems for one where sys1 does not refine sys2.
+/// Returns the result and the explored state pairs
+fn search_refinement(
+    sys1: &TransitionSystemPtr,
+    sys2: &TransitionSystemPtr,
+    ignored: &IgnoredActions,
+    options: &RefinementOptions,
+) -> (RefinementResult, PassedStateList) {
+    match prepare_refinement(sys1, sys2, ignored) {
+        Ok((initial_pair, actions)) => {
+            debug!(
+                "Searching the state pairs in {} order",
+                options.search_order
+            );
+            if options.threads > 1 {
+                search_refinement_in_parallel(initial_pair, &actions, sys1, sys2, options)
+            } else {
+                search_refinement_sequentially(initial_pair, &actions, sys1, sys2, options)
+            }
+        }
+        Err(result) => (result, PassedStateList::new()),
+    }
+}
+
+/// Checks the preconditions of the refinement and finds the actions to compare and the initial pair.
+/// The `ignored` actions are taken by one system while the other stays, like the extra actions.
+/// Returns the result if it is decided without exploring the state pairs
+fn prepare_refinement(
+    sys1: &TransitionSystemPtr,
+    sys2: &TransitionSystemPtr,
+    ignored: &IgnoredActions,
+) -> Result<(StatePair, RefinementActions), RefinementResult> {
     let dimensions = sys1.get_dim();
     debug!("Dimensions: {}", dimensions);
 
     //Firstly we check the preconditions
-    if let RefinementResult::Failure(failure) = check_preconditions(&sys1, &sys2) {
+    if let RefinementResult::Failure(failure) = check_preconditions(sys1, sys2, ignored) {
         warn!("Refinement failed with failure: {}", failure);
-        return RefinementResult::Failure(failure);
+        return Err(RefinementResult::Failure(failure));
     }
 
     // Common inputs and outputs
-    let inputs = common_actions(&sys1, &sys2, true);
-    let outputs = common_actions(&sys1, &sys2, false);
+    let inputs = common_actions(sys1, sys2, true);
+    let outputs = common_actions(sys1, sys2, false);
 
     info!(
         "Left inputs: {:?}, Left outputs: {:?}",
@@ -155,8 +465,10 @@ pub fn check_refinement(sys1: TransitionSystemPtr, sys2: TransitionSystemPtr) ->
     );
 
     // Extra inputs and outputs are ignored by default
-    let extra_inputs = extra_actions(&sys1, &sys2, true);
-    let extra_outputs = extra_actions(&sys1, &sys2, false);
+    let mut extra_inputs = extra_actions(sys1, sys2, true);
+    let mut extra_outputs = extra_actions(sys1, sys2, false);
+    extra_inputs.extend(inputs.intersection(&ignored.inputs).cloned());
+    extra_outputs.extend(outputs.intersection(&ignored.outputs).cloned());
 
     let initial_locations_1 = sys1.get_initial_location();
     let initial_locations_2 = sys2.get_initial_location();
@@ -167,14 +479,18 @@ pub fn check_refinement(sys1: TransitionSystemPtr, sys2: TransitionSystemPtr) ->
     if initial_locations_1.is_none() {
         if initial_locations_2.is_none() {
             // Both are empty, so trivially true
-            return RefinementResult::Success;
+            return Err(RefinementResult::Success(None));
         }
-        return RefinementResult::Failure(RefinementFailure::EmptyImplementation);
+        return Err(RefinementResult::Failure(
+            RefinementFailure::EmptyImplementation,
+        ));
     }
 
     if initial_locations_2.is_none() {
         //The empty automata cannot implement
-        return RefinementResult::Failure(RefinementFailure::EmptySpecification);
+        return Err(RefinementResult::Failure(
+            RefinementFailure::EmptySpecification,
+        ));
     }
 
     let initial_locations_1 = initial_locations_1.unwrap();
@@ -187,142 +503,297 @@ pub fn check_refinement(sys1: TransitionSystemPtr, sys2: TransitionSystemPtr) ->
     );
 
     if !prepare_init_state(&mut initial_pair, initial_locations_1, initial_locations_2) {
-        return RefinementResult::Failure(RefinementFailure::InitialState(initial_pair));
+        return Err(RefinementResult::Failure(RefinementFailure::InitialState(
+            initial_pair,
+        )));
     }
-    initial_pair.extrapolate_max_bounds(context.sys1, context.sys2);
+    initial_pair.extrapolate_max_bounds(sys1, sys2);
 
     debug!("Initial {}", initial_pair);
+    Ok((
+        initial_pair,
+        RefinementActions {
+            inputs,
+            outputs,
+            extra_inputs,
+            extra_outputs,
+        },
+    ))
+}
+
+fn search_refinement_sequentially(
+    initial_pair: StatePair,
+    actions: &RefinementActions,
+    sys1: &TransitionSystemPtr,
+    sys2: &TransitionSystemPtr,
+    options: &RefinementOptions,
+) -> (RefinementResult, PassedStateList) {
+    let mut context = RefinementContext::new(sys1, sys2, actions, options.search_order);
     context.waiting_list.put(initial_pair);
 
-    while !context.waiting_list.is_empty() {
-        let curr_pair = context.waiting_list.pop().unwrap();
+    while let Some(curr_pair) = context.waiting_list.pop() {
         trace!("Checking {}", curr_pair);
+        // A stopped check is inconclusive, so its result does not matter
+        if limits::should_stop() {
+            debug!("Refinement check stopped");
+            return (RefinementResult::Success(None), context.passed_list);
+        }
 
         context.passed_list.put(curr_pair.clone());
-        for output in &outputs {
-            let extra = extra_outputs.contains(output);
-
-            let output_transition1 = sys1.next_outputs(curr_pair.get_locations1(), output);
-            let output_transition2 = if extra {
-                vec![Transition::new(curr_pair.get_locations2(), dimensions)]
-            } else {
-                sys2.next_outputs(curr_pair.get_locations2(), output)
-            };
-
-            match has_valid_state_pairs(
-                &output_transition1,
-                &output_transition2,
-                &curr_pair,
-                &mut context,
-                true,
-            ) {
-                StatePairResult::Valid => trace!("Created state pairs for input {}", output),
-                StatePairResult::EmptyTransition2s => {
-                    log_refinement_check_failure(
-                        &output_transition1,
-                        &output_transition2,
-                        &curr_pair,
-                        &context,
-                        output,
-                        false,
-                    );
-                    return RefinementResult::Failure(RefinementFailure::EmptyTransition2s(
-                        curr_pair,
-                    ));
-                }
-                StatePairResult::NotEmptyResult => {
-                    log_refinement_check_failure(
-                        &output_transition1,
-                        &output_transition2,
-                        &curr_pair,
-                        &context,
-                        output,
-                        false,
-                    );
-                    return RefinementResult::Failure(RefinementFailure::NotEmptyResult(curr_pair));
-                }
-                StatePairResult::CutsDelaySolutions => {
-                    log_refinement_check_failure(
-                        &output_transition1,
-                        &output_transition2,
-                        &curr_pair,
-                        &context,
-                        output,
-                        false,
-                    );
-                    return RefinementResult::Failure(RefinementFailure::CutsDelaySolutions(
-                        curr_pair,
-                    ));
-                }
-            }
+        if let Err(failure) = explore_state_pair(curr_pair, &mut context) {
+            return (RefinementResult::Failure(failure), context.passed_list);
         }
+    }
+    info!("Refinement check passed");
+    if log_enabled!(Level::Debug) {
+        debug!("With relation:");
+        print_relation(&context.passed_list);
+    }
 
-        for input in &inputs {
-            let extra = extra_inputs.contains(input);
+    (RefinementResult::Success(None), context.passed_list)
+}
 
-            let input_transitions1 = if extra {
-                vec![Transition::new(curr_pair.get_locations1(), dimensions)]
-            } else {
-                sys1.next_inputs(curr_pair.get_locations1(), input)
-            };
+/// Explores the state pairs with `threads` workers sharing a passed list and a waiting list.
+/// Each worker takes a pair from the waiting list and, if no worker has explored it already,
+/// puts the successors of the pair in the waiting list, until it is empty or a failure is found
+fn search_refinement_in_parallel(
+    initial_pair: StatePair,
+    actions: &RefinementActions,
+    sys1: &TransitionSystemPtr,
+    sys2: &TransitionSystemPtr,
+    options: &RefinementOptions,
+) -> (RefinementResult, PassedStateList) {
+    let threads = options.threads;
+    debug!("Checking refinement with {} threads", threads);
+    let passed_list = SharedPassedList::new(threads * 4);
+    let mut waiting = SearchQueue::new(options.search_order);
+    waiting.push(initial_pair, 0);
+    let waiting_list = SharedWaitingList {
+        state: Mutex::new(SharedWaitingState {
+            waiting,
+            active_workers: 0,
+            failure: None,
+            stopped: false,
+        }),
+        changed: Condvar::new(),
+    };
 
-            let input_transitions2 = sys2.next_inputs(curr_pair.get_locations2(), input);
+    // The workers check the limits of the query of this thread, and their log records are captured for it
+    let token = limits::current_token();
+    let capture = logging::current_capture();
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                logging::with_capture(capture.clone(), || {
+                    limits::with_token(&token, || {
+                        refinement_worker(actions, sys1, sys2, options, &passed_list, &waiting_list)
+                    })
+                })
+            });
+        }
+    });
+
+    let state = waiting_list.state.into_inner().unwrap();
+    let passed_list = passed_list.into_passed_list();
+    match state.failure {
+        Some(failure) => (RefinementResult::Failure(failure), passed_list),
+        None if state.stopped => {
+            debug!("Refinement check stopped");
+            (RefinementResult::Success(None), passed_list)
+        }
+        None => {
+            info!("Refinement check passed");
+            (RefinementResult::Success(None), passed_list)
+        }
+    }
+}
 
-            match has_valid_state_pairs(
-                &input_transitions2,
-                &input_transitions1,
-                &curr_pair,
-                &mut context,
-                false,
-            ) {
-                StatePairResult::Valid => trace!("Created state pairs for input {}", input),
-                StatePairResult::EmptyTransition2s => {
-                    log_refinement_check_failure(
-                        &input_transitions1,
-                        &input_transitions2,
-                        &curr_pair,
-                        &context,
-                        input,
-                        true,
-                    );
-                    return RefinementResult::Failure(RefinementFailure::EmptyTransition2s(
-                        curr_pair,
-                    ));
+fn refinement_worker(
+    actions: &RefinementActions,
+    sys1: &TransitionSystemPtr,
+    sys2: &TransitionSystemPtr,
+    options: &RefinementOptions,
+    passed_list: &SharedPassedList,
+    waiting_list: &SharedWaitingList,
+) {
+    // The waiting list of the context holds the successors of the pair being explored
+    let mut context = RefinementContext::new(sys1, sys2, actions, options.search_order);
+    context.shared_passed_list = Some(passed_list);
+
+    loop {
+        let curr_pair = {
+            let mut state = waiting_list.state.lock().unwrap();
+            loop {
+                if state.failure.is_some() || state.stopped {
+                    return;
+                }
+                if let Some((pair, _)) = state.waiting.pop() {
+                    state.active_workers += 1;
+                    break pair;
                 }
-                StatePairResult::NotEmptyResult => {
-                    log_refinement_check_failure(
-                        &input_transitions1,
-                        &input_transitions2,
-                        &curr_pair,
-                        &context,
-                        input,
-                        true,
-                    );
-                    return RefinementResult::Failure(RefinementFailure::NotEmptyResult(curr_pair));
+                if state.active_workers == 0 {
+                    // Nothing is waiting and no worker can add more pairs
+                    return;
                 }
-                StatePairResult::CutsDelaySolutions => {
-                    log_refinement_check_failure(
-                        &input_transitions1,
-                        &input_transitions2,
-                        &curr_pair,
-                        &context,
-                        input,
-                        true,
-                    );
-                    return RefinementResult::Failure(RefinementFailure::CutsDelaySolutions(
-                        curr_pair,
-                    ));
+                state = waiting_list.changed.wait(state).unwrap();
+            }
+        };
+
+        // The error is None if the query was stopped
+        let result = if !passed_list.put_if_new(&curr_pair) {
+            Ok(())
+        } else if limits::should_stop() {
+            Err(None)
+        } else {
+            trace!("Checking {}", curr_pair);
+            explore_state_pair(curr_pair, &mut context).map_err(Some)
+        };
+
+        let mut state = waiting_list.state.lock().unwrap();
+        state.active_workers -= 1;
+        match result {
+            Ok(()) => {
+                while let Some((pair, distance)) = context.waiting_list.pop_with_distance() {
+                    state.waiting.push(pair, distance);
+                }
+            }
+            Err(Some(failure)) => {
+                if state.failure.is_none() {
+                    state.failure = Some(failure);
                 }
             }
+            Err(None) => state.stopped = true,
         }
+        waiting_list.changed.notify_all();
     }
-    info!("Refinement check passed");
-    if log_enabled!(Level::Debug) {
-        debug!("With relation:");
-        print_relation(&context.passed_list);
+}
+
+/// Checks that the outputs of sys1 and the inputs of sys2 in `curr_pair` can be matched by the other system,
+/// and puts the resulting state pairs in the waiting list of the context
+fn explore_state_pair(
+    curr_pair: StatePair,
+    context: &mut RefinementContext,
+) -> Result<(), RefinementFailure> {
+    let (sys1, sys2, actions) = (context.sys1, context.sys2, context.actions);
+    let dimensions = sys1.get_dim();
+    let RefinementActions {
+        inputs,
+        outputs,
+        extra_inputs,
+        extra_outputs,
+    } = actions;
+
+    for output in outputs {
+        let extra = extra_outputs.contains(output);
+
+        let output_transition1 = sys1.next_outputs(curr_pair.get_locations1(), output);
+        let output_transition2 = if extra {
+            vec![Transition::new(curr_pair.get_locations2(), dimensions)]
+        } else {
+            sys2.next_outputs(curr_pair.get_locations2(), output)
+        };
+
+        match has_valid_state_pairs(
+            &output_transition1,
+            &output_transition2,
+            &curr_pair,
+            context,
+            output,
+            true,
+        ) {
+            StatePairResult::Valid => trace!("Created state pairs for input {}", output),
+            StatePairResult::EmptyTransition2s => {
+                log_refinement_check_failure(
+                    &output_transition1,
+                    &output_transition2,
+                    &curr_pair,
+                    context,
+                    output,
+                    false,
+                );
+                return Err(RefinementFailure::EmptyTransition2s(curr_pair));
+            }
+            StatePairResult::NotEmptyResult => {
+                log_refinement_check_failure(
+                    &output_transition1,
+                    &output_transition2,
+                    &curr_pair,
+                    context,
+                    output,
+                    false,
+                );
+                return Err(RefinementFailure::NotEmptyResult(curr_pair));
+            }
+            StatePairResult::CutsDelaySolutions => {
+                log_refinement_check_failure(
+                    &output_transition1,
+                    &output_transition2,
+                    &curr_pair,
+                    context,
+                    output,
+                    false,
+                );
+                return Err(RefinementFailure::CutsDelaySolutions(curr_pair));
+            }
+        }
     }
 
-    RefinementResult::Success
+    for input in inputs {
+        let extra = extra_inputs.contains(input);
+
+        let input_transitions1 = if extra {
+            vec![Transition::new(curr_pair.get_locations1(), dimensions)]
+        } else {
+            sys1.next_inputs(curr_pair.get_locations1(), input)
+        };
+
+        let input_transitions2 = sys2.next_inputs(curr_pair.get_locations2(), input);
+
+        match has_valid_state_pairs(
+            &input_transitions2,
+            &input_transitions1,
+            &curr_pair,
+            context,
+            input,
+            false,
+        ) {
+            StatePairResult::Valid => trace!("Created state pairs for input {}", input),
+            StatePairResult::EmptyTransition2s => {
+                log_refinement_check_failure(
+                    &input_transitions1,
+                    &input_transitions2,
+                    &curr_pair,
+                    context,
+                    input,
+                    true,
+                );
+                return Err(RefinementFailure::EmptyTransition2s(curr_pair));
+            }
+            StatePairResult::NotEmptyResult => {
+                log_refinement_check_failure(
+                    &input_transitions1,
+                    &input_transitions2,
+                    &curr_pair,
+                    context,
+                    input,
+                    true,
+                );
+                return Err(RefinementFailure::NotEmptyResult(curr_pair));
+            }
+            StatePairResult::CutsDelaySolutions => {
+                log_refinement_check_failure(
+                    &input_transitions1,
+                    &input_transitions2,
+                    &curr_pair,
+                    context,
+                    input,
+                    true,
+                );
+                return Err(RefinementFailure::CutsDelaySolutions(curr_pair));
+            }
+        }
+    }
+    Ok(())
 }
 
 fn log_refinement_check_failure(
@@ -349,6 +820,7 @@ fn log_refinement_check_failure(
             debug!("{}", t);
         }
         debug!("Current pair: {}", curr_pair);
+        debug!("Trace: \n{}", curr_pair.trace_to_string());
         debug!("Relation:");
         print_relation(&context.passed_list);
     }
@@ -358,9 +830,10 @@ fn print_relation(passed_list: &PassedStateList) {
     let verbose = false;
 
     let mut sorted_keys: Vec<_> = passed_list.keys().collect();
-    sorted_keys.sort_by_key(|(a, b)| format!("1:{}, 2:{}", a, b));
-    for (id1, id2) in sorted_keys {
-        let zones = passed_list.zones(&(id1.clone(), id2.clone()));
+    sorted_keys.sort_by_key(|(a, b)| format!("1:{}, 2:{}", format_key(a), format_key(b)));
+    for key in sorted_keys {
+        let zones = passed_list.zones(key);
+        let (id1, id2) = (format_key(&key.0), format_key(&key.1));
 
         debug!(
             "{}",
@@ -375,11 +848,20 @@ fn print_relation(passed_list: &PassedStateList) {
     }
 }
 
+fn format_key((id, values): &LocationKey) -> String {
+    if values.is_empty() {
+        id.to_string()
+    } else {
+        format!("{}{:?}", id, values)
+    }
+}
+
 fn has_valid_state_pairs(
     transitions1: &[Transition],
     transitions2: &[Transition],
     curr_pair: &StatePair,
     context: &mut RefinementContext,
+    action: &str,
     is_state1: bool,
 ) -> StatePairResult {
     let (fed1, fed2) = get_guard_fed_for_sides(transitions1, transitions2, curr_pair, is_state1);
@@ -403,7 +885,14 @@ fn has_valid_state_pairs(
     }
 
     // Finally try to create the pairs
-    let res = try_create_new_state_pairs(transitions1, transitions2, curr_pair, context, is_state1);
+    let res = try_create_new_state_pairs(
+        transitions1,
+        transitions2,
+        curr_pair,
+        context,
+        action,
+        is_state1,
+    );
 
     match res {
         BuildResult::Success => StatePairResult::Valid,
@@ -455,13 +944,19 @@ fn try_create_new_state_pairs(
     transitions2: &[Transition],
     curr_pair: &StatePair,
     context: &mut RefinementContext,
+    action: &str,
     is_state1: bool,
 ) -> BuildResult {
     for transition1 in transitions1 {
         for transition2 in transitions2 {
-            if let BuildResult::Failure =
-                build_state_pair(transition1, transition2, curr_pair, context, is_state1)
-            {
+            if let BuildResult::Failure = build_state_pair(
+                transition1,
+                transition2,
+                curr_pair,
+                context,
+                action,
+                is_state1,
+            ) {
                 return BuildResult::Failure;
             }
         }
@@ -475,6 +970,7 @@ fn build_state_pair(
     transition2: &Transition,
     curr_pair: &StatePair,
     context: &mut RefinementContext,
+    action: &str,
     is_state1: bool,
 ) -> BuildResult {
     //Creates new state pair
@@ -540,15 +1036,61 @@ fn build_state_pair(
 
     new_sp.extrapolate_max_bounds(context.sys1, context.sys2);
 
-    if !context.passed_list.has(&new_sp) && !context.waiting_list.has(&new_sp) {
+    if !context.has(&new_sp) {
         debug!("New state {}", new_sp);
 
-        context.waiting_list.put(new_sp);
+        let (left_transition, right_transition) = if is_state1 {
+            (transition1, transition2)
+        } else {
+            (transition2, transition1)
+        };
+        new_sp.set_predecessor(StatePairStep {
+            previous: curr_pair.clone(),
+            action: action.to_string(),
+            transition1: left_transition.id.clone(),
+            transition2: right_transition.id.clone(),
+        });
+        let distance = if context.waiting_list.order().uses_distance() {
+            distance_to_failure(&new_sp, context)
+        } else {
+            0
+        };
+        context.waiting_list.put_with_distance(new_sp, distance);
     }
 
     BuildResult::Success
 }
 
+/// Estimates how close the state pair is to a refinement failure, by the number of compared actions
+/// which are not taken by only one of the systems from the locations of the pair.
+/// An action with transitions in only one of the systems fails the refinement, unless its guards never hold
+fn distance_to_failure(pair: &StatePair, context: &RefinementContext) -> usize {
+    let (sys1, sys2) = (context.sys1, context.sys2);
+    let actions = context.actions;
+    let (locations1, locations2) = (pair.get_locations1(), pair.get_locations2());
+
+    let unmatched_outputs = actions
+        .outputs
+        .iter()
+        .filter(|output| !actions.extra_outputs.contains(*output))
+        .filter(|output| {
+            !sys1.next_outputs(locations1, output).is_empty()
+                && sys2.next_outputs(locations2, output).is_empty()
+        })
+        .count();
+    let unmatched_inputs = actions
+        .inputs
+        .iter()
+        .filter(|input| !actions.extra_inputs.contains(*input))
+        .filter(|input| {
+            !sys2.next_inputs(locations2, input).is_empty()
+                && sys1.next_inputs(locations1, input).is_empty()
+        })
+        .count();
+
+    actions.outputs.len() + actions.inputs.len() - unmatched_outputs - unmatched_inputs
+}
+
 fn prepare_init_state(
     initial_pair: &mut StatePair,
     initial_locations_1: LocationTuple,
@@ -563,7 +1105,11 @@ fn prepare_init_state(
     !initial_pair.ref_zone().is_empty()
 }
 
-fn check_preconditions(sys1: &TransitionSystemPtr, sys2: &TransitionSystemPtr) -> RefinementResult {
+fn check_preconditions(
+    sys1: &TransitionSystemPtr,
+    sys2: &TransitionSystemPtr,
+    ignored: &IgnoredActions,
+) -> RefinementResult {
     match sys1.precheck_sys_rep() {
         PrecheckResult::Success => {}
         PrecheckResult::NotDeterministic(location, action) => {
@@ -625,11 +1171,11 @@ fn check_preconditions(sys1: &TransitionSystemPtr, sys2: &TransitionSystemPtr) -
         }
     }
 
-    let s_outputs = sys1.get_output_actions();
-    let t_outputs = sys2.get_output_actions();
+    let s_outputs = without_ignored(sys1.get_output_actions(), &ignored.outputs);
+    let t_outputs = without_ignored(sys2.get_output_actions(), &ignored.outputs);
 
-    let s_inputs = sys1.get_input_actions();
-    let t_inputs = sys2.get_input_actions();
+    let s_inputs = without_ignored(sys1.get_input_actions(), &ignored.inputs);
+    let t_inputs = without_ignored(sys2.get_input_actions(), &ignored.inputs);
 
     let mut disjoint = true;
 
@@ -671,6 +1217,6 @@ fn check_preconditions(sys1: &TransitionSystemPtr, sys2: &TransitionSystemPtr) -
             actions,
         )))
     } else {
-        RefinementResult::Success
+        RefinementResult::Success(None)
     }
 }
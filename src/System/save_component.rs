@@ -31,8 +31,8 @@ pub fn combine_components(
     let mut comp = Component {
         name: "".to_string(),
         declarations: Declarations {
-            ints: HashMap::new(),
             clocks,
+            ..Declarations::empty()
         },
         locations,
         edges,
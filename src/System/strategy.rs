@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use edbm::util::constraints::ClockIndex;
+use edbm::zones::OwnedFederation;
+
+use crate::ModelObjects::component::State;
+use crate::ModelObjects::representations::BoolExpression;
+use crate::System::local_consistency::ConsistencyFailure;
+use crate::TransitionSystems::{LocationKey, LocationTuple, TransitionID};
+
+/// What the outputs of a system do in a zone of a location to stay consistent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StrategyMove {
+    Delay,
+    Output {
+        action: String,
+        transition: TransitionID,
+    },
+}
+
+impl fmt::Display for StrategyMove {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StrategyMove::Delay => write!(f, "delay"),
+            StrategyMove::Output { action, transition } => {
+                write!(f, "output {} by {}", action, transition)
+            }
+        }
+    }
+}
+
+/// The result of a strategy query. A system which is not consistent has no strategy, so the [ConsistencyFailure] says why
+pub enum StrategyResult {
+    /// The strategy and the transitions of the outputs it takes from the initial state, found by simulating it
+    Success(ConsistencyStrategy, Vec<TransitionID>),
+    Failure(ConsistencyFailure),
+}
+
+/// A zone of a location tuple and the move taken in it
+#[derive(Debug, Clone)]
+pub struct StrategyEntry {
+    pub federation: OwnedFederation,
+    pub strategy_move: StrategyMove,
+}
+
+/// A winning strategy for the outputs of a system to avoid inconsistent states,
+/// found by the least consistency check
+#[derive(Debug, Clone, Default)]
+pub struct ConsistencyStrategy {
+    pub moves: HashMap<LocationKey, Vec<StrategyEntry>>,
+    /// The names of the clocks of the system, to print the federations of the entries with
+    pub clock_names: HashMap<String, ClockIndex>,
+}
+
+impl ConsistencyStrategy {
+    pub fn new(clock_names: HashMap<String, ClockIndex>) -> Self {
+        ConsistencyStrategy {
+            moves: HashMap::new(),
+            clock_names,
+        }
+    }
+
+    pub fn add_move(
+        &mut self,
+        location: &LocationTuple,
+        federation: OwnedFederation,
+        strategy_move: StrategyMove,
+    ) {
+        if federation.is_empty() {
+            return;
+        }
+        self.moves
+            .entry(location.get_key())
+            .or_default()
+            .push(StrategyEntry {
+                federation,
+                strategy_move,
+            });
+    }
+
+    /// Adds the moves of `other`, which must be a strategy for the same system
+    pub fn extend(&mut self, other: ConsistencyStrategy) {
+        for (key, entries) in other.moves {
+            self.moves.entry(key).or_default().extend(entries);
+        }
+    }
+
+    /// Returns the move to take in `state`, if the strategy covers any of its zone
+    pub fn get_move(&self, state: &State) -> Option<&StrategyMove> {
+        self.moves
+            .get(&state.get_location().get_key())?
+            .iter()
+            .find(|entry| {
+                !entry
+                    .federation
+                    .clone()
+                    .intersection(state.zone_ref())
+                    .is_empty()
+            })
+            .map(|entry| &entry.strategy_move)
+    }
+
+    /// Returns the entries of the strategy sorted by location, for printing and exporting
+    pub fn sorted_entries(&self) -> Vec<(&LocationKey, &StrategyEntry)> {
+        let mut keys: Vec<_> = self.moves.keys().collect();
+        keys.sort_by_key(|(id, values)| format!("{}{:?}", id, values));
+        keys.into_iter()
+            .flat_map(|key| self.moves[key].iter().map(move |entry| (key, entry)))
+            .collect()
+    }
+
+    /// Formats a federation of the strategy using the names of the clocks
+    pub fn format_federation(&self, federation: &OwnedFederation) -> String {
+        match BoolExpression::from_disjunction(&federation.minimal_constraints(), &self.clock_names)
+        {
+            Some(expression) => expression.to_string(),
+            None => "true".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ConsistencyStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for ((id, values), entry) in self.sorted_entries() {
+            if values.is_empty() {
+                write!(f, "{}", id)?;
+            } else {
+                write!(f, "{}{:?}", id, values)?;
+            }
+            writeln!(
+                f,
+                " {}: {}",
+                self.format_federation(&entry.federation),
+                entry.strategy_move
+            )?;
+        }
+        Ok(())
+    }
+}
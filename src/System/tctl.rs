@@ -0,0 +1,498 @@
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use edbm::util::bounds::Bounds;
+use edbm::util::constraints::{ClockIndex, Inequality};
+use edbm::zones::OwnedFederation;
+use log::debug;
+
+use crate::ModelObjects::component::State;
+use crate::ModelObjects::representations::QueryExpression;
+use crate::System::reachability::{make_path, Path, SubPath};
+use crate::TransitionSystems::{LocationID, TransitionSystem};
+
+/// The path quantifiers of the supported TCTL formulas
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TctlQuantifier {
+    /// `E<> p`: Some reachable state satisfies `p`
+    Possibly,
+    /// `A[] p`: Every reachable state satisfies `p`
+    Invariantly,
+    /// `E[] p`: Some maximal path only goes through states satisfying `p`
+    EventuallyAlways,
+    /// `A<> p`: Every maximal path goes through a state satisfying `p`
+    Potentially,
+}
+
+impl TctlQuantifier {
+    /// Splits a TCTL formula into its quantifier and predicate
+    pub fn from_formula(formula: &QueryExpression) -> Option<(Self, &QueryExpression)> {
+        match formula {
+            QueryExpression::Possibly(predicate) => Some((TctlQuantifier::Possibly, predicate)),
+            QueryExpression::Invariantly(predicate) => {
+                Some((TctlQuantifier::Invariantly, predicate))
+            }
+            QueryExpression::EventuallyAlways(predicate) => {
+                Some((TctlQuantifier::EventuallyAlways, predicate))
+            }
+            QueryExpression::Potentially(predicate) => {
+                Some((TctlQuantifier::Potentially, predicate))
+            }
+            QueryExpression::Parentheses(inner) => TctlQuantifier::from_formula(inner),
+            _ => None,
+        }
+    }
+}
+
+/// The result of a TCTL query.
+/// `path` is a witness for `E<>` and `E[]` formulas that are satisfied,
+/// and a counterexample for `A[]` and `A<>` formulas that are not.
+pub struct TctlResult {
+    pub satisfied: bool,
+    pub path: Option<Path>,
+}
+
+/// A state predicate over locations and clocks, compiled against a [TransitionSystem]
+pub enum Predicate {
+    Bool(bool),
+    Location {
+        component: Option<String>,
+        location: String,
+    },
+    Clocks(OwnedFederation),
+    And(Box<Predicate>, Box<Predicate>),
+    Or(Box<Predicate>, Box<Predicate>),
+    Not(Box<Predicate>),
+}
+
+/// One side of a clock constraint, either a clock or an integer constant
+enum Operand {
+    Clock(ClockIndex),
+    Int(i32),
+}
+
+impl Predicate {
+    /// Compiles a predicate such as `Machine.L5 && Machine.y < 5` against the given [TransitionSystem].
+    /// The bounds of all clock constraints are added to `bounds`, so extrapolation does not lose precision.
+    pub fn compile(
+        expression: &QueryExpression,
+        system: &dyn TransitionSystem,
+        bounds: &mut Bounds,
+    ) -> Result<Predicate, String> {
+        match expression {
+            QueryExpression::Bool(value) => Ok(Predicate::Bool(*value)),
+            QueryExpression::Parentheses(inner) => Predicate::compile(inner, system, bounds),
+            QueryExpression::AndOp(left, right) => Ok(Predicate::And(
+                Box::new(Predicate::compile(left, system, bounds)?),
+                Box::new(Predicate::compile(right, system, bounds)?),
+            )),
+            QueryExpression::OrOp(left, right) => Ok(Predicate::Or(
+                Box::new(Predicate::compile(left, system, bounds)?),
+                Box::new(Predicate::compile(right, system, bounds)?),
+            )),
+            QueryExpression::Not(inner) => Ok(Predicate::Not(Box::new(Predicate::compile(
+                inner, system, bounds,
+            )?))),
+            QueryExpression::VarName(name) => match name.split_once('.') {
+                Some((component, location)) => {
+                    if !system.component_names().contains(&component) {
+                        return Err(format!("Unknown component \"{}\" in {}", component, name));
+                    }
+                    Ok(Predicate::Location {
+                        component: Some(component.to_string()),
+                        location: location.to_string(),
+                    })
+                }
+                None => Ok(Predicate::Location {
+                    component: None,
+                    location: name.clone(),
+                }),
+            },
+            QueryExpression::LessEQ(left, right) => {
+                compile_constraint(left, right, false, false, system, bounds)
+            }
+            QueryExpression::LessT(left, right) => {
+                compile_constraint(left, right, true, false, system, bounds)
+            }
+            QueryExpression::GreatEQ(left, right) => {
+                compile_constraint(left, right, false, true, system, bounds)
+            }
+            QueryExpression::GreatT(left, right) => {
+                compile_constraint(left, right, true, true, system, bounds)
+            }
+            _ => Err(format!(
+                "Unsupported expression in TCTL predicate: {:?}",
+                expression
+            )),
+        }
+    }
+
+    /// Returns the part of the zone of `state` where the predicate holds
+    pub fn satisfying_zone(&self, state: &State) -> OwnedFederation {
+        let zone = state.zone_ref();
+        match self {
+            Predicate::Bool(true) => zone.clone(),
+            Predicate::Bool(false) => OwnedFederation::empty(zone.dim()),
+            Predicate::Location {
+                component,
+                location,
+            } => {
+                if is_in_location(&state.get_location().id, component, location) {
+                    zone.clone()
+                } else {
+                    OwnedFederation::empty(zone.dim())
+                }
+            }
+            Predicate::Clocks(fed) => zone.clone().intersection(fed),
+            Predicate::And(left, right) => left
+                .satisfying_zone(state)
+                .intersection(&right.satisfying_zone(state)),
+            Predicate::Or(left, right) => left
+                .satisfying_zone(state)
+                .union(&right.satisfying_zone(state)),
+            Predicate::Not(inner) => zone.clone().subtraction(&inner.satisfying_zone(state)),
+        }
+    }
+}
+
+/// Returns the names of the components referred to as `Component.name` in a predicate, in order of appearance
+pub fn referenced_components(expression: &QueryExpression) -> Vec<String> {
+    fn collect(expression: &QueryExpression, names: &mut Vec<String>) {
+        match expression {
+            QueryExpression::VarName(name) => {
+                if let Some((component, _)) = name.split_once('.') {
+                    if !names.iter().any(|n| n == component) {
+                        names.push(component.to_string());
+                    }
+                }
+            }
+            QueryExpression::Possibly(inner)
+            | QueryExpression::Invariantly(inner)
+            | QueryExpression::EventuallyAlways(inner)
+            | QueryExpression::Potentially(inner)
+            | QueryExpression::Parentheses(inner)
+            | QueryExpression::Not(inner) => collect(inner, names),
+            QueryExpression::AndOp(left, right)
+            | QueryExpression::OrOp(left, right)
+            | QueryExpression::LessEQ(left, right)
+            | QueryExpression::LessT(left, right)
+            | QueryExpression::GreatEQ(left, right)
+            | QueryExpression::GreatT(left, right) => {
+                collect(left, names);
+                collect(right, names);
+            }
+            _ => (),
+        }
+    }
+
+    let mut names = vec![];
+    collect(expression, &mut names);
+    names
+}
+
+fn is_in_location(id: &LocationID, component: &Option<String>, location: &str) -> bool {
+    id.inorder_vec_tranform().iter().any(|leaf| match leaf {
+        LocationID::Simple {
+            location_id,
+            component_id,
+        } => location_id == location && (component.is_none() || component_id == component),
+        _ => false,
+    })
+}
+
+/// Compiles `left < right`, `left <= right`, `left > right` or `left >= right` into a federation.
+/// Both sides are written as `x + c` where `x` is a clock or the reference clock 0.
+fn compile_constraint(
+    left: &QueryExpression,
+    right: &QueryExpression,
+    strict: bool,
+    greater: bool,
+    system: &dyn TransitionSystem,
+    bounds: &mut Bounds,
+) -> Result<Predicate, String> {
+    let (left, right) = if greater {
+        (right, left)
+    } else {
+        (left, right)
+    };
+    let (left_clock, left_constant) = match compile_operand(left, system)? {
+        Operand::Clock(clock) => (clock, 0),
+        Operand::Int(value) => (0, value),
+    };
+    let (right_clock, right_constant) = match compile_operand(right, system)? {
+        Operand::Clock(clock) => (clock, 0),
+        Operand::Int(value) => (0, value),
+    };
+
+    // left_clock + left_constant < right_clock + right_constant
+    // <=> left_clock - right_clock < right_constant - left_constant
+    let bound = right_constant - left_constant;
+    if left_clock == 0 && right_clock == 0 {
+        return Ok(Predicate::Bool(if strict { bound > 0 } else { bound >= 0 }));
+    }
+
+    for clock in [left_clock, right_clock] {
+        if clock != 0 {
+            bounds.add_lower(clock, bound.abs());
+            bounds.add_upper(clock, bound.abs());
+        }
+    }
+
+    let inequality = if strict {
+        Inequality::LS(bound)
+    } else {
+        Inequality::LE(bound)
+    };
+    Ok(Predicate::Clocks(
+        OwnedFederation::universe(system.get_dim()).constrain(left_clock, right_clock, inequality),
+    ))
+}
+
+fn compile_operand(
+    expression: &QueryExpression,
+    system: &dyn TransitionSystem,
+) -> Result<Operand, String> {
+    match expression {
+        QueryExpression::Int(value) => Ok(Operand::Int(*value)),
+        QueryExpression::Parentheses(inner) => compile_operand(inner, system),
+        QueryExpression::VarName(name) => {
+            let clock = match name.split_once('.') {
+                Some((component, clock)) => {
+                    system.clock_name_and_component_to_index(clock, component)
+                }
+                None => {
+                    let matches: Vec<ClockIndex> = system
+                        .clock_name_and_component_to_index_map()
+                        .into_iter()
+                        .filter(|((clock, _), _)| clock == name)
+                        .map(|(_, index)| index)
+                        .collect();
+                    if matches.len() > 1 {
+                        return Err(format!(
+                            "Clock \"{}\" is ambiguous, prefix it with its component name",
+                            name
+                        ));
+                    }
+                    matches.first().copied()
+                }
+            };
+            clock
+                .map(Operand::Clock)
+                .ok_or_else(|| format!("Unknown clock \"{}\" in TCTL predicate", name))
+        }
+        _ => Err(format!(
+            "Unsupported operand in clock constraint: {:?}",
+            expression
+        )),
+    }
+}
+
+/// Checks a TCTL formula with the given quantifier and predicate on a [TransitionSystem].
+/// `bounds` must contain the bounds of the clock constraints in `predicate`.
+pub fn check(
+    system: &dyn TransitionSystem,
+    quantifier: TctlQuantifier,
+    predicate: Predicate,
+    bounds: &Bounds,
+) -> TctlResult {
+    let (path, satisfied_if_found) = match quantifier {
+        TctlQuantifier::Possibly => (find_path_to(system, &predicate, bounds), true),
+        TctlQuantifier::Invariantly => (
+            find_path_to(system, &Predicate::Not(Box::new(predicate)), bounds),
+            false,
+        ),
+        TctlQuantifier::EventuallyAlways => (find_always_path(system, &predicate, bounds), true),
+        TctlQuantifier::Potentially => (
+            find_always_path(system, &Predicate::Not(Box::new(predicate)), bounds),
+            false,
+        ),
+    };
+
+    debug!(
+        "TCTL {:?} check found {}",
+        quantifier,
+        if path.is_some() { "a path" } else { "no path" }
+    );
+
+    TctlResult {
+        satisfied: path.is_some() == satisfied_if_found,
+        path,
+    }
+}
+
+/// The initial state of the system, delayed as far as the invariant allows
+fn delayed_initial_state(system: &dyn TransitionSystem) -> Option<State> {
+    let mut state = system.get_initial_state()?;
+    let zone = state.take_zone().up();
+    let zone = state.decorated_locations.apply_invariants(zone);
+    state.set_zone(zone);
+    Some(state)
+}
+
+fn extrapolate(state: &mut State, system: &dyn TransitionSystem, bounds: &Bounds) {
+    let mut local_bounds = system.get_local_max_bounds(&state.decorated_locations);
+    local_bounds.add_bounds(bounds);
+    let zone = state.take_zone().extrapolate_max_bounds(&local_bounds);
+    state.set_zone(zone);
+}
+
+fn get_sorted_actions(system: &dyn TransitionSystem) -> Vec<String> {
+    let mut actions: Vec<String> = system.get_actions().into_iter().collect();
+    actions.sort();
+    actions
+}
+
+/// Runs a BFS for a reachable state where `predicate` holds, returning the path to it
+fn find_path_to(
+    system: &dyn TransitionSystem,
+    predicate: &Predicate,
+    bounds: &Bounds,
+) -> Option<Path> {
+    let start_state = delayed_initial_state(system)?;
+    let actions = get_sorted_actions(system);
+
+    let mut passed_list: Vec<State> = vec![];
+    let mut waiting_list = VecDeque::from([Rc::new(SubPath {
+        previous_sub_path: None,
+        destination_state: start_state,
+        transition: None,
+    })]);
+
+    while let Some(sub_path) = waiting_list.pop_front() {
+        let state = &sub_path.destination_state;
+        if !predicate.satisfying_zone(state).is_empty() {
+            return Some(make_path(sub_path));
+        }
+        if state.is_contained_in_list(&passed_list) {
+            continue;
+        }
+        passed_list.push(state.clone());
+
+        for action in &actions {
+            for transition in system.next_transitions(&state.decorated_locations, action) {
+                let mut new_state = state.clone();
+                if transition.use_transition(&mut new_state) {
+                    extrapolate(&mut new_state, system, bounds);
+                    if !new_state.is_contained_in_list(&passed_list) {
+                        waiting_list.push_back(Rc::new(SubPath {
+                            previous_sub_path: Some(Rc::clone(&sub_path)),
+                            destination_state: new_state,
+                            transition: Some(transition.clone()),
+                        }));
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Runs a DFS for a maximal path on which `predicate` always holds.
+/// Such a path either loops, lets time pass forever or ends in a state without successors.
+/// Zones are restricted to the predicate, so a path only ends by delaying if every delay from its last zone satisfies the predicate.
+fn find_always_path(
+    system: &dyn TransitionSystem,
+    predicate: &Predicate,
+    bounds: &Bounds,
+) -> Option<Path> {
+    let mut start_state = delayed_initial_state(system)?;
+    let zone = predicate.satisfying_zone(&start_state);
+    if zone.is_empty() {
+        return None;
+    }
+    start_state.set_zone(zone);
+
+    let start = Rc::new(SubPath {
+        previous_sub_path: None,
+        destination_state: start_state,
+        transition: None,
+    });
+    let actions = get_sorted_actions(system);
+    let mut stack = vec![];
+    let mut failed = vec![];
+
+    always_path_helper(
+        start,
+        &actions,
+        &mut stack,
+        &mut failed,
+        system,
+        predicate,
+        bounds,
+    )
+    .map(make_path)
+}
+
+fn always_path_helper(
+    sub_path: Rc<SubPath>,
+    actions: &[String],
+    stack: &mut Vec<State>,
+    failed: &mut Vec<State>,
+    system: &dyn TransitionSystem,
+    predicate: &Predicate,
+    bounds: &Bounds,
+) -> Option<Rc<SubPath>> {
+    let state = &sub_path.destination_state;
+    if state.is_contained_in_list(failed) {
+        return None;
+    }
+    // We have returned to a state on the current path
+    if state.is_contained_in_list(stack) {
+        return Some(sub_path);
+    }
+    let delays_satisfy = delays_satisfy(state, predicate);
+    // Time can diverge without leaving the predicate
+    if delays_satisfy && state.zone_ref().can_delay_indefinitely() {
+        return Some(sub_path);
+    }
+
+    stack.push(state.clone());
+    let mut has_successor = false;
+    for action in actions {
+        for transition in system.next_transitions(&state.decorated_locations, action) {
+            let mut new_state = state.clone();
+            if !transition.use_transition(&mut new_state) {
+                continue;
+            }
+            has_successor = true;
+
+            let zone = predicate.satisfying_zone(&new_state);
+            if zone.is_empty() {
+                continue;
+            }
+            new_state.set_zone(zone);
+            extrapolate(&mut new_state, system, bounds);
+
+            let next = Rc::new(SubPath {
+                previous_sub_path: Some(Rc::clone(&sub_path)),
+                destination_state: new_state,
+                transition: Some(transition.clone()),
+            });
+            if let Some(path) =
+                always_path_helper(next, actions, stack, failed, system, predicate, bounds)
+            {
+                return Some(path);
+            }
+        }
+    }
+    stack.pop();
+
+    if !has_successor && delays_satisfy {
+        // The path can not be extended, so it is maximal
+        return Some(sub_path);
+    }
+    failed.push(state.clone());
+    None
+}
+
+/// Returns whether `predicate` holds after every delay from the zone of `state` allowed by the invariants.
+/// The zone is restricted to the predicate, so this fails if a valuation can leave the predicate just by delaying.
+fn delays_satisfy(state: &State, predicate: &Predicate) -> bool {
+    let mut delayed = state.clone();
+    let zone = delayed.take_zone().up();
+    let zone = delayed.decorated_locations.apply_invariants(zone);
+    delayed.set_zone(zone);
+    delayed
+        .zone_ref()
+        .subset_eq(&predicate.satisfying_zone(&delayed))
+}
@@ -0,0 +1,320 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+
+use edbm::util::constraints::{ClockIndex, Inequality};
+use edbm::zones::OwnedFederation;
+
+use crate::ModelObjects::component::{State, Transition};
+use crate::TransitionSystems::{LocationID, LocationKey, TransitionID, TransitionSystem};
+
+pub enum NonZenoResult {
+    Success,
+    Failure(ZenoCycle),
+}
+
+/// A cycle of the zone graph along which no clock is both reset and bounded from below,
+/// so its outputs may be taken infinitely often in finite time
+#[derive(Debug, Clone)]
+pub struct ZenoCycle {
+    /// The location the cycle starts and ends in
+    pub location: LocationID,
+    pub transitions: Vec<TransitionID>,
+}
+
+impl fmt::Display for NonZenoResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NonZenoResult::Success => write!(f, "Success"),
+            NonZenoResult::Failure(cycle) => write!(f, "{}", cycle),
+        }
+    }
+}
+
+impl fmt::Display for ZenoCycle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Possibly Zeno cycle from {}: {}",
+            self.location,
+            self.transitions
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+}
+
+/// An edge of the zone graph between the states with the indices `source` and `target`
+struct ZoneEdge {
+    source: usize,
+    target: usize,
+    transition: TransitionID,
+    /// The clocks the transition resets to 0
+    resets: Vec<ClockIndex>,
+    /// The clocks the guard of the transition bounds from below by at least 1
+    bounded: Vec<ClockIndex>,
+}
+
+impl ZoneEdge {
+    fn new(source: usize, target: usize, transition: &Transition, dim: ClockIndex) -> Self {
+        ZoneEdge {
+            source,
+            target,
+            transition: transition.id.clone(),
+            resets: transition
+                .updates
+                .iter()
+                .filter(|update| update.value == 0)
+                .map(|update| update.clock_index)
+                .collect(),
+            bounded: (1..dim)
+                .filter(|clock| is_bounded_from_below(&transition.guard_zone, *clock))
+                .collect(),
+        }
+    }
+}
+
+/// Returns whether `clock` must be at least 1 in `guard`.
+/// A strict bound like `x > 0` is not enough, as the delays of the rounds of a cycle may still converge.
+fn is_bounded_from_below(guard: &OwnedFederation, clock: ClockIndex) -> bool {
+    guard
+        .clone()
+        .constrain(clock, 0, Inequality::LS(1))
+        .is_empty()
+}
+
+/// Checks whether the system is strongly non-Zeno, meaning that every cycle of output transitions in its zone graph
+/// has a clock which is both reset and bounded from below on it, so each round of the cycle takes some time.
+/// Input transitions are not considered, as the environment decides how often they are taken.
+///
+/// This is a sufficient condition, so a reported cycle is possibly, but not necessarily, Zeno.
+pub fn check_non_zeno(system: &dyn TransitionSystem) -> Result<NonZenoResult, String> {
+    let initial_state = system
+        .get_initial_state()
+        .ok_or_else(|| "No initial state in the transition system".to_string())?;
+
+    let (states, edges) = explore_zone_graph(initial_state, system);
+    let edges: Vec<&ZoneEdge> = edges.iter().collect();
+
+    Ok(match find_zeno_cycle(&edges, system.get_dim()) {
+        Some(cycle) => NonZenoResult::Failure(ZenoCycle {
+            location: states[cycle[0].source].get_location().id.clone(),
+            transitions: cycle
+                .into_iter()
+                .map(|edge| edge.transition.clone())
+                .collect(),
+        }),
+        None => NonZenoResult::Success,
+    })
+}
+
+/// Explores the zone graph of the system from `initial_state`.
+/// Returns the states of the zone graph and the edges between them taken by output transitions.
+/// A successor which is included in an explored state of the same location is not added again, but gets an edge to that state.
+fn explore_zone_graph(
+    mut initial_state: State,
+    system: &dyn TransitionSystem,
+) -> (Vec<State>, Vec<ZoneEdge>) {
+    let dim = system.get_dim();
+    let outputs = system.get_output_actions();
+    let mut actions: Vec<String> = system.get_actions().into_iter().collect();
+    actions.sort();
+
+    let zone = initial_state.take_zone().up();
+    let zone = initial_state.decorated_locations.apply_invariants(zone);
+    initial_state.set_zone(zone);
+
+    let mut states = vec![];
+    let mut edges = vec![];
+    let mut explored: HashMap<LocationKey, Vec<usize>> = HashMap::new();
+    let mut frontier = VecDeque::new();
+
+    add_state(initial_state, &mut states, &mut explored);
+    frontier.push_back(0);
+
+    while let Some(source) = frontier.pop_front() {
+        for action in &actions {
+            for transition in &system.next_transitions(&states[source].decorated_locations, action)
+            {
+                let mut next_state = states[source].clone();
+                if !transition.use_transition(&mut next_state) {
+                    continue;
+                }
+                next_state.extrapolate_max_bounds(system);
+
+                let key = next_state.get_location().get_key();
+                let existing = explored.get(&key).and_then(|indices| {
+                    indices
+                        .iter()
+                        .copied()
+                        .find(|index| next_state.zone_ref().subset_eq(states[*index].zone_ref()))
+                });
+                let target = match existing {
+                    Some(target) => target,
+                    None => {
+                        let target = add_state(next_state, &mut states, &mut explored);
+                        frontier.push_back(target);
+                        target
+                    }
+                };
+
+                // The universal location allows any behaviour, so its outputs are not a modelling error
+                if outputs.contains(action) && !states[source].get_location().is_universal() {
+                    edges.push(ZoneEdge::new(source, target, transition, dim));
+                }
+            }
+        }
+    }
+
+    (states, edges)
+}
+
+fn add_state(
+    state: State,
+    states: &mut Vec<State>,
+    explored: &mut HashMap<LocationKey, Vec<usize>>,
+) -> usize {
+    let index = states.len();
+    explored
+        .entry(state.get_location().get_key())
+        .or_default()
+        .push(index);
+    states.push(state);
+    index
+}
+
+/// Finds a cycle of `edges` along which no clock is both reset and bounded from below.
+///
+/// Within a strongly connected component where some clock is both reset and bounded, every such cycle
+/// either avoids the edges resetting the clock or the edges bounding it, so both are searched without them.
+fn find_zeno_cycle<'a>(edges: &[&'a ZoneEdge], dim: ClockIndex) -> Option<Vec<&'a ZoneEdge>> {
+    for component in strongly_connected_components(edges) {
+        let progress_clock = (1..dim).find(|clock| {
+            component.iter().any(|edge| edge.resets.contains(clock))
+                && component.iter().any(|edge| edge.bounded.contains(clock))
+        });
+
+        let clock = match progress_clock {
+            Some(clock) => clock,
+            None => return Some(find_cycle(&component)),
+        };
+
+        let without_resets: Vec<&ZoneEdge> = component
+            .iter()
+            .copied()
+            .filter(|edge| !edge.resets.contains(&clock))
+            .collect();
+        if let Some(cycle) = find_zeno_cycle(&without_resets, dim) {
+            return Some(cycle);
+        }
+
+        let without_bounds: Vec<&ZoneEdge> = component
+            .iter()
+            .copied()
+            .filter(|edge| !edge.bounded.contains(&clock))
+            .collect();
+        if let Some(cycle) = find_zeno_cycle(&without_bounds, dim) {
+            return Some(cycle);
+        }
+    }
+    None
+}
+
+/// Returns the edges within each strongly connected component of the graph of `edges`,
+/// leaving out the components without any edges
+fn strongly_connected_components<'a>(edges: &[&'a ZoneEdge]) -> Vec<Vec<&'a ZoneEdge>> {
+    let mut successors: HashMap<usize, Vec<usize>> = HashMap::new();
+    let mut predecessors: HashMap<usize, Vec<usize>> = HashMap::new();
+    for edge in edges {
+        successors.entry(edge.source).or_default().push(edge.target);
+        predecessors
+            .entry(edge.target)
+            .or_default()
+            .push(edge.source);
+    }
+    let mut nodes: Vec<usize> = successors.keys().copied().collect();
+    nodes.sort_unstable();
+
+    // Kosaraju's algorithm: order the nodes by when they are finished in a depth-first search...
+    let mut finished = vec![];
+    let mut visited = HashSet::new();
+    for node in &nodes {
+        if !visited.insert(*node) {
+            continue;
+        }
+        let mut stack = vec![(*node, 0)];
+        while let Some((current, next)) = stack.pop() {
+            match successors.get(&current).and_then(|succ| succ.get(next)) {
+                Some(successor) => {
+                    stack.push((current, next + 1));
+                    if visited.insert(*successor) {
+                        stack.push((*successor, 0));
+                    }
+                }
+                None => finished.push(current),
+            }
+        }
+    }
+
+    // ...then the nodes reached backwards from them in reverse order make up the components
+    let mut component_of: HashMap<usize, usize> = HashMap::new();
+    let mut component_count = 0;
+    for node in finished.into_iter().rev() {
+        if component_of.contains_key(&node) {
+            continue;
+        }
+        let mut stack = vec![node];
+        component_of.insert(node, component_count);
+        while let Some(current) = stack.pop() {
+            for predecessor in predecessors.get(&current).into_iter().flatten() {
+                if !component_of.contains_key(predecessor) {
+                    component_of.insert(*predecessor, component_count);
+                    stack.push(*predecessor);
+                }
+            }
+        }
+        component_count += 1;
+    }
+
+    let mut components = vec![vec![]; component_count];
+    for edge in edges {
+        let component = component_of[&edge.source];
+        if component == component_of[&edge.target] {
+            components[component].push(*edge);
+        }
+    }
+    components.retain(|component| !component.is_empty());
+    components
+}
+
+/// Returns a cycle through the first edge of a strongly connected component
+fn find_cycle<'a>(component: &[&'a ZoneEdge]) -> Vec<&'a ZoneEdge> {
+    let first = component[0];
+
+    // Breadth-first search back to the source of the first edge, remembering the edge each node was reached by
+    let mut reached_by: HashMap<usize, &'a ZoneEdge> = HashMap::new();
+    let mut frontier = VecDeque::from([first.target]);
+    while let Some(node) = frontier.pop_front() {
+        if node == first.source {
+            break;
+        }
+        for edge in component.iter().copied().filter(|edge| edge.source == node) {
+            if edge.target != first.target && !reached_by.contains_key(&edge.target) {
+                reached_by.insert(edge.target, edge);
+                frontier.push_back(edge.target);
+            }
+        }
+    }
+
+    let mut cycle = vec![];
+    let mut node = first.source;
+    while node != first.target {
+        let edge = reached_by[&node];
+        cycle.push(edge);
+        node = edge.source;
+    }
+    cycle.push(first);
+    cycle.reverse();
+    cycle
+}
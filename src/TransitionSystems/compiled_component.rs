@@ -3,11 +3,16 @@ use crate::ModelObjects::component::{
     Component, DeclarationProvider, Declarations, State, Transition,
 };
 use crate::System::local_consistency::{self, ConsistencyResult, DeterminismResult};
-use crate::TransitionSystems::{LocationTuple, TransitionSystem, TransitionSystemPtr};
+use crate::TransitionSystems::{
+    LocationTuple, TransitionID, TransitionSystem, TransitionSystemPtr,
+};
 use edbm::util::bounds::Bounds;
 use edbm::util::constraints::ClockIndex;
+use edbm::zones::OwnedFederation;
+use log::error;
 use std::collections::hash_set::HashSet;
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 use super::common::CollectionOperation;
 use super::{CompositionType, LocationID};
@@ -35,6 +40,10 @@ pub struct CompiledComponent {
     location_edges: HashMap<LocationID, Vec<(Action, Transition)>>,
     initial_location: Option<LocationTuple>,
     comp_info: ComponentInfo,
+    /// Components with int variables keep their edges, as the transitions depend on the values of the ints
+    int_component: Option<Component>,
+    /// The first error met when taking an edge of `int_component`, shared with the clones of the component
+    update_error: Arc<Mutex<Option<String>>>,
     dim: ClockIndex,
 }
 
@@ -67,19 +76,37 @@ impl CompiledComponent {
             })
             .collect();
 
+        let int_component = if component.get_declarations().get_ints().is_empty() {
+            None
+        } else {
+            Some(component.clone())
+        };
+
         let mut location_edges: HashMap<LocationID, Vec<(Action, Transition)>> =
             locations.keys().map(|k| (k.clone(), vec![])).collect();
 
-        for edge in component.get_edges() {
-            let id = LocationID::Simple {
-                location_id: edge.source_location.clone(),
-                component_id: Some(component.get_name().to_owned()),
-            };
-            let transition = Transition::from(&component, edge, dim);
-            location_edges
-                .get_mut(&id)
-                .unwrap()
-                .push((edge.sync.clone(), transition));
+        // The transitions of components with ints are found from the values of the ints when needed
+        if int_component.is_none() {
+            for edge in component.get_edges() {
+                let id = LocationID::Simple {
+                    location_id: edge.source_location.clone(),
+                    component_id: Some(component.get_name().to_owned()),
+                };
+                let transition = match Transition::from(&component, edge, dim) {
+                    Ok(transition) => transition,
+                    Err(reason) => {
+                        return Err(SystemRecipeFailure::new_from_component(
+                            reason,
+                            component.clone(),
+                            vec![edge.sync.clone()],
+                        ))
+                    }
+                };
+                location_edges
+                    .get_mut(&id)
+                    .unwrap()
+                    .push((edge.sync.clone(), transition));
+            }
         }
 
         let initial_location = locations.values().find(|loc| loc.is_initial()).cloned();
@@ -91,6 +118,8 @@ impl CompiledComponent {
             locations,
             location_edges,
             initial_location,
+            int_component,
+            update_error: Arc::default(),
             dim,
             comp_info: ComponentInfo {
                 name: component.name,
@@ -121,6 +150,66 @@ impl CompiledComponent {
     fn _comp_info(&self) -> &ComponentInfo {
         &self.comp_info
     }
+
+    /// Finds the transitions of a component with int variables from the values of the ints in `locations`.
+    /// Inputs are enabled here rather than by adding edges to the component, as it depends on the values as well
+    fn next_int_transitions(
+        &self,
+        component: &Component,
+        locations: &LocationTuple,
+        action: &str,
+        is_input: bool,
+    ) -> Vec<Transition> {
+        let location_id = match &locations.id {
+            LocationID::Simple { location_id, .. } => location_id,
+            id => unreachable!("Component location {} is not simple", id),
+        };
+
+        let mut decl = self.comp_info.declarations.clone();
+        decl.ints = locations
+            .ints
+            .iter()
+            .map(|(name, value)| (name.clone(), *value))
+            .collect();
+
+        let mut transitions: Vec<Transition> = component
+            .get_edges()
+            .iter()
+            .filter(|edge| edge.source_location == *location_id && edge.sync == action)
+            .filter_map(|edge| {
+                match Transition::from_declarations(component, edge, &decl, self.dim) {
+                    Ok(transition) => transition,
+                    Err(message) => {
+                        // The search can not take the edge, so its result is replaced by the error
+                        error!("{}", message);
+                        self.update_error.lock().unwrap().get_or_insert(message);
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        if is_input {
+            let enabled = transitions
+                .iter()
+                .fold(OwnedFederation::empty(self.dim), |fed, transition| {
+                    fed + transition.get_allowed_federation()
+                });
+            let not_enabled = locations
+                .apply_invariants(OwnedFederation::universe(self.dim))
+                .subtraction(&enabled);
+            if !not_enabled.is_empty() {
+                transitions.push(Transition {
+                    id: TransitionID::Simple(format!("input_{}_{}", location_id, action)),
+                    guard_zone: not_enabled,
+                    target_locations: locations.clone(),
+                    updates: vec![],
+                });
+            }
+        }
+
+        transitions
+    }
 }
 
 impl TransitionSystem for CompiledComponent {
@@ -148,6 +237,10 @@ impl TransitionSystem for CompiledComponent {
             return vec![Transition::new(locations, self.dim)];
         }
 
+        if let Some(component) = &self.int_component {
+            return self.next_int_transitions(component, locations, action, is_input);
+        }
+
         let mut transitions = vec![];
         let edges = self.location_edges.get(&locations.id).unwrap();
 
@@ -206,6 +299,10 @@ impl TransitionSystem for CompiledComponent {
         CompositionType::Simple
     }
 
+    fn get_update_error(&self) -> Option<String> {
+        self.update_error.lock().unwrap().clone()
+    }
+
     fn get_combined_decls(&self) -> Declarations {
         self.comp_info.declarations.clone()
     }
@@ -1,4 +1,5 @@
 use edbm::util::constraints::ClockIndex;
+use edbm::zones::OwnedFederation;
 
 use crate::extract_system_rep::SystemRecipeFailure;
 use crate::ModelObjects::component::Transition;
@@ -18,6 +19,9 @@ pub struct Composition {
     left_unique_actions: HashSet<String>,
     right_unique_actions: HashSet<String>,
     common_actions: HashSet<String>,
+    left_outputs: HashSet<String>,
+    right_outputs: HashSet<String>,
+    broadcast_channels: HashSet<String>,
 
     dim: ClockIndex,
 }
@@ -63,6 +67,13 @@ impl Composition {
         // Act_o = Act1_o ∪ Act2_o
         let outputs = left_out.union(&right_out).cloned().collect();
 
+        let broadcast_channels = left
+            .get_decls()
+            .into_iter()
+            .chain(right.get_decls())
+            .flat_map(|decls| decls.broadcast_channels.iter().cloned())
+            .collect();
+
         Ok(Box::new(Composition {
             left,
             right,
@@ -71,9 +82,32 @@ impl Composition {
             left_unique_actions: left_actions.difference(&right_actions).cloned().collect(),
             right_unique_actions: right_actions.difference(&left_actions).cloned().collect(),
             common_actions: left_actions.intersection(&right_actions).cloned().collect(),
+            left_outputs: left_out,
+            right_outputs: right_out,
+            broadcast_channels,
             dim,
         }))
     }
+
+    /// On a broadcast channel the output does not wait for a receiver, so a receiver which cannot take any of
+    /// its `transitions` stays in `location` while the output is taken
+    fn not_receiving(
+        &self,
+        location: &LocationTuple,
+        transitions: &[Transition],
+    ) -> Option<Transition> {
+        let mut zone = location.apply_invariants(OwnedFederation::universe(self.dim));
+        for transition in transitions {
+            zone = zone.subtraction(&transition.guard_zone);
+        }
+
+        if zone.is_empty() {
+            return None;
+        }
+        let mut transition = Transition::new(location, self.dim);
+        transition.guard_zone = zone;
+        Some(transition)
+    }
 }
 
 impl ComposedTransitionSystem for Composition {
@@ -84,8 +118,15 @@ impl ComposedTransitionSystem for Composition {
         let loc_right = location.get_right();
 
         if self.common_actions.contains(action) {
-            let left = self.left.next_transitions(loc_left, action);
-            let right = self.right.next_transitions(loc_right, action);
+            let mut left = self.left.next_transitions(loc_left, action);
+            let mut right = self.right.next_transitions(loc_right, action);
+            if self.broadcast_channels.contains(action) {
+                if self.left_outputs.contains(action) {
+                    right.extend(self.not_receiving(loc_right, &right));
+                } else if self.right_outputs.contains(action) {
+                    left.extend(self.not_receiving(loc_left, &left));
+                }
+            }
             return Transition::combinations(&left, &right, CompositionType::Composition);
         }
 
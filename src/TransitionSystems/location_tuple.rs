@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use edbm::{util::constraints::ClockIndex, zones::OwnedFederation};
 
 use crate::{
@@ -15,19 +17,28 @@ pub enum CompositionType {
     Simple,
 }
 
+/// The values of the integer variables of a component, by name
+pub type IntValuation = BTreeMap<String, i32>;
+
+/// Identifies a [`LocationTuple`] together with the values of its integer variables,
+/// so states in the same location with different values are kept apart
+pub type LocationKey = (LocationID, Vec<i32>);
+
 #[derive(Clone, Debug)]
 pub struct LocationTuple {
     pub id: LocationID,
     /// The invariant for the `Location`
     pub invariant: Option<OwnedFederation>,
     pub loc_type: LocationType,
+    /// The values of the integer variables, only set for simple locations
+    pub ints: IntValuation,
     left: Option<Box<LocationTuple>>,
     right: Option<Box<LocationTuple>>,
 }
 
 impl PartialEq for LocationTuple {
     fn eq(&self, other: &Self) -> bool {
-        self.id == other.id && self.loc_type == other.loc_type
+        self.id == other.id && self.loc_type == other.loc_type && self.same_int_values(other)
     }
 }
 
@@ -52,6 +63,11 @@ impl LocationTuple {
             },
             invariant,
             loc_type: location.get_location_type().clone(),
+            ints: decls
+                .get_ints()
+                .iter()
+                .map(|(name, value)| (name.clone(), *value))
+                .collect(),
             left: None,
             right: None,
         }
@@ -65,6 +81,7 @@ impl LocationTuple {
             id: LocationID::AnyLocation(),
             invariant: None,
             loc_type: LocationType::Any,
+            ints: IntValuation::new(),
             left: None,
             right: None,
         }
@@ -92,6 +109,7 @@ impl LocationTuple {
             id,
             invariant: None,
             loc_type,
+            ints: IntValuation::new(),
             left: Some(Box::new(left.clone())),
             right: Some(Box::new(right.clone())),
         }
@@ -134,6 +152,7 @@ impl LocationTuple {
             id,
             invariant,
             loc_type,
+            ints: IntValuation::new(),
             left: Some(Box::new(left.clone())),
             right: Some(Box::new(right.clone())),
         }
@@ -150,6 +169,48 @@ impl LocationTuple {
         fed
     }
 
+    /// Returns the values of the integer variables of all the simple locations in the tuple, from left to right
+    pub fn get_int_values(&self) -> Vec<i32> {
+        let mut values: Vec<i32> = self.ints.values().copied().collect();
+        if let Some(left) = &self.left {
+            values.extend(left.get_int_values());
+        }
+        if let Some(right) = &self.right {
+            values.extend(right.get_int_values());
+        }
+        values
+    }
+
+    pub fn get_key(&self) -> LocationKey {
+        (self.id.clone(), self.get_int_values())
+    }
+
+    fn same_int_values(&self, other: &Self) -> bool {
+        fn same_children(
+            left: &Option<Box<LocationTuple>>,
+            right: &Option<Box<LocationTuple>>,
+        ) -> bool {
+            match (left, right) {
+                (Some(left), Some(right)) => left.same_int_values(right),
+                _ => true,
+            }
+        }
+
+        self.ints == other.ints
+            && same_children(&self.left, &other.left)
+            && same_children(&self.right, &other.right)
+    }
+
+    /// A unique string for the location and the values of its integer variables
+    pub(super) fn get_unique_string(&self) -> String {
+        let values = self.get_int_values();
+        if values.is_empty() {
+            self.id.get_unique_string()
+        } else {
+            format!("{}{:?}", self.id.get_unique_string(), values)
+        }
+    }
+
     pub fn get_left(&self) -> &LocationTuple {
         if self.is_universal() || self.is_inconsistent() {
             return self;
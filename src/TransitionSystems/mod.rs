@@ -13,7 +13,7 @@ pub use compiled_component::CompiledComponent;
 pub use composition::Composition;
 pub use conjunction::Conjunction;
 pub use location_id::LocationID;
-pub use location_tuple::{CompositionType, LocationTuple};
+pub use location_tuple::{CompositionType, IntValuation, LocationKey, LocationTuple};
 pub use quotient::Quotient;
 pub use transition_id::TransitionID;
 pub use transition_system::{TransitionSystem, TransitionSystemPtr};
@@ -35,7 +35,8 @@ pub enum PrecheckResult {
     NotConsistent(ConsistencyFailure),
 }
 
-pub trait TransitionSystem: DynClone {
+/// Transition systems are shared between the threads of a parallel refinement check
+pub trait TransitionSystem: DynClone + Send + Sync {
     fn get_local_max_bounds(&self, loc: &LocationTuple) -> Bounds;
     fn get_dim(&self) -> ClockIndex;
 
@@ -98,6 +99,7 @@ pub trait TransitionSystem: DynClone {
         if let DeterminismResult::Failure(DeterminismFailure::NotDeterministicFrom(
             location,
             action,
+            _,
         )) = self.is_deterministic()
         {
             warn!("Not deterministic");
@@ -111,15 +113,16 @@ pub trait TransitionSystem: DynClone {
         PrecheckResult::Success
     }
     fn get_combined_decls(&self) -> Declarations {
-        let mut clocks = HashMap::new();
-        let mut ints = HashMap::new();
+        let mut combined = Declarations::empty();
 
         for decl in self.get_decls() {
-            clocks.extend(decl.clocks.clone());
-            ints.extend(decl.ints.clone())
+            combined.clocks.extend(decl.clocks.clone());
+            combined.ints.extend(decl.ints.clone());
+            combined.consts.extend(decl.consts.clone());
+            combined.ranges.extend(decl.ranges.clone());
         }
 
-        Declarations { ints, clocks }
+        combined
     }
 
     fn is_deterministic(&self) -> DeterminismResult;
@@ -130,6 +133,13 @@ pub trait TransitionSystem: DynClone {
 
     fn get_children(&self) -> (&TransitionSystemPtr, &TransitionSystemPtr);
 
+    /// Returns the error met when an edge of the system could not be taken, such as when an update takes an int out of its range.
+    /// The searches of the system leave such an edge out, so their results can not be trusted if there is one
+    fn get_update_error(&self) -> Option<String> {
+        let (left, right) = self.get_children();
+        left.get_update_error().or_else(|| right.get_update_error())
+    }
+
     fn get_composition_type(&self) -> CompositionType;
 
     /// Returns a [`Vec`] of all component names in a given [`TransitionSystem`].
@@ -211,7 +221,7 @@ pub trait TransitionSystem: DynClone {
         //Constructs a node to represent this location and add it to the graph.
         let mut node: ClockAnalysisNode = ClockAnalysisNode {
             invariant_dependencies: HashSet::new(),
-            id: location.id.get_unique_string(),
+            id: location.get_unique_string(),
         };
 
         //Finds clocks used in invariants in this location.
@@ -231,8 +241,8 @@ pub trait TransitionSystem: DynClone {
             let transitions = self.next_transitions_if_available(location, action);
             for transition in transitions {
                 let mut edge = ClockAnalysisEdge {
-                    from: location.id.get_unique_string(),
-                    to: transition.target_locations.id.get_unique_string(),
+                    from: location.get_unique_string(),
+                    to: transition.target_locations.get_unique_string(),
                     guard_dependencies: HashSet::new(),
                     updates: transition.updates,
                     edge_type: action.to_string(),
@@ -253,7 +263,7 @@ pub trait TransitionSystem: DynClone {
                 //represented as a node in the graph.
                 if !graph
                     .nodes
-                    .contains_key(&transition.target_locations.id.get_unique_string())
+                    .contains_key(&transition.target_locations.get_unique_string())
                 {
                     self.find_edges_and_nodes(&transition.target_locations, actions, graph);
                 }
@@ -11,12 +11,13 @@ pub mod logging;
 pub mod tests;
 
 pub use crate::DataReader::component_loader::{
-    ComponentLoader, JsonProjectLoader, ProjectLoader, XmlProjectLoader,
+    ComponentLoader, EngineOptions, JsonProjectLoader, ProjectLoader, XmlProjectLoader,
 };
 pub use crate::DataReader::{parse_queries, xml_parser};
 pub use crate::ModelObjects::queries::Query;
 use crate::ProtobufServer::services::query_request::Settings;
 pub use crate::System::extract_system_rep;
+use crate::System::limits::QueryLimits;
 pub use ModelObjects::component;
 pub use ModelObjects::queries;
 pub use ProtobufServer::start_grpc_server_with_tokio;
@@ -27,6 +28,13 @@ pub const DEFAULT_SETTINGS: Settings = Settings {
     disable_clock_reduction: false,
 };
 
+/// The default options of the engine
+pub const DEFAULT_OPTIONS: EngineOptions = EngineOptions {
+    refinement_threads: 1,
+    search_order: None,
+    limits: QueryLimits::UNLIMITED,
+};
+
 #[macro_use]
 extern crate pest_derive;
 extern crate colored;
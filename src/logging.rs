@@ -1,11 +1,16 @@
-use crate::ProtobufServer::services::query_response::Information;
 use chrono::Local;
 use colored::{ColoredString, Colorize};
-use log::SetLoggerError;
+use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+use std::cell::RefCell;
 use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+/// The least severe level of the records captured for a query
+const CAPTURED_LEVEL: LevelFilter = LevelFilter::Info;
 
 #[cfg(feature = "logging")]
-/// Sets up the logging
+/// Sets up the logging.
+/// The records are printed as filtered by `RUST_LOG`, and captured for a query on the threads running it, see [with_capture]
 pub fn setup_logger() -> Result<(), SetLoggerError> {
     fn colored_level(level: log::Level) -> ColoredString {
         match level {
@@ -17,7 +22,7 @@ pub fn setup_logger() -> Result<(), SetLoggerError> {
         }
     }
 
-    env_logger::Builder::from_env(env_logger::Env::default())
+    let logger = env_logger::Builder::from_env(env_logger::Env::default())
         .format(|buf, record| {
             writeln!(
                 buf,
@@ -29,11 +34,95 @@ pub fn setup_logger() -> Result<(), SetLoggerError> {
                 record.args()
             )
         })
-        .try_init()
+        .build();
+
+    let max_level = logger.filter().max(CAPTURED_LEVEL);
+    log::set_boxed_logger(Box::new(CapturingLogger { logger }))?;
+    log::set_max_level(max_level);
+    Ok(())
+}
+
+#[cfg(feature = "logging")]
+/// Prints the records like env_logger, and captures them on the threads with a [LogCapture]
+struct CapturingLogger {
+    logger: env_logger::Logger,
+}
+
+#[cfg(feature = "logging")]
+impl Log for CapturingLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.logger.enabled(metadata) || (metadata.level() <= CAPTURED_LEVEL && is_capturing())
+    }
+
+    fn log(&self, record: &Record) {
+        capture(record);
+        // The logger only prints the records matching its own filter
+        self.logger.log(record);
+    }
+
+    fn flush(&self) {
+        self.logger.flush();
+    }
+}
+
+/// The log records captured while running a query, with their level.
+/// Clones share the records, so the threads working on the query can capture to the same [LogCapture]
+#[derive(Clone, Debug, Default)]
+pub struct LogCapture {
+    messages: Arc<Mutex<Vec<(Level, String)>>>,
+}
+
+impl LogCapture {
+    pub fn messages(&self) -> Vec<(Level, String)> {
+        self.messages.lock().unwrap().clone()
+    }
+}
+
+thread_local! {
+    static CURRENT_CAPTURE: RefCell<Option<LogCapture>> = RefCell::new(None);
+}
+
+/// Runs `f` with `capture` as the capture of the current thread, where `None` does not capture the records.
+pub fn with_capture<R>(capture: Option<LogCapture>, f: impl FnOnce() -> R) -> R {
+    // Restores the previous capture when dropped, even if `f` panics
+    struct Restore(Option<LogCapture>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            CURRENT_CAPTURE.with(|current| *current.borrow_mut() = self.0.take());
+        }
+    }
+
+    let _restore = Restore(CURRENT_CAPTURE.with(|current| current.replace(capture)));
+    f()
+}
+
+/// Returns the capture of the current thread, so it can be used by the threads a query spawns
+pub fn current_capture() -> Option<LogCapture> {
+    CURRENT_CAPTURE.with(|current| current.borrow().clone())
+}
+
+fn is_capturing() -> bool {
+    CURRENT_CAPTURE.with(|current| current.borrow().is_some())
+}
+
+fn capture(record: &Record) {
+    if record.level() > CAPTURED_LEVEL {
+        return;
+    }
+    CURRENT_CAPTURE.with(|current| {
+        if let Some(capture) = current.borrow().as_ref() {
+            capture
+                .messages
+                .lock()
+                .unwrap()
+                .push((record.level(), record.args().to_string()));
+        }
+    });
 }
 
-// TODO: Implement a logging for informations to both the CLI and gRPC
-/// Gets messages saved for other clients (through gRPC)
-pub fn get_messages() -> Vec<Information> {
-    unimplemented!()
+/// Gets the messages captured on the current thread, which are sent to the client of its query (through gRPC)
+pub fn get_messages() -> Vec<(Level, String)> {
+    current_capture()
+        .map(|capture| capture.messages())
+        .unwrap_or_default()
 }
@@ -2,12 +2,18 @@
 use clap::{load_yaml, App};
 use reveaal::logging::setup_logger;
 
+use reveaal::DataReader::json_writer;
+use reveaal::DataTypes::SearchOrder;
 use reveaal::ProtobufServer::services::query_request::Settings;
+use reveaal::System::limits::QueryLimits;
+use reveaal::System::refine::RefinementResult;
+use reveaal::System::strategy::StrategyResult;
 use reveaal::{
     extract_system_rep, parse_queries, start_grpc_server_with_tokio, xml_parser, ComponentLoader,
-    JsonProjectLoader, ProjectLoader, Query, QueryResult, XmlProjectLoader,
+    EngineOptions, JsonProjectLoader, ProjectLoader, Query, QueryResult, XmlProjectLoader,
 };
 use std::env;
+use std::time::Duration;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     #[cfg(feature = "logging")]
@@ -36,7 +42,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 }
 
 fn start_using_cli(matches: &clap::ArgMatches) {
-    let (mut comp_loader, queries) = parse_args(matches);
+    let (mut comp_loader, mut queries) = parse_args(matches);
+    let save_relation = matches.is_present("save-relation");
+    for query in &mut queries {
+        query.save_relation = save_relation;
+    }
 
     let mut results = vec![];
     for query in &queries {
@@ -55,10 +65,34 @@ fn start_using_cli(matches: &clap::ArgMatches) {
 
     println!("\nQuery results:");
     for index in 0..queries.len() {
-        results[index].print_result(&queries[index].query.as_ref().unwrap().pretty_string())
+        results[index].print_result(&queries[index].query.as_ref().unwrap().pretty_string());
+
+        if let QueryResult::Refinement(RefinementResult::Success(Some(relation))) = &results[index]
+        {
+            let path = format!("relation_{}.json", index);
+            json_writer::relation_to_json_file(&path, relation);
+            println!("Saved relation to {}", path);
+        }
+        if let QueryResult::Strategy(StrategyResult::Success(strategy, _)) = &results[index] {
+            let path = format!("strategy_{}.json", index);
+            json_writer::strategy_to_json_file(&path, strategy);
+            println!("Saved strategy to {}", path);
+        }
     }
 }
 
+/// Parses the value of a limit argument, where 0 means unlimited
+fn parse_limit(matches: &clap::ArgMatches, name: &str) -> Option<u64> {
+    matches
+        .value_of(name)
+        .map(|value| {
+            value
+                .parse()
+                .unwrap_or_else(|_| panic!("Could not parse the input for {}", name))
+        })
+        .filter(|&limit| limit != 0)
+}
+
 fn parse_args(matches: &clap::ArgMatches) -> (Box<dyn ComponentLoader>, Vec<Query>) {
     let folder_path = matches.value_of("folder").unwrap_or("");
     let query = matches.value_of("query").unwrap_or("");
@@ -66,7 +100,28 @@ fn parse_args(matches: &clap::ArgMatches) -> (Box<dyn ComponentLoader>, Vec<Quer
         disable_clock_reduction: matches.is_present("clock-reduction"),
     };
 
-    let project_loader = get_project_loader(folder_path.to_string(), settings);
+    let options = EngineOptions {
+        refinement_threads: matches
+            .value_of("refinement-threads")
+            .unwrap()
+            .parse()
+            .expect("Could not parse the input for the number of refinement threads"),
+        search_order: matches
+            .value_of("search-order")
+            .map(|order| order.parse::<SearchOrder>().unwrap()),
+        limits: QueryLimits {
+            timeout: parse_limit(matches, "timeout").map(Duration::from_secs),
+            max_states: parse_limit(matches, "max-states"),
+            max_memory: parse_limit(matches, "max-memory").map(|megabytes| megabytes * 1024 * 1024),
+        },
+    };
+
+    let mut project_loader = get_project_loader(
+        folder_path.to_string(),
+        settings,
+        matches.is_present("broadcast-channels"),
+    );
+    project_loader.set_options(options);
 
     let queries = if query.is_empty() {
         project_loader.get_queries().clone()
@@ -77,9 +132,15 @@ fn parse_args(matches: &clap::ArgMatches) -> (Box<dyn ComponentLoader>, Vec<Quer
     (project_loader.to_comp_loader(), queries)
 }
 
-fn get_project_loader(project_path: String, settings: Settings) -> Box<dyn ProjectLoader> {
+fn get_project_loader(
+    project_path: String,
+    settings: Settings,
+    broadcast_channels: bool,
+) -> Box<dyn ProjectLoader> {
     if xml_parser::is_xml_project(&project_path) {
         XmlProjectLoader::new(project_path, settings)
+    } else if broadcast_channels {
+        JsonProjectLoader::with_broadcast_channels(project_path, settings)
     } else {
         JsonProjectLoader::new(project_path, settings)
     }
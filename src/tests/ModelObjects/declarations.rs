@@ -0,0 +1,88 @@
+#[cfg(test)]
+mod test {
+    use crate::DataReader::json_reader::json_to_component;
+    use crate::DataReader::parse_edge::{self, EdgeAttribute, Update};
+    use crate::DataReader::serialization::decode_data_declaration;
+    use crate::EdgeEval::updater::compile_updates;
+    use crate::ModelObjects::component::Declarations;
+
+    fn parse_updates(updates: &str) -> Vec<Update> {
+        match parse_edge::parse(updates).unwrap() {
+            EdgeAttribute::Updates(updates) => updates,
+            EdgeAttribute::Guard(_) => panic!("Expected updates"),
+        }
+    }
+
+    fn decode(declarations: &[&str]) -> Declarations {
+        let mut decls = Declarations::empty();
+        for declaration in declarations {
+            decode_data_declaration(declaration, &mut decls).unwrap();
+        }
+        decls
+    }
+
+    #[test]
+    fn constant_is_declared() {
+        let decls = decode(&["const int N = 5"]);
+        assert_eq!(decls.get_int_value("N"), Some(5));
+        assert!(decls.get_ints().is_empty());
+    }
+
+    #[test]
+    fn ranged_int_uses_constant() {
+        let decls = decode(&["const int N = 10", "int[0,N] n = 3, m"]);
+        assert_eq!(decls.get_int_value("n"), Some(3));
+        assert_eq!(decls.get_int_value("m"), Some(0));
+        assert_eq!(decls.get_range("n"), (0, 10));
+        assert_eq!(decls.get_range("m"), (0, 10));
+    }
+
+    #[test]
+    fn bool_is_int_within_zero_and_one() {
+        let decls = decode(&["bool b = true, c"]);
+        assert_eq!(decls.get_int_value("b"), Some(1));
+        assert_eq!(decls.get_int_value("c"), Some(0));
+        assert_eq!(decls.get_range("b"), (0, 1));
+    }
+
+    #[test]
+    fn value_outside_range_fails() {
+        let mut decls = Declarations::empty();
+        let error = decode_data_declaration("int[0,2] n = 3", &mut decls).unwrap_err();
+        assert!(error.contains("outside its range [0,2]"));
+    }
+
+    #[test]
+    fn unknown_value_fails() {
+        let mut decls = Declarations::empty();
+        assert!(decode_data_declaration("int[0,N] n", &mut decls).is_err());
+    }
+
+    #[test]
+    fn component_with_invalid_declaration_is_not_read() {
+        let json =
+            r#"{"name": "C", "declarations": "int[0,2] n = 3;", "locations": [], "edges": []}"#;
+        assert!(json_to_component(json).is_err());
+    }
+
+    #[test]
+    fn updates_are_applied_in_order() {
+        let mut decls = decode(&["int[0,10] n = 1"]);
+        let compiled = compile_updates(&parse_updates("n = n + 1, n = n * 3"), &mut decls);
+        assert!(compiled.unwrap().is_empty());
+        assert_eq!(decls.get_int_value("n"), Some(6));
+    }
+
+    #[test]
+    fn update_outside_range_fails() {
+        let mut decls = decode(&["int[0,2] n = 2"]);
+        let error = compile_updates(&parse_updates("n = n + 1"), &mut decls).unwrap_err();
+        assert!(error.contains("outside its range [0,2]"));
+    }
+
+    #[test]
+    fn update_of_constant_fails() {
+        let mut decls = decode(&["const int N = 2"]);
+        assert!(compile_updates(&parse_updates("N = 3"), &mut decls).is_err());
+    }
+}
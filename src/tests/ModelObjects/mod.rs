@@ -1,2 +1,3 @@
 pub mod arith_expression;
 pub mod bool_expression;
+pub mod declarations;
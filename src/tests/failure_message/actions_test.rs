@@ -21,7 +21,7 @@ mod test {
             component_id: Some(String::from("NonDeterminismCom")),
         };
         if let QueryResult::Determinism(DeterminismResult::Failure(
-            DeterminismFailure::NotDeterministicFrom(actual_location, actual_action),
+            DeterminismFailure::NotDeterministicFrom(actual_location, actual_action, _),
         )) = json_run_query(PATH, "determinism: NonDeterministic1")
         {
             assert_eq!(
@@ -3,8 +3,9 @@
 mod test {
     use crate::System::refine::{RefinementFailure, RefinementResult};
     use crate::{
-        tests::refinement::Helper::json_run_query, System::executable_query::QueryResult,
-        System::local_consistency::DeterminismResult,
+        tests::refinement::Helper::json_run_query,
+        System::executable_query::QueryResult,
+        System::local_consistency::{DeterminismFailure, DeterminismResult},
     };
 
     const PATH: &str = "samples/json/Determinism";
@@ -19,6 +20,27 @@ mod test {
         ));
     }
 
+    #[test]
+    fn determinism_failure_reports_overlapping_transitions_test() {
+        let actual = json_run_query(PATH, "determinism: NonDeterminismCom");
+
+        if let QueryResult::Determinism(DeterminismResult::Failure(
+            DeterminismFailure::NotDeterministicFrom(_, action, overlapping),
+        )) = actual
+        {
+            assert_eq!(action, "1");
+            let mut transitions = vec![
+                overlapping.transitions.0.to_string(),
+                overlapping.transitions.1.to_string(),
+            ];
+            transitions.sort();
+            assert_eq!(transitions, vec!["E0", "E1"]);
+            assert!(!overlapping.overlap.is_empty());
+        } else {
+            panic!("Expected the determinism check to fail with overlapping transitions");
+        }
+    }
+
     #[test]
     fn determinism_failure_in_refinement_test() {
         let actual = json_run_query(PATH, "refinement: NonDeterminismCom <= Component2");
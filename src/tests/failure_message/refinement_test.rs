@@ -3,6 +3,7 @@
 mod test {
     use crate::extract_system_rep::SystemRecipeFailure;
     use crate::System::refine::{RefinementFailure, RefinementResult};
+    use crate::TransitionSystems::TransitionID;
     use crate::{tests::refinement::Helper::json_run_query, System::executable_query::QueryResult};
 
     const PATH: &str = "samples/json/RefinementTests";
@@ -18,6 +19,26 @@ mod test {
         ));
     }
 
+    #[test]
+    fn refinement_failure_trace_test() {
+        let actual = json_run_query(PATH, "refinement: A2 <= B");
+        let state_pair = match &actual {
+            QueryResult::Refinement(RefinementResult::Failure(failure)) => {
+                failure.get_state_pair().unwrap()
+            }
+            _ => panic!("Expected a refinement failure"),
+        };
+
+        let trace = state_pair.get_trace();
+        assert_eq!(trace.len(), 1);
+        assert_eq!(trace[0].action, "in");
+        assert_eq!(trace[0].transition1, TransitionID::Simple("E7".to_string()));
+        assert_eq!(trace[0].transition2, TransitionID::Simple("E6".to_string()));
+        assert_eq!(trace[0].previous.get_locations1().id.to_string(), "L7");
+        assert_eq!(state_pair.get_locations1().id.to_string(), "L8");
+        assert_eq!(state_pair.get_trace_actions(), vec!["in".to_string()]);
+    }
+
     #[test]
     fn empty_transition2s_test() {
         let actual = json_run_query(PATH, "refinement: A <= A2");
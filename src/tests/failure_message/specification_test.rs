@@ -0,0 +1,47 @@
+#[cfg(test)]
+
+mod test {
+    use crate::System::local_consistency::{
+        ConsistencyFailure, DeterminismFailure, SpecificationFailure, SpecificationResult,
+    };
+    use crate::{tests::refinement::Helper::json_run_query, System::executable_query::QueryResult};
+
+    #[test]
+    fn specification_success_test() {
+        let actual = json_run_query("samples/json/EcdarUniversity", "specification: Spec");
+        assert!(matches!(
+            actual,
+            QueryResult::Specification(SpecificationResult::Success)
+        ));
+    }
+
+    #[test]
+    fn specification_not_deterministic_test() {
+        let actual = json_run_query(
+            "samples/json/Determinism",
+            "specification: NonDeterminismCom",
+        );
+        assert!(matches!(
+            actual,
+            QueryResult::Specification(SpecificationResult::Failure(
+                SpecificationFailure::NotDeterministic(DeterminismFailure::NotDeterministicFrom(
+                    ..
+                ))
+            ))
+        ));
+    }
+
+    #[test]
+    fn specification_not_consistent_test() {
+        let actual = json_run_query(
+            "samples/json/ConsistencyTest",
+            "specification: notConsistent",
+        );
+        assert!(matches!(
+            actual,
+            QueryResult::Specification(SpecificationResult::Failure(
+                SpecificationFailure::NotConsistent(ConsistencyFailure::NotConsistentFrom(..))
+            ))
+        ));
+    }
+}
@@ -1,3 +1,4 @@
+pub mod query_cache;
 pub mod send_query;
 pub mod simulation;
 pub mod start_simulation;
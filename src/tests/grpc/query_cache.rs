@@ -0,0 +1,119 @@
+#[cfg(test)]
+mod test {
+    use crate::DataReader::component_loader::ModelCache;
+    use crate::ProtobufServer::services::component::Rep;
+    use crate::ProtobufServer::services::query_request::Settings;
+    use crate::ProtobufServer::services::{Component, ComponentsInfo, QueryRequest, QueryResponse};
+    use crate::ProtobufServer::ConcreteEcdarBackend;
+    use crate::System::limits::{CancellationToken, QueryLimits};
+
+    static ECDAR_UNI: &str = "samples/json/EcdarUniversity";
+
+    #[test]
+    fn identical_query__is_answered_from_cache() {
+        let cache = ModelCache::default();
+
+        let first = send_query(
+            &cache,
+            query_request(1, "refinement: Machine <= Machine", 0),
+        );
+        let second = send_query(&cache, query_request(2, "refinement:Machine<=Machine", 0));
+
+        assert_eq!(cache.response_stats(), (1, 1));
+        assert_eq!(second.query_id, 2);
+        assert_eq!(second.result, first.result);
+    }
+
+    #[test]
+    fn query_with_other_settings__is_not_answered_from_cache() {
+        let cache = ModelCache::default();
+        send_query(
+            &cache,
+            query_request(1, "refinement: Machine <= Machine", 0),
+        );
+
+        let mut request = query_request(2, "refinement: Machine <= Machine", 0);
+        request.settings = Some(Settings {
+            disable_clock_reduction: false,
+            ..crate::tests::TEST_SETTINGS
+        });
+        send_query(&cache, request);
+
+        assert_eq!(cache.response_stats(), (0, 2));
+    }
+
+    #[test]
+    fn changed_model__invalidates_cached_responses() {
+        let cache = ModelCache::default();
+
+        send_query(
+            &cache,
+            query_request(1, "refinement: Machine <= Machine", 0),
+        );
+        send_query(
+            &cache,
+            query_request(2, "refinement: Machine <= Machine", 1),
+        );
+        send_query(
+            &cache,
+            query_request(3, "refinement: Machine <= Machine", 0),
+        );
+
+        assert_eq!(cache.response_stats(), (0, 3));
+    }
+
+    #[test]
+    fn inconclusive_query__is_not_cached() {
+        let cache = ModelCache::default();
+        let limited_token = || {
+            CancellationToken::new(QueryLimits {
+                max_states: Some(1),
+                ..QueryLimits::UNLIMITED
+            })
+        };
+
+        send_query_with_token(
+            &cache,
+            query_request(1, "refinement: Machine <= Machine", 0),
+            limited_token(),
+        );
+        send_query_with_token(
+            &cache,
+            query_request(2, "refinement: Machine <= Machine", 0),
+            limited_token(),
+        );
+
+        assert_eq!(cache.response_stats(), (0, 2));
+    }
+
+    fn send_query(cache: &ModelCache, request: QueryRequest) -> QueryResponse {
+        send_query_with_token(cache, request, CancellationToken::unlimited())
+    }
+
+    fn send_query_with_token(
+        cache: &ModelCache,
+        request: QueryRequest,
+        token: CancellationToken,
+    ) -> QueryResponse {
+        ConcreteEcdarBackend::handle_send_query(request, cache.clone(), token).unwrap()
+    }
+
+    fn query_request(query_id: i32, query: &str, components_hash: u32) -> QueryRequest {
+        let json =
+            std::fs::read_to_string(format!("{}/Components/Machine.json", ECDAR_UNI)).unwrap();
+
+        QueryRequest {
+            user_id: 1,
+            query_id,
+            query: String::from(query),
+            components_info: Some(ComponentsInfo {
+                components: vec![Component {
+                    rep: Some(Rep::Json(json)),
+                }],
+                components_hash,
+            }),
+            ignored_input_outputs: None,
+            settings: Some(crate::tests::TEST_SETTINGS),
+        }
+    }
+}
@@ -2,6 +2,7 @@
 mod refinements {
     use crate::ProtobufServer::services::component::Rep;
     use crate::ProtobufServer::services::ecdar_backend_server::EcdarBackend;
+    use crate::ProtobufServer::services::query_request::IgnoredInputOutputs;
     use crate::ProtobufServer::services::query_response;
     use crate::ProtobufServer::services::Component;
     use crate::ProtobufServer::services::ComponentsInfo;
@@ -11,6 +12,7 @@ mod refinements {
 
     //static CONJUN: &str = "samples/xml/conjun.xml";
     static ECDAR_UNI: &str = "samples/json/EcdarUniversity";
+    static CONSISTENCY_TEST: &str = "samples/json/ConsistencyTest";
 
     #[tokio::test]
     async fn send_self_refinement_query() {
@@ -23,7 +25,10 @@ mod refinements {
         let query_result = query_response.unwrap().into_inner();
         let result = query_result.result.unwrap();
         match result {
-            query_response::Result::Refinement(refine) => assert!(refine.success),
+            query_response::Result::Refinement(refine) => {
+                assert!(refine.success);
+                assert!(!refine.relation.is_empty());
+            }
             _ => panic!(),
         }
     }
@@ -60,9 +65,47 @@ mod refinements {
         }
     }
 
+    #[tokio::test]
+    async fn send_consistency_query_ignoring_actions_is_unimplemented() {
+        let backend = ConcreteEcdarBackend::default();
+        let mut query_request = create_query_request("consistency: Machine");
+        query_request.get_mut().ignored_input_outputs = Some(IgnoredInputOutputs {
+            ignored_inputs: vec![String::from("coin")],
+            ignored_outputs: vec![],
+        });
+
+        let query_response = backend.send_query(query_request).await;
+
+        assert_eq!(
+            query_response.unwrap_err().code(),
+            tonic::Code::Unimplemented
+        );
+    }
+
+    #[cfg(feature = "logging")]
+    #[tokio::test]
+    async fn send_inconsistent_query_responds_with_warnings() {
+        let _ = crate::logging::setup_logger();
+        let backend = ConcreteEcdarBackend::default();
+        let query_request = create_query_request_for(
+            "consistency: notConsistent",
+            &format!("{}/Components/notConsistent.json", CONSISTENCY_TEST),
+        );
+
+        let query_response = backend.send_query(query_request).await;
+
+        let info = query_response.unwrap().into_inner().info;
+        assert!(info
+            .iter()
+            .any(|information| information.message.starts_with("WARN: ")));
+    }
+
     fn create_query_request(query: &str) -> Request<QueryRequest> {
-        let json =
-            std::fs::read_to_string(format!("{}/Components/Machine.json", ECDAR_UNI)).unwrap();
+        create_query_request_for(query, &format!("{}/Components/Machine.json", ECDAR_UNI))
+    }
+
+    fn create_query_request_for(query: &str, component_path: &str) -> Request<QueryRequest> {
+        let json = std::fs::read_to_string(component_path).unwrap();
 
         Request::new(QueryRequest {
             user_id: 0,
@@ -0,0 +1,111 @@
+#[cfg(test)]
+mod test {
+    use std::thread;
+    use std::time::Duration;
+
+    use crate::tests::refinement::Helper::json_run_query_with_options;
+    use crate::DataReader::component_loader::JsonProjectLoader;
+    use crate::DataReader::parse_queries;
+    use crate::EngineOptions;
+    use crate::ModelObjects::queries::Query;
+    use crate::System::executable_query::QueryResult;
+    use crate::System::extract_system_rep::create_executable_query_with_token;
+    use crate::System::limits::{CancellationToken, QueryLimits, StopReason};
+    use crate::System::refine::RefinementResult;
+    use test_case::test_case;
+
+    const PATH: &str = "samples/json/EcdarUniversity";
+    const BIG_PATH: &str = "samples/json/BigRefinement";
+
+    fn with_max_states(max_states: u64) -> QueryLimits {
+        QueryLimits {
+            max_states: Some(max_states),
+            ..QueryLimits::UNLIMITED
+        }
+    }
+
+    #[test_case(BIG_PATH, "refinement: Ref1 <= Ref1", 1; "Refinement")]
+    #[test_case(BIG_PATH, "refinement: Ref1 <= Ref1", 4; "Parallel refinement")]
+    #[test_case(PATH, "reachability: Machine || Researcher -> [L5, L6](); [L4, L9]()", 1; "Reachability")]
+    #[test_case(PATH, "consistency: Administration || Researcher || Machine", 1; "Consistency")]
+    #[test_case(PATH, "determinism: Administration || Researcher || Machine", 1; "Determinism")]
+    #[test_case(PATH, "implementation: Machine", 1; "Implementation")]
+    #[test_case(PATH, "refinement: Researcher <= Spec // Administration // Machine", 1; "Quotient")]
+    fn state_limit_makes_query_inconclusive(path: &str, query: &str, threads: usize) {
+        let options = EngineOptions {
+            refinement_threads: threads,
+            limits: with_max_states(1),
+            ..crate::DEFAULT_OPTIONS
+        };
+        match json_run_query_with_options(path, query, options) {
+            QueryResult::Inconclusive(inconclusive) => {
+                assert_eq!(inconclusive.reason, StopReason::StateLimit(1));
+                assert!(inconclusive.stats.explored_states > 1);
+            }
+            _ => panic!("Expected the query to be inconclusive"),
+        }
+    }
+
+    #[test]
+    fn query_within_limits_is_decided() {
+        let options = EngineOptions {
+            limits: QueryLimits {
+                timeout: Some(Duration::from_secs(60)),
+                max_memory: Some(64 * 1024 * 1024 * 1024),
+                ..with_max_states(1_000_000)
+            },
+            ..crate::DEFAULT_OPTIONS
+        };
+        assert!(matches!(
+            json_run_query_with_options(BIG_PATH, "refinement: Ref1 <= Ref1", options),
+            QueryResult::Refinement(RefinementResult::Success(_))
+        ));
+    }
+
+    #[test]
+    fn cancelled_query_is_inconclusive() {
+        let mut comp_loader =
+            JsonProjectLoader::new(String::from(BIG_PATH), crate::tests::TEST_SETTINGS)
+                .to_comp_loader();
+        let query = parse_queries::parse_to_expression_tree("refinement: Ref1 <= Ref1")
+            .unwrap()
+            .remove(0);
+        let token = CancellationToken::unlimited();
+        let query = create_executable_query_with_token(
+            &Query::new(query, ""),
+            &mut *comp_loader,
+            token.clone(),
+        )
+        .unwrap();
+
+        token.cancel();
+        match query.execute() {
+            QueryResult::Inconclusive(inconclusive) => {
+                assert_eq!(inconclusive.reason, StopReason::Cancelled)
+            }
+            _ => panic!("Expected the query to be inconclusive"),
+        }
+    }
+
+    #[test]
+    fn token_stops_after_timeout() {
+        let token = CancellationToken::new(QueryLimits {
+            timeout: Some(Duration::from_millis(1)),
+            ..QueryLimits::default()
+        });
+        assert!(!token.explore_state());
+        thread::sleep(Duration::from_millis(5));
+        assert!(token.explore_state());
+        assert_eq!(
+            token.stopped().map(|inconclusive| inconclusive.reason),
+            Some(StopReason::Timeout(Duration::from_millis(1)))
+        );
+        // The token stays stopped
+        assert!(token.explore_state());
+    }
+
+    #[test]
+    fn queries_are_unlimited_by_default() {
+        assert_eq!(crate::DEFAULT_OPTIONS.limits, QueryLimits::default());
+    }
+}
@@ -0,0 +1,46 @@
+#[cfg(test)]
+#[cfg(feature = "logging")]
+mod test {
+    use std::thread;
+
+    use crate::logging::{self, setup_logger, LogCapture};
+    use log::{debug, error, info, warn, Level};
+
+    #[test]
+    fn captured_records_keep_their_severity() {
+        let _ = setup_logger();
+        let capture = LogCapture::default();
+
+        logging::with_capture(Some(capture.clone()), || {
+            error!("An error");
+            warn!("A warning");
+            info!("Some information");
+            debug!("A debug message");
+        });
+
+        assert_eq!(
+            capture.messages(),
+            vec![
+                (Level::Error, "An error".to_string()),
+                (Level::Warn, "A warning".to_string()),
+                (Level::Info, "Some information".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn records_are_only_captured_on_the_capturing_thread() {
+        let _ = setup_logger();
+        let capture = LogCapture::default();
+
+        logging::with_capture(Some(capture.clone()), || {
+            thread::spawn(|| warn!("A warning on another thread"))
+                .join()
+                .unwrap();
+        });
+        warn!("A warning outside the capture");
+
+        assert!(capture.messages().is_empty());
+        assert!(logging::get_messages().is_empty());
+    }
+}
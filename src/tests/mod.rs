@@ -6,10 +6,13 @@ pub mod Simulation;
 pub mod edge_ids;
 pub mod failure_message;
 pub mod grpc;
+pub mod limits;
+pub mod logging;
 pub mod reachability;
 pub mod refinement;
 pub mod sample;
 pub mod save_component;
+pub mod strategy;
 pub mod system_recipe;
 
 /// The default settings for Testing
@@ -20,6 +20,12 @@ mod reachability_grammar_test {
     #[test_case("reachability: Hi -> [_](); [_]()"; "no location or clock values specified")] // 1 location argument provided
     #[test_case("reachability: Hi -> [L2, L3](y<2)"; "No start state is given")]
     #[test_case("reachability: Hi -> [_]()"; "No start state is given, valid grammar")]
+    #[test_case("reachability: Hi -> [L1](); [L2]() || [L3](y<2)"; "Several end states")]
+    #[test_case("reachability: Hi -> [L2]() || [L3]()"; "Several end states and no start state")]
+    #[test_case("reachability: Hi || M2 -> [L1, L3](); [!Err, _]()"; "Negated location in end state")]
+    #[test_case("reachability: Hi || M2 -> [L1, L3](); [L2, L3](Hi.y<2)"; "Clock prefixed by its component")]
+    #[test_case("fastest-reachability: Hi -> [L1](); [L2]()"; "Fastest path")]
+    #[test_case("fastest-reachability: Hi -> [L2]() || [L3]()"; "Fastest path to several end states")]
     fn query_grammar_test_valid_queries(parser_input: &str) {
         // This tests that the grammar accepts this string, and does not panic:
         assert!(matches!(
@@ -0,0 +1,48 @@
+#[cfg(test)]
+mod lock_tests {
+    use crate::tests::refinement::Helper::json_run_query;
+    use crate::QueryResult;
+    use test_case::test_case;
+
+    const PATH: &str = "samples/json/Locks";
+    const PATH2: &str = "samples/json/EcdarUniversity";
+    const PATH3: &str = "samples/json/IntVariables";
+
+    #[test_case(PATH, "deadlock: Deadlock", Some("L0"), &[]; "Deadlock when the invariant runs out after the output")]
+    #[test_case(PATH, "timelock: Deadlock", Some("L0"), &[]; "A deadlock is also a timelock")]
+    #[test_case(PATH, "deadlock: Timelock", None, &[]; "Strict invariant is not a deadlock")]
+    #[test_case(PATH, "timelock: Timelock", Some("L1"), &["E0"]; "Timelock behind an output")]
+    #[test_case(PATH, "timelock: PartialTimelock", Some("L1"), &["E0"]; "Timelock in the disjunct of the invariant which cannot delay forever")]
+    #[test_case(PATH, "deadlock: LockFree", None, &[]; "No deadlock when the output is enabled at the bound")]
+    #[test_case(PATH, "timelock: LockFree", None, &[]; "No timelock when the output is enabled before the bound")]
+    #[test_case(PATH2, "deadlock: Machine", None, &[]; "Machine has no deadlock")]
+    #[test_case(PATH2, "timelock: Machine || Researcher", None, &[]; "Machine || Researcher has no timelock")]
+    #[test_case(PATH3, "timelock: Timer", None, &[]; "No timelock in Timer as the int bounding the invariant grows")]
+    fn lock_check_finds_first_locked_state(
+        path: &str,
+        query: &str,
+        expected_location: Option<&str>,
+        expected_path: &[&str],
+    ) {
+        match json_run_query(path, query) {
+            QueryResult::Lock(result) => {
+                assert_eq!(result.is_lock_free(), expected_location.is_none());
+                if let Some((state, path)) = result.locked {
+                    assert!(!state.zone_ref().is_empty());
+                    assert_eq!(
+                        Some(state.get_location().id.to_string()),
+                        expected_location.map(str::to_string)
+                    );
+                    let edges: Vec<String> = path
+                        .path
+                        .unwrap()
+                        .iter()
+                        .map(|transition| transition.id.to_string())
+                        .collect();
+                    assert_eq!(edges, expected_path);
+                }
+            }
+            _ => panic!("Inconsistent query result, expected Lock"),
+        }
+    }
+}
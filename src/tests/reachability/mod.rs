@@ -3,9 +3,13 @@ pub mod get_leaves_tests;
 pub mod grammar;
 pub mod helper_functions;
 pub mod location_validation;
+pub mod lock_tests;
 pub mod parse_partial_state;
 pub mod parser_output_datatypes;
 pub mod parser_restrictions;
 pub mod partial_state;
 pub mod search_algorithm_test;
+pub mod search_order_tests;
 pub mod split_component_tests;
+pub mod tctl_tests;
+pub mod zeno_tests;
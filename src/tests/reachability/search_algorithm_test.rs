@@ -1,12 +1,16 @@
 #[cfg(test)]
 mod reachability_search_algorithm_test {
     use crate::component::Transition;
-    use crate::tests::refinement::Helper::json_run_query;
+    use crate::tests::refinement::Helper::{
+        json_run_query, json_run_query_with_broadcast_channels,
+    };
     use crate::QueryResult;
     use test_case::test_case;
 
     const PATH: &str = "samples/json/EcdarUniversity";
     const PATH2: &str = "samples/json/AutomatonTestReachability";
+    const PATH3: &str = "samples/json/IntVariables";
+    const PATH4: &str = "samples/json/Broadcast";
 
     #[test_case(PATH, "reachability: Machine -> [L5](y<6); [L4](y<=6)", true; "Existing states and with right clocks")]
     #[test_case(PATH, "reachability: Machine -> [L5](); [L4](y>7)", false; "Exisiting locations but not possible with the clocks")]
@@ -49,6 +53,9 @@ mod reachability_search_algorithm_test {
     #[test_case(PATH2, "reachability: Component8 -> [L20](); [L22]()", true; "Reset clock to reach end state")]
     #[test_case(PATH2, "reachability: Component7 -> [L16](); [L19](y<2)", false; "Unreachable due to second clock")]
     #[test_case(PATH2, "reachability: Component3 && Component3 -> [L6, L6](); [L7, L7]()", true; "Simple conjunction")]
+    #[test_case(PATH3, "reachability: Timer -> [L0](); [L0](x>3)", true; "Reachable as the int in the invariant grows")]
+    #[test_case(PATH3, "reachability: Timer -> [L0](); [L0](x>4)", false; "Unreachable due to the int bounding the invariant")]
+    #[test_case(PATH3, "reachability: Counter -> [L0](); [L1]()", true; "Reachable after the int guard is satisfied")]
     fn search_algorithm_returns_result(path: &str, query: &str, expected: bool) {
         match json_run_query(path, query) {
             QueryResult::Reachability(path) => assert_eq!(path.was_reachable, expected),
@@ -56,6 +63,24 @@ mod reachability_search_algorithm_test {
         }
     }
 
+    #[test_case("reachability: Sender || Receiver -> [L0, L0](); [L1, L0]()", true, false; "Broadcast output is taken without a receiver")]
+    #[test_case("reachability: Sender || Receiver -> [L0, L0](); [L1, L1]()", false, false; "Broadcast receiver cannot take the input as its guard is not satisfied")]
+    fn search_algorithm_returns_result_broadcast(
+        query: &str,
+        expected: bool,
+        expected_without: bool,
+    ) {
+        match json_run_query_with_broadcast_channels(PATH4, query) {
+            QueryResult::Reachability(path) => assert_eq!(path.was_reachable, expected),
+            _ => panic!("Inconsistent query result, expected Reachability"),
+        }
+        // Without opting in, the broadcast channels synchronise like any other channel
+        match json_run_query(PATH4, query) {
+            QueryResult::Reachability(path) => assert_eq!(path.was_reachable, expected_without),
+            _ => panic!("Inconsistent query result, expected Reachability"),
+        }
+    }
+
     #[test_case(PATH2, "reachability: Component1 -> [L0](); [L2]()", Vec::from(["E3", "E2"]); "Path in Component1 from L0 to L2")]
     #[test_case(PATH2, "reachability: Component3 -> [L6](); [L7]()", Vec::from(["E5"]); "Path in Component3 from L6 to L7")]
     #[test_case(PATH2, "reachability: Component3 -> [L7](); [L8]()", Vec::from(["E6"]); "Path in Component3 from L7 to L8")]
@@ -67,6 +92,7 @@ mod reachability_search_algorithm_test {
     #[test_case(PATH2, "reachability: Component9 -> [L23](x<5); [L26]()", Vec::from(["E16", "E19"]); "Path in Component9 from L23 x lt 5 to L26")]
     #[test_case(PATH2, "reachability: Component3 && Component3 -> [L6, L6](); [L7, L7]()", Vec::from(["E5&&E5"]); "Path in Component3 && Component3 from L6 && L6 to L7 && L7")]
     #[test_case(PATH, "reachability: Researcher && Researcher -> [U0, U0](); [_, U0]()", Vec::from([]); "Path in Researcher && Researcher from universal state to partial universal state")]
+    #[test_case(PATH3, "reachability: Timer -> [L0](); [L1]()", Vec::from(["E0", "E0", "E1"]); "Path in Timer updating an int twice")]
     fn path_gen_test_correct_path(folder_path: &str, query: &str, expected_path: Vec<&str>) {
         match json_run_query(folder_path, query) {
             QueryResult::Reachability(actual_path) => {
@@ -88,4 +114,70 @@ mod reachability_search_algorithm_test {
             _ => panic!("Inconsistent query result, expected Reachability"),
         }
     }
+
+    #[test_case(PATH, "reachability: Machine -> [L5](); [L4](y>=4)", "4", "4"; "Delay until the lower bound of the end state")]
+    #[test_case(PATH, "reachability: Machine -> [L5](); [L4](y>4 && y<5)", "9/2", "9/2"; "Delay into the open interval of the end state")]
+    #[test_case(PATH, "reachability: Machine -> [L4](y<=6); [L5](y>=4)", "4", "4"; "Delay until the guard of the output is satisfied")]
+    fn concrete_trace_has_earliest_delays(
+        folder_path: &str,
+        query: &str,
+        expected_y: &str,
+        expected_duration: &str,
+    ) {
+        match json_run_query(folder_path, query) {
+            QueryResult::Reachability(path) => {
+                let trace = path.concrete_trace.expect("Expected a concrete trace");
+                assert_eq!(trace.final_value("y").unwrap().to_string(), expected_y);
+                assert_eq!(trace.duration().to_string(), expected_duration);
+                assert_eq!(trace.steps.len(), path.path.unwrap().len());
+            }
+            _ => panic!("Inconsistent query result, expected Reachability"),
+        }
+    }
+
+    #[test_case(PATH, "reachability: Machine -> [L5](); [L4](y>7) || [L4](y>=4)", true; "One of two targets is reachable")]
+    #[test_case(PATH, "reachability: Machine -> [L5](); [L4](y>7) || [L4](y>8) || [L4]()", true; "The last of three targets is reachable")]
+    #[test_case(PATH, "reachability: Machine -> [L5](); [L4](y>7) || [L4](y>8)", false; "No target is reachable")]
+    #[test_case(PATH, "reachability: Machine -> [L5](); [!L5]()", true; "Any location except the initial one")]
+    #[test_case(PATH, "reachability: Researcher -> [U0](); [!U0]()", false; "The universal location cannot be left")]
+    #[test_case(PATH, "reachability: Machine || Researcher -> [L5, L6](); [!L5, !L6]()", true; "Negated locations in both components")]
+    #[test_case(PATH, "reachability: Machine -> [L5](); [L4](Machine.y>=4)", true; "Clock referred to by its component")]
+    #[test_case(PATH, "reachability: Machine -> [L5](); [L4](Machine.y>7)", false; "Clock referred to by its component beyond the invariant")]
+    fn search_algorithm_returns_result_for_targets(path: &str, query: &str, expected: bool) {
+        match json_run_query(path, query) {
+            QueryResult::Reachability(path) => {
+                assert_eq!(path.was_reachable, expected);
+                assert_eq!(path.reached_target.is_some(), expected);
+            }
+            _ => panic!("Inconsistent query result, expected Reachability"),
+        }
+    }
+
+    #[test]
+    fn search_algorithm_reports_reached_target() {
+        match json_run_query(PATH, "reachability: Machine -> [L5](); [L4](y>7) || [L5]()") {
+            QueryResult::Reachability(path) => {
+                assert!(path.reached_target.unwrap().starts_with("[L5]"));
+            }
+            _ => panic!("Inconsistent query result, expected Reachability"),
+        }
+    }
+
+    #[test_case(PATH, "fastest-reachability: Machine -> [L5](); [L4]()", Some("0"); "Input can be taken immediately")]
+    #[test_case(PATH, "fastest-reachability: Machine -> [L5](); [L4](y>=4)", Some("4"); "Wait for the clock after the reset")]
+    #[test_case(PATH, "fastest-reachability: Machine || Researcher -> [_, L9]()", Some("4"); "Coffee is dispensed after 4")]
+    #[test_case(PATH, "fastest-reachability: Machine || Researcher -> [_, L7](Researcher.x>=3)", Some("3"); "Tea is fastest after the coin")]
+    #[test_case(PATH, "fastest-reachability: Machine -> [L5](); [L4](y>7)", None; "Unreachable due to the invariant")]
+    fn fastest_search_returns_minimal_time(path: &str, query: &str, expected: Option<&str>) {
+        match json_run_query(path, query) {
+            QueryResult::Reachability(path) => {
+                assert_eq!(path.was_reachable, expected.is_some());
+                assert_eq!(
+                    path.minimal_time.map(|time| time.to_string()).as_deref(),
+                    expected
+                );
+            }
+            _ => panic!("Inconsistent query result, expected Reachability"),
+        }
+    }
 }
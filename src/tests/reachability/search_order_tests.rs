@@ -0,0 +1,41 @@
+#[cfg(test)]
+mod reachability_search_order_tests {
+    use crate::tests::refinement::Helper::json_run_query_with_options;
+    use crate::DataTypes::SearchOrder;
+    use crate::EngineOptions;
+    use crate::QueryResult;
+    use test_case::test_case;
+
+    const PATH: &str = "samples/json/EcdarUniversity";
+    const PATH2: &str = "samples/json/AutomatonTestReachability";
+
+    #[test_case(PATH, "reachability: Machine -> [L5](y<6); [L4](y<=6)", true; "Existing states and with right clocks")]
+    #[test_case(PATH, "reachability: Machine -> [L5](); [L4](y>7)", false; "Exisiting locations but not possible with the clocks")]
+    #[test_case(PATH, "reachability: Machine || Researcher -> [L5, L6](); [L4, L9]()", true; "Composition with existing locations")]
+    #[test_case(PATH, "reachability: Machine || Researcher -> [L5, U0](); [L5, L7]()", false; "No valid path from the two states")]
+    #[test_case(PATH, "reachability: Machine || Researcher -> [L5, L6](); [_, L9]()", true; "Partial end state")]
+    #[test_case(PATH2, "reachability: Component3 -> [L6](); [L8]()", false; "False due to guards on the last transition")]
+    #[test_case(PATH2, "reachability: Component6 -> [L13](); [L15]()", true; "Reachable due to the updates at L14")]
+    #[test_case(PATH2, "reachability: Component7 -> [L16](); [L19]()", true; "Overwrite state of location once to reach end state")]
+    fn reachability_is_independent_of_search_order(path: &str, query: &str, expected: bool) {
+        for order in [
+            SearchOrder::BreadthFirst,
+            SearchOrder::DepthFirst,
+            SearchOrder::RandomDepthFirst,
+            SearchOrder::ClosestToFailure,
+        ] {
+            let options = EngineOptions {
+                search_order: Some(order),
+                ..crate::DEFAULT_OPTIONS
+            };
+            match json_run_query_with_options(path, query, options) {
+                QueryResult::Reachability(path) => assert_eq!(
+                    path.was_reachable, expected,
+                    "Result differs with {} order",
+                    order
+                ),
+                _ => panic!("Inconsistent query result, expected Reachability"),
+            }
+        }
+    }
+}
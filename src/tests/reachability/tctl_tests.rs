@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod tctl_tests {
+    use crate::tests::refinement::Helper::json_run_query;
+    use crate::QueryResult;
+    use test_case::test_case;
+
+    const PATH: &str = "samples/json/EcdarUniversity";
+    const DELAY_PATH: &str = "samples/json/TctlDelay";
+
+    #[test_case("E<> Machine.L4", true; "Possibly reachable location")]
+    #[test_case("E<> Machine.L4 && Machine.y > 6", false; "Possibly not reachable because of invariant")]
+    #[test_case("E<> Machine.L5 && Machine.y > 6", true; "Possibly reachable with clock constraint")]
+    #[test_case("A[] Machine.y <= 6", false; "Invariantly violated by delay")]
+    #[test_case("A[] not (Machine.L4 && Machine.y > 6)", true; "Invariantly holds because of invariant")]
+    #[test_case("E[] Machine.L5", true; "Eventually always by delaying in the initial location")]
+    #[test_case("A<> Machine.L4", false; "Potentially violated by delaying in the initial location")]
+    #[test_case("A<> Researcher.L6", true; "Potentially holds in the initial location")]
+    #[test_case("E<> Machine.L4 && Researcher.L9", true; "Possibly in the composition of the referenced components")]
+    #[test_case("Machine || Researcher: E<> L4 && L9", true; "Possibly with explicit system")]
+    #[test_case("Machine: A[] L4 || L5", true; "Invariantly with explicit system")]
+    #[test_case("(Machine || Researcher): E<> Machine.L4 && Researcher.U0", true; "Possibly with parenthesized system")]
+    fn tctl_query_returns_result_university(query: &str, expected: bool) {
+        match json_run_query(PATH, query) {
+            QueryResult::Tctl(result) => assert_eq!(result.satisfied, expected),
+            _ => panic!("Inconsistent query result, expected TCTL result"),
+        }
+    }
+
+    #[test_case("E[] Waiting.y <= 6", false; "Eventually always violated by delaying without invariant")]
+    #[test_case("A<> Waiting.y > 6", true; "Potentially holds by delaying without invariant")]
+    #[test_case("E[] Bounded.y <= 6", true; "Eventually always holds until the invariant stops time")]
+    #[test_case("A<> Bounded.y > 6", false; "Potentially violated as the invariant stops time")]
+    fn tctl_query_returns_result_delay(query: &str, expected: bool) {
+        match json_run_query(DELAY_PATH, query) {
+            QueryResult::Tctl(result) => assert_eq!(result.satisfied, expected),
+            _ => panic!("Inconsistent query result, expected TCTL result"),
+        }
+    }
+
+    #[test_case("E<> Machine.L4", 1; "Witness of possibly")]
+    #[test_case("A[] Machine.L5", 1; "Counterexample of invariantly")]
+    #[test_case("E<> Machine.L4 && Researcher.L9", 3; "Witness in composition")]
+    fn tctl_query_returns_path(query: &str, expected_length: usize) {
+        match json_run_query(PATH, query) {
+            QueryResult::Tctl(result) => {
+                let path = result.path.unwrap().path.unwrap();
+                assert_eq!(path.len(), expected_length);
+            }
+            _ => panic!("Inconsistent query result, expected TCTL result"),
+        }
+    }
+
+    #[test]
+    fn tctl_query_without_path_when_satisfied_invariantly() {
+        match json_run_query(PATH, "A[] Machine.L4 || Machine.L5") {
+            QueryResult::Tctl(result) => assert!(result.path.is_none()),
+            _ => panic!("Inconsistent query result, expected TCTL result"),
+        }
+    }
+}
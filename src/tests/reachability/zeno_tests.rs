@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod zeno_tests {
+    use crate::tests::refinement::Helper::json_run_query;
+    use crate::QueryResult;
+    use crate::System::zeno::NonZenoResult;
+    use test_case::test_case;
+
+    const PATH: &str = "samples/json/EcdarUniversity";
+    const PATH2: &str = "samples/json/Locks";
+
+    #[test_case(PATH, "non-zeno: Researcher"; "Researcher only outputs after its clock has been reset and grown")]
+    #[test_case(PATH2, "non-zeno: LockFree"; "The cycle resets x and bounds it from below")]
+    #[test_case(PATH2, "non-zeno: Deadlock"; "No cycles")]
+    fn non_zeno_check_succeeds(path: &str, query: &str) {
+        match json_run_query(path, query) {
+            QueryResult::NonZeno(NonZenoResult::Success) => {}
+            QueryResult::NonZeno(NonZenoResult::Failure(cycle)) => {
+                panic!("Expected no Zeno cycle, got {}", cycle)
+            }
+            _ => panic!("Inconsistent query result, expected NonZeno"),
+        }
+    }
+
+    #[test]
+    fn machine_tea_loop_is_zeno() {
+        // Once y>=2, tea can be output infinitely often without any time passing
+        match json_run_query(PATH, "non-zeno: Machine") {
+            QueryResult::NonZeno(NonZenoResult::Failure(cycle)) => {
+                assert_eq!(cycle.location.to_string(), "L5");
+                let transitions: Vec<String> =
+                    cycle.transitions.iter().map(|id| id.to_string()).collect();
+                assert_eq!(transitions, vec!["E29"]);
+            }
+            _ => panic!("Expected a Zeno cycle in Machine"),
+        }
+    }
+
+    #[test]
+    fn strict_lower_bound_does_not_prevent_zeno() {
+        // x is reset on the cycle, but x>0 lets every round take less time than the one before
+        match json_run_query(PATH2, "non-zeno: StrictZeno") {
+            QueryResult::NonZeno(NonZenoResult::Failure(cycle)) => {
+                assert_eq!(cycle.location.to_string(), "L0");
+                let transitions: Vec<String> =
+                    cycle.transitions.iter().map(|id| id.to_string()).collect();
+                assert_eq!(transitions, vec!["E0", "E1"]);
+            }
+            _ => panic!("Expected a Zeno cycle in StrictZeno"),
+        }
+    }
+
+    #[test]
+    fn zeno_cycle_is_found_in_composition() {
+        match json_run_query(PATH, "non-zeno: Machine || Researcher") {
+            QueryResult::NonZeno(NonZenoResult::Failure(cycle)) => {
+                assert!(!cycle.transitions.is_empty())
+            }
+            _ => panic!("Expected a Zeno cycle in Machine || Researcher"),
+        }
+    }
+}
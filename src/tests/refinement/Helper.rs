@@ -1,5 +1,5 @@
 use crate::logging::setup_logger;
-use crate::DataReader::component_loader::{JsonProjectLoader, XmlProjectLoader};
+use crate::DataReader::component_loader::{EngineOptions, JsonProjectLoader, XmlProjectLoader};
 use crate::DataReader::parse_queries;
 use crate::ModelObjects::queries::Query;
 use crate::System::executable_query::QueryResult;
@@ -14,7 +14,7 @@ fn try_setup_logging() {
 pub fn xml_refinement_check(PATH: &str, QUERY: &str) -> bool {
     try_setup_logging();
     match xml_run_query(PATH, QUERY) {
-        QueryResult::Refinement(RefinementResult::Success) => true,
+        QueryResult::Refinement(RefinementResult::Success(_)) => true,
         QueryResult::Refinement(RefinementResult::Failure(_)) => false,
         QueryResult::Error(err) => panic!("{}", err),
         _ => panic!("Not a refinement check"),
@@ -25,7 +25,18 @@ pub fn json_refinement_check(PATH: &str, QUERY: &str) -> bool {
     try_setup_logging();
 
     match json_run_query(PATH, QUERY) {
-        QueryResult::Refinement(RefinementResult::Success) => true,
+        QueryResult::Refinement(RefinementResult::Success(_)) => true,
+        QueryResult::Refinement(RefinementResult::Failure(_)) => false,
+        QueryResult::Error(err) => panic!("{}", err),
+        _ => panic!("Not a refinement check"),
+    }
+}
+
+pub fn json_refinement_check_with_options(PATH: &str, QUERY: &str, options: EngineOptions) -> bool {
+    try_setup_logging();
+
+    match json_run_query_with_options(PATH, QUERY, options) {
+        QueryResult::Refinement(RefinementResult::Success(_)) => true,
         QueryResult::Refinement(RefinementResult::Failure(_)) => false,
         QueryResult::Error(err) => panic!("{}", err),
         _ => panic!("Not a refinement check"),
@@ -38,10 +49,7 @@ pub fn xml_run_query(PATH: &str, QUERY: &str) -> QueryResult {
     let query = parse_queries::parse_to_expression_tree(QUERY)
         .unwrap()
         .remove(0);
-    let q = Query {
-        query: Option::from(query),
-        comment: "".to_string(),
-    };
+    let q = Query::new(query, "");
 
     let mut comp_loader = project_loader.to_comp_loader();
     let query = create_executable_query(&q, &mut *comp_loader).unwrap();
@@ -50,17 +58,47 @@ pub fn xml_run_query(PATH: &str, QUERY: &str) -> QueryResult {
 }
 
 pub fn json_run_query(PATH: &str, QUERY: &str) -> QueryResult {
-    let project_loader = JsonProjectLoader::new(String::from(PATH), crate::tests::TEST_SETTINGS);
     let query = parse_queries::parse_to_expression_tree(QUERY)
         .unwrap()
         .remove(0);
-    let q = Query {
-        query: Option::from(query),
-        comment: "".to_string(),
-    };
+    json_run_parsed_query(PATH, &Query::new(query, ""))
+}
+
+pub fn json_run_query_with_options(PATH: &str, QUERY: &str, options: EngineOptions) -> QueryResult {
+    let query = parse_queries::parse_to_expression_tree(QUERY)
+        .unwrap()
+        .remove(0);
+    json_run_parsed_query_with_options(PATH, &Query::new(query, ""), options)
+}
+
+pub fn json_run_parsed_query(PATH: &str, query: &Query) -> QueryResult {
+    json_run_parsed_query_with_options(PATH, query, crate::DEFAULT_OPTIONS)
+}
+
+pub fn json_run_parsed_query_with_options(
+    PATH: &str,
+    query: &Query,
+    options: EngineOptions,
+) -> QueryResult {
+    let mut project_loader =
+        JsonProjectLoader::new(String::from(PATH), crate::tests::TEST_SETTINGS);
+    project_loader.set_options(options);
 
     let mut comp_loader = project_loader.to_comp_loader();
-    let query = create_executable_query(&q, &mut *comp_loader).unwrap();
+    let query = create_executable_query(query, &mut *comp_loader).unwrap();
+
+    query.execute()
+}
+
+pub fn json_run_query_with_broadcast_channels(PATH: &str, QUERY: &str) -> QueryResult {
+    let query = parse_queries::parse_to_expression_tree(QUERY)
+        .unwrap()
+        .remove(0);
+    let project_loader =
+        JsonProjectLoader::with_broadcast_channels(String::from(PATH), crate::tests::TEST_SETTINGS);
+
+    let mut comp_loader = project_loader.to_comp_loader();
+    let query = create_executable_query(&Query::new(query, ""), &mut *comp_loader).unwrap();
 
     query.execute()
 }
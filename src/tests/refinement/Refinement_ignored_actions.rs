@@ -0,0 +1,92 @@
+#[cfg(test)]
+mod test {
+    use crate::tests::refinement::Helper::json_run_parsed_query;
+    use crate::DataReader::component_loader::JsonProjectLoader;
+    use crate::DataReader::parse_queries;
+    use crate::ModelObjects::queries::Query;
+    use crate::System::executable_query::QueryResult;
+    use crate::System::extract_system_rep::create_executable_query;
+    use crate::System::refine::RefinementResult;
+    use std::collections::HashSet;
+
+    static PATH: &str = "samples/json/EcdarUniversity";
+    static HIDDEN_PATH: &str = "samples/json/HiddenActions";
+
+    fn refinement_ignoring(path: &str, query: &str, inputs: &[&str], outputs: &[&str]) -> bool {
+        let mut query = parse_queries::parse_to_query(query).remove(0);
+        let to_strings =
+            |actions: &[&str]| actions.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+        query.ignore_actions(&to_strings(inputs), &to_strings(outputs));
+
+        match json_run_parsed_query(path, &query) {
+            QueryResult::Refinement(RefinementResult::Success(_)) => true,
+            QueryResult::Refinement(RefinementResult::Failure(_)) => false,
+            QueryResult::Error(err) => panic!("{}", err),
+            _ => panic!("Not a refinement check"),
+        }
+    }
+
+    #[test]
+    fn BeeperNotRefinesLateBeeper() {
+        assert!(!refinement_ignoring(
+            HIDDEN_PATH,
+            "refinement: Beeper <= LateBeeper",
+            &[],
+            &[]
+        ));
+    }
+
+    #[test]
+    fn BeeperRefinesLateBeeperIgnoringBeep() {
+        // Beeper may beep before LateBeeper can, but an ignored beep is not matched by LateBeeper
+        assert!(refinement_ignoring(
+            HIDDEN_PATH,
+            "refinement: Beeper <= LateBeeper",
+            &[],
+            &["beep"]
+        ));
+    }
+
+    #[test]
+    fn MachineNotRefinesSpecIgnoringSomeActions() {
+        assert!(!refinement_ignoring(
+            PATH,
+            "refinement: Machine <= Spec",
+            &["coin", "grant"],
+            &["cof", "tea"]
+        ));
+    }
+
+    #[test]
+    fn ConsistencyIgnoringActionsIsRejected() {
+        let mut query = parse_queries::parse_to_query("consistency: Machine").remove(0);
+        query.ignore_actions(&["coin".to_string()], &[]);
+
+        let mut comp_loader =
+            JsonProjectLoader::new(PATH.to_string(), crate::tests::TEST_SETTINGS).to_comp_loader();
+
+        assert!(create_executable_query(&query, &mut *comp_loader).is_err());
+    }
+
+    #[test]
+    fn IgnoredActionsAreReadFromQueryJson() {
+        let query: Query = serde_json::from_str(
+            r#"{
+                "query": "refinement: Machine <= Spec",
+                "comment": "",
+                "ignoredInputs": {"coin": true, "grant": false},
+                "ignoredOutputs": {"tea": true}
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            query.get_ignored_inputs(),
+            HashSet::from(["coin".to_string()])
+        );
+        assert_eq!(
+            query.get_ignored_outputs(),
+            HashSet::from(["tea".to_string()])
+        );
+    }
+}
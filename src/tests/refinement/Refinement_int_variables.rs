@@ -0,0 +1,112 @@
+#[cfg(test)]
+mod test {
+    use crate::extract_system_rep::get_system_recipe;
+    use crate::tests::refinement::Helper::{json_refinement_check, json_run_query};
+    use crate::JsonProjectLoader;
+    use crate::ModelObjects::representations::QueryExpression;
+    use crate::QueryResult;
+    use crate::System::local_consistency::{ConsistencyFailure, ConsistencyResult};
+    use crate::System::refine::{self, RefinementFailure, RefinementResult};
+
+    static PATH: &str = "samples/json/IntVariables";
+    static GLOBAL_PATH: &str = "samples/json/GlobalVariables";
+
+    #[test]
+    fn CounterRefinesCounterSpec() {
+        assert!(json_refinement_check(
+            PATH,
+            "refinement: Counter <= CounterSpec"
+        ));
+    }
+
+    #[test]
+    fn CounterSpecRefinesCounter() {
+        assert!(json_refinement_check(
+            PATH,
+            "refinement: CounterSpec <= Counter"
+        ));
+    }
+
+    #[test]
+    fn WrongCounterNotRefinesCounterSpec() {
+        assert!(!json_refinement_check(
+            PATH,
+            "refinement: WrongCounter <= CounterSpec"
+        ));
+    }
+
+    #[test]
+    fn CounterComposedWithTimerRefinesCounterSpecComposedWithTimer() {
+        assert!(json_refinement_check(
+            PATH,
+            "refinement: Counter || Timer <= CounterSpec || Timer"
+        ));
+    }
+
+    #[test]
+    fn CounterConjoinedWithCounterSpecRefinesCounterSpec() {
+        assert!(json_refinement_check(
+            PATH,
+            "refinement: Counter && CounterSpec <= CounterSpec"
+        ));
+    }
+
+    #[test]
+    fn BoundedCounterRefinesCounterSpec() {
+        assert!(json_refinement_check(
+            PATH,
+            "refinement: BoundedCounter <= CounterSpec"
+        ));
+    }
+
+    #[test]
+    fn OverflowingUpdateIsAnError() {
+        match json_run_query(PATH, "consistency: OverflowCounter") {
+            QueryResult::Error(message) => assert!(message.contains("outside its range [0,2]")),
+            _ => panic!("Expected the update outside the range of the int to fail the query"),
+        }
+    }
+
+    #[test]
+    fn OverflowingUpdateFailsRefinementCheckedDirectly() {
+        // Library users check refinements without a query executor
+        let mut comp_loader =
+            JsonProjectLoader::new(PATH.to_string(), crate::tests::TEST_SETTINGS).to_comp_loader();
+        let expression = QueryExpression::VarName("OverflowCounter".to_string());
+        let mut dim = 0;
+        let sys1 = get_system_recipe(&expression, &mut *comp_loader, &mut dim, &mut None);
+        let sys2 = get_system_recipe(&expression, &mut *comp_loader, &mut dim, &mut None);
+        match refine::check_refinement(sys1.compile(dim).unwrap(), sys2.compile(dim).unwrap()) {
+            RefinementResult::Failure(RefinementFailure::UpdateError(message)) => {
+                assert!(message.contains("outside its range [0,2]"))
+            }
+            _ => panic!("Expected the update outside the range of the int to fail the refinement"),
+        }
+    }
+
+    #[test]
+    fn GlobalIntAssignedInOneComponentAndReadInAnotherIsAnError() {
+        match json_run_query(GLOBAL_PATH, "consistency: Incrementer || Watcher") {
+            QueryResult::Consistency(ConsistencyResult::Failure(
+                ConsistencyFailure::NotDisjoint(failure),
+            )) => assert!(failure.reason.contains("global variable n")),
+            _ => panic!("Expected the global int shared by the components to fail the query"),
+        }
+    }
+
+    #[test]
+    fn GlobalIntReadInBothComponentsIsAllowed() {
+        assert!(matches!(
+            json_run_query(GLOBAL_PATH, "consistency: Watcher || Idle"),
+            QueryResult::Consistency(ConsistencyResult::Success)
+        ));
+    }
+
+    #[test]
+    fn CounterSpecRefinesBoundedCounter() {
+        assert!(json_refinement_check(
+            PATH,
+            "refinement: CounterSpec <= BoundedCounter"
+        ));
+    }
+}
@@ -0,0 +1,38 @@
+#[cfg(test)]
+mod test {
+    use crate::tests::refinement::Helper::{
+        json_refinement_check, json_refinement_check_with_options,
+    };
+    use crate::EngineOptions;
+    use test_case::test_case;
+
+    static PATH: &str = "samples/json/EcdarUniversity";
+    static BIG_PATH: &str = "samples/json/BigRefinement";
+
+    #[test_case(PATH, "refinement: Machine <= Machine", true; "Self refinement")]
+    #[test_case(PATH, "refinement: Administration || Researcher || Machine <= Spec", true; "Composition refines spec")]
+    #[test_case(PATH, "refinement: Machine || Adm2 <= Spec // Researcher", false; "Quotient failure")]
+    #[test_case(PATH, "refinement: Researcher <= Spec // Administration // Machine", true; "Nested quotient")]
+    #[test_case(PATH, "refinement: Machine3 <= Spec", false; "Machine3 does not refine Spec")]
+    #[test_case(PATH, "refinement: Spec <= Researcher", false; "Spec does not refine Researcher")]
+    #[test_case(BIG_PATH, "refinement: Ref1 <= Ref1", true; "Big self refinement")]
+    #[test_case(BIG_PATH, "refinement: Comp1 <= Ref1", false; "Big refinement failure")]
+    fn parallel_verdict_matches_sequential(path: &str, query: &str, expected: bool) {
+        assert_eq!(json_refinement_check(path, query), expected);
+        for threads in [2, 4, 8] {
+            assert_eq!(
+                json_refinement_check_with_options(
+                    path,
+                    query,
+                    EngineOptions {
+                        refinement_threads: threads,
+                        ..crate::DEFAULT_OPTIONS
+                    }
+                ),
+                expected,
+                "Verdict differs with {} threads",
+                threads
+            );
+        }
+    }
+}
@@ -0,0 +1,46 @@
+#[cfg(test)]
+mod test {
+    use crate::tests::refinement::Helper::json_run_parsed_query;
+    use crate::DataReader::parse_queries;
+    use crate::System::executable_query::QueryResult;
+    use crate::System::refine::RefinementResult;
+
+    static PATH: &str = "samples/json/EcdarUniversity";
+
+    fn run_saving_relation(query: &str) -> QueryResult {
+        let mut query = parse_queries::parse_to_query(query).remove(0);
+        query.save_relation = true;
+        json_run_parsed_query(PATH, &query)
+    }
+
+    #[test]
+    fn SelfRefinementRelatesEqualLocations() {
+        match run_saving_relation("refinement: Machine <= Machine") {
+            QueryResult::Refinement(RefinementResult::Success(Some(relation))) => {
+                assert!(!relation.pairs.is_empty());
+                for pair in &relation.pairs {
+                    assert_eq!(pair.locations1.0.to_string(), pair.locations2.0.to_string());
+                    assert!(!pair.federation.is_empty());
+                }
+            }
+            _ => panic!("Expected a refinement relation"),
+        }
+    }
+
+    #[test]
+    fn RelationIsOnlyReturnedWhenRequested() {
+        let query = parse_queries::parse_to_query("refinement: Machine <= Machine").remove(0);
+        assert!(matches!(
+            json_run_parsed_query(PATH, &query),
+            QueryResult::Refinement(RefinementResult::Success(None))
+        ));
+    }
+
+    #[test]
+    fn FailedRefinementHasNoRelation() {
+        assert!(matches!(
+            run_saving_relation("refinement: Machine <= Spec"),
+            QueryResult::Refinement(RefinementResult::Failure(_))
+        ));
+    }
+}
@@ -0,0 +1,56 @@
+#[cfg(test)]
+mod test {
+    use crate::tests::refinement::Helper::json_refinement_check_with_options;
+    use crate::DataTypes::SearchOrder;
+    use crate::EngineOptions;
+    use test_case::test_case;
+
+    static PATH: &str = "samples/json/EcdarUniversity";
+    static BIG_PATH: &str = "samples/json/BigRefinement";
+
+    #[test_case(PATH, "refinement: Administration || Researcher || Machine <= Spec", true; "Composition refines spec")]
+    #[test_case(PATH, "refinement: Researcher <= Spec // Administration // Machine", true; "Nested quotient")]
+    #[test_case(PATH, "refinement: Machine || Adm2 <= Spec // Researcher", false; "Quotient failure")]
+    #[test_case(PATH, "refinement: Machine3 <= Spec", false; "Machine3 does not refine Spec")]
+    #[test_case(BIG_PATH, "refinement: Ref1 <= Ref1", true; "Big self refinement")]
+    #[test_case(BIG_PATH, "refinement: Comp1 <= Ref1", false; "Big refinement failure")]
+    fn verdict_is_independent_of_search_order(path: &str, query: &str, expected: bool) {
+        for order in [
+            SearchOrder::BreadthFirst,
+            SearchOrder::DepthFirst,
+            SearchOrder::RandomDepthFirst,
+            SearchOrder::ClosestToFailure,
+        ] {
+            for threads in [1, 4] {
+                assert_eq!(
+                    json_refinement_check_with_options(
+                        path,
+                        query,
+                        EngineOptions {
+                            refinement_threads: threads,
+                            search_order: Some(order),
+                            ..crate::DEFAULT_OPTIONS
+                        }
+                    ),
+                    expected,
+                    "Verdict differs with {} order and {} threads",
+                    order,
+                    threads
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn search_order_argument_round_trips() {
+        for order in [
+            SearchOrder::BreadthFirst,
+            SearchOrder::DepthFirst,
+            SearchOrder::RandomDepthFirst,
+            SearchOrder::ClosestToFailure,
+        ] {
+            assert_eq!(order.to_string().parse::<SearchOrder>(), Ok(order));
+        }
+        assert!("sideways".parse::<SearchOrder>().is_err());
+    }
+}
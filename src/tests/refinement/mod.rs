@@ -3,6 +3,11 @@ mod Big_Refinement;
 mod Conjunction_refinement;
 pub mod Helper;
 mod Refinement_delay_add;
+mod Refinement_ignored_actions;
+mod Refinement_int_variables;
+mod Refinement_parallel;
+mod Refinement_relation;
+mod Refinement_search_order;
 mod Refinement_university;
 mod Refinement_unspec;
 pub mod xml;
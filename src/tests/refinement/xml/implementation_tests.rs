@@ -0,0 +1,55 @@
+#[cfg(test)]
+mod test {
+    use crate::tests::refinement::Helper::xml_run_query;
+    use crate::System::executable_query::QueryResult;
+    use crate::System::local_consistency::{ImplementationFailure, ImplementationResult};
+    use test_case::test_case;
+
+    static PATH: &str = "samples/xml/ImplTests.xml";
+
+    fn run_implementation_query(component: &str) -> ImplementationResult {
+        match xml_run_query(PATH, &format!("implementation: {}", component)) {
+            QueryResult::Implementation(result) => result,
+            _ => panic!("Not implementation query"),
+        }
+    }
+
+    #[test_case("G1"; "G1")]
+    #[test_case("G2"; "G2")]
+    #[test_case("G6"; "G6")]
+    #[test_case("G8"; "G8")]
+    #[test_case("G13"; "G13")]
+    #[test_case("G15"; "G15")]
+    #[test_case("G16"; "G16")]
+    #[test_case("G17"; "G17")]
+    fn implementation_success(component: &str) {
+        assert!(matches!(
+            run_implementation_query(component),
+            ImplementationResult::Success
+        ));
+    }
+
+    #[test_case("G3"; "G3")]
+    #[test_case("G4"; "G4")]
+    #[test_case("G5"; "G5")]
+    #[test_case("G7"; "G7")]
+    #[test_case("G9"; "G9")]
+    #[test_case("G10"; "G10")]
+    #[test_case("G11"; "G11")]
+    #[test_case("G12"; "G12")]
+    #[test_case("G14"; "G14")]
+    fn implementation_not_consistent(component: &str) {
+        assert!(matches!(
+            run_implementation_query(component),
+            ImplementationResult::Failure(ImplementationFailure::NotConsistent(_))
+        ));
+    }
+
+    #[test]
+    fn output_blocked_when_time_stops_is_not_urgent() {
+        assert!(matches!(
+            run_implementation_query("G19"),
+            ImplementationResult::Failure(ImplementationFailure::NotOutputUrgent(..))
+        ));
+    }
+}
@@ -2,4 +2,5 @@ mod conjunction_tests;
 mod consistency_tests;
 mod delay_refinement;
 mod determinism_tests;
+mod implementation_tests;
 mod misc_tests;
@@ -1,8 +1,11 @@
 #[cfg(test)]
 mod samples {
-    use crate::DataReader::component_loader::JsonProjectLoader;
+    use crate::DataReader::component_loader::{JsonProjectLoader, XmlProjectLoader};
+    use crate::DataReader::xml_parser::parse_query_text;
 
     static CONJUNCTION_SAMPLE: &str = "samples/json/Conjunction";
+    static CONS_TESTS_SAMPLE: &str = "samples/xml/ConsTests.xml";
+    static LOOP_SAMPLE: &str = "samples/xml/loop.xml";
 
     #[test]
     fn test_locations_T1() {
@@ -45,4 +48,39 @@ mod samples {
             assert_eq!(t.name, format!("Test{}", i));
         }
     }
+
+    #[test]
+    fn test_xml_queries_from_query_file() {
+        let project_loader =
+            XmlProjectLoader::new(CONS_TESTS_SAMPLE.to_string(), crate::tests::TEST_SETTINGS);
+        let queries = project_loader.get_queries();
+
+        assert_eq!(queries.len(), 23);
+        assert_eq!(
+            queries[0].query.as_ref().unwrap().pretty_string(),
+            "consistency: G1"
+        );
+        assert_eq!(queries[0].comment, "Pass");
+        assert_eq!(queries[2].comment, "Fail");
+    }
+
+    #[test]
+    fn test_xml_empty_embedded_query_is_skipped() {
+        let project_loader =
+            XmlProjectLoader::new(LOOP_SAMPLE.to_string(), crate::tests::TEST_SETTINGS);
+
+        assert!(project_loader.get_queries().is_empty());
+    }
+
+    #[test]
+    fn test_query_text_comments() {
+        let queries = parse_query_text(
+            "//header\n\n/* first\nline */\nconsistency: A\n\nrefinement: A <= B; determinism: B\n",
+        );
+
+        assert_eq!(queries.len(), 3);
+        assert_eq!(queries[0].comment, "first\nline");
+        assert_eq!(queries[1].comment, "");
+        assert_eq!(queries[2].comment, "");
+    }
 }
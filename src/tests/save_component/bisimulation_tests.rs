@@ -0,0 +1,26 @@
+#[cfg(test)]
+mod test {
+    use crate::tests::save_component::save_comp_helper::util::json_minimized_component_refines_base_self;
+
+    static ECDAR_UNI: &str = "samples/json/EcdarUniversity";
+
+    #[test]
+    fn Adm2MinimizedRefinesSelf() {
+        json_minimized_component_refines_base_self(ECDAR_UNI, "Adm2");
+    }
+
+    #[test]
+    fn Adm2MachineMinimizedRefinesSelf() {
+        json_minimized_component_refines_base_self(ECDAR_UNI, "Adm2 || Machine");
+    }
+
+    #[test]
+    fn AdministrationResearcherMinimizedRefinesSelf() {
+        json_minimized_component_refines_base_self(ECDAR_UNI, "Administration || Researcher");
+    }
+
+    #[test]
+    fn HalfAdm1HalfAdm2MinimizedRefinesSelf() {
+        json_minimized_component_refines_base_self(ECDAR_UNI, "HalfAdm1 && HalfAdm2");
+    }
+}
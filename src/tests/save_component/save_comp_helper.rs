@@ -2,7 +2,9 @@
 pub mod util {
     use crate::DataReader::component_loader::JsonProjectLoader;
     use crate::DataReader::parse_queries;
+    use crate::ModelObjects::component::Component;
     use crate::ModelObjects::representations::QueryExpression;
+    use crate::System::bisimulation;
     use crate::System::extract_system_rep;
     use crate::System::extract_system_rep::SystemRecipe;
     use crate::System::refine;
@@ -13,6 +15,23 @@ pub mod util {
     use edbm::util::constraints::ClockIndex;
 
     pub fn json_reconstructed_component_refines_base_self(input_path: &str, system: &str) {
+        reconstructed_component_refines_base_self(input_path, system, |comp| comp);
+    }
+
+    /// Like [json_reconstructed_component_refines_base_self], but the saved component is minimized first
+    pub fn json_minimized_component_refines_base_self(input_path: &str, system: &str) {
+        reconstructed_component_refines_base_self(input_path, system, |comp| {
+            let minimized = bisimulation::minimize(&comp);
+            assert!(minimized.get_locations().len() <= comp.get_locations().len());
+            minimized
+        });
+    }
+
+    fn reconstructed_component_refines_base_self(
+        input_path: &str,
+        system: &str,
+        reconstruct: impl Fn(Component) -> Component,
+    ) {
         let project_loader =
             JsonProjectLoader::new(String::from(input_path), crate::tests::TEST_SETTINGS);
 
@@ -48,7 +67,10 @@ pub mod util {
         if new_comp.is_err() {
             return;
         }
-        let new_comp = combine_components(&new_comp.unwrap(), PruningStrategy::NoPruning);
+        let new_comp = reconstruct(combine_components(
+            &new_comp.unwrap(),
+            PruningStrategy::NoPruning,
+        ));
 
         let new_comp = SystemRecipe::Component(Box::new(new_comp))
             .compile(dim)
@@ -64,11 +86,11 @@ pub mod util {
         if helper(&base_precheck) && helper(&new_precheck) {
             assert!(matches!(
                 refine::check_refinement(new_comp.clone(), base_system.clone()),
-                RefinementResult::Success
+                RefinementResult::Success(_)
             ));
             assert!(matches!(
                 refine::check_refinement(base_system.clone(), new_comp.clone()),
-                RefinementResult::Success
+                RefinementResult::Success(_)
             ));
         }
     }
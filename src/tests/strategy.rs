@@ -0,0 +1,123 @@
+#[cfg(test)]
+mod test {
+    use crate::tests::refinement::Helper::json_run_query;
+    use crate::tests::Simulation::test_data::create_EcdarUniversity_Machine_system;
+    use crate::DataReader::json_writer::strategy_to_json;
+    use crate::Simulation::transition_decision_point::TransitionDecisionPoint;
+    use crate::System::executable_query::QueryResult;
+    use crate::System::local_consistency::synthesize_consistency_strategy;
+    use crate::System::strategy::{ConsistencyStrategy, StrategyMove, StrategyResult};
+    use crate::TransitionSystems::TransitionSystemPtr;
+
+    fn moves_in<'a>(strategy: &'a ConsistencyStrategy, location: &str) -> Vec<&'a StrategyMove> {
+        strategy
+            .moves
+            .iter()
+            .filter(|((id, _), _)| id.to_string() == location)
+            .flat_map(|(_, entries)| entries.iter().map(|entry| &entry.strategy_move))
+            .collect()
+    }
+
+    fn machine_strategy(system: &TransitionSystemPtr) -> ConsistencyStrategy {
+        synthesize_consistency_strategy(&**system)
+            .expect("A consistent system should have a strategy")
+    }
+
+    #[test]
+    fn machine_strategy_outputs_before_invariant_runs_out() {
+        let system = create_EcdarUniversity_Machine_system();
+        let strategy = machine_strategy(&system);
+
+        // L4 has the invariant y<=6, so an output must be taken before it runs out
+        assert!(moves_in(&strategy, "L4")
+            .iter()
+            .any(|strategy_move| matches!(
+                strategy_move,
+                StrategyMove::Output { action, .. } if action == "cof" || action == "tea"
+            )));
+        // L5 has no invariant, so it can always delay
+        let l5_moves = moves_in(&strategy, "L5");
+        assert!(!l5_moves.is_empty());
+        assert!(l5_moves
+            .iter()
+            .all(|strategy_move| **strategy_move == StrategyMove::Delay));
+    }
+
+    #[test]
+    fn machine_strategy_delays_in_initial_state() {
+        let system = create_EcdarUniversity_Machine_system();
+        let strategy = machine_strategy(&system);
+        let decision = TransitionDecisionPoint::initial(&system).unwrap();
+
+        assert!(decision.strategy_decision(&strategy).is_none());
+    }
+
+    #[test]
+    fn machine_strategy_to_json() {
+        let system = create_EcdarUniversity_Machine_system();
+        let strategy = machine_strategy(&system);
+
+        let json: serde_json::Value = serde_json::from_str(&strategy_to_json(&strategy)).unwrap();
+        let entries = json.as_array().unwrap();
+
+        assert!(entries
+            .iter()
+            .any(|entry| entry["location"] == "L5" && entry["action"] == "delay"));
+    }
+
+    #[test]
+    fn machine_strategy_delays_only_until_the_output() {
+        let system = create_EcdarUniversity_Machine_system();
+        let strategy = machine_strategy(&system);
+
+        let entries: Vec<_> = strategy
+            .moves
+            .iter()
+            .filter(|((id, _), _)| id.to_string() == "L4")
+            .flat_map(|(_, entries)| entries.iter())
+            .collect();
+        for delay in entries
+            .iter()
+            .filter(|entry| entry.strategy_move == StrategyMove::Delay)
+        {
+            for output in entries
+                .iter()
+                .filter(|entry| entry.strategy_move != StrategyMove::Delay)
+            {
+                assert!(delay
+                    .federation
+                    .clone()
+                    .intersection(&output.federation)
+                    .is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn machine_strategy_is_not_followed_from_initial_state() {
+        let system = create_EcdarUniversity_Machine_system();
+        let strategy = machine_strategy(&system);
+        let decision = TransitionDecisionPoint::initial(&system).unwrap();
+
+        // L5 has no invariant, so the strategy delays before taking any output
+        assert!(decision.follow_strategy(&system, &strategy).is_empty());
+    }
+
+    #[test]
+    fn strategy_query_returns_strategy() {
+        match json_run_query("samples/json/EcdarUniversity", "strategy: Machine") {
+            QueryResult::Strategy(StrategyResult::Success(strategy, _)) => {
+                assert!(!moves_in(&strategy, "L4").is_empty())
+            }
+            _ => panic!("Expected a strategy for the consistent Machine"),
+        }
+    }
+
+    #[test]
+    fn strategy_query_fails_for_inconsistent_component() {
+        assert!(matches!(
+            json_run_query("samples/json/ConsistencyTest", "strategy: notConsistent"),
+            QueryResult::Strategy(StrategyResult::Failure(_))
+        ));
+    }
+}